@@ -1,30 +1,29 @@
-static API_VERSION: &'static str = "v1.37";
+use crate::api::request::{Method, Request};
 
-/// Gives a formatted API request which should be writtern
-/// to the socket to docker.
-pub fn get_formatted_api_request(
+/// Highest version of the Engine API the client speaks, negotiation never
+/// picks a more recent one.
+pub const MAX_API_VERSION: &str = "1.47";
+
+/// Oldest version of the Engine API the client can fall back to when
+/// negotiating with an older daemon.
+pub const MIN_API_VERSION: &str = "1.24";
+
+/// Builds the request for `DockerApiClient::get_response_from_api`, the body
+/// of GET and HEAD requests is the query string appended as is to the
+/// endpoint while the body of other requests is sent as JSON.
+pub fn get_api_request(
     api_endpoint: &str,
     method: &str,
     body: &str,
-) -> Option<String> {
-    if method == "GET" || method == "get" {
-        return Some(format!(
-            "GET {endpoint}{body} HTTP/1.1\r\nHost: {version}\r\n\r\n",
-            endpoint = api_endpoint,
-            body = body,
-            version = API_VERSION
-        ));
+) -> Option<Request> {
+    let method = Method::parse(method)?;
+    match method {
+        Method::GET | Method::HEAD => {
+            Some(Request::new(method, format!("{}{}", api_endpoint, body)))
+        }
+        _ if body.is_empty() => Some(Request::new(method, api_endpoint)),
+        _ => Some(
+            Request::new(method, api_endpoint).body(body, "application/json"),
+        ),
     }
-
-    if method == "POST" || method == "post" {
-        return Some(format!(
-            "POST {endpoint} HTTP/1.1\r\nHost: {version}\r\nContent-Length: {length}\r\nContent-Type: application/json\r\n\r\n{external_body}\r\n\r\n",
-            endpoint = api_endpoint,
-            version = API_VERSION,
-            length = body.len(),
-            external_body = body
-        ));
-    }
-
-    None
 }
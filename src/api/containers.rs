@@ -1,79 +1,508 @@
 #![allow(non_snake_case)]
 use std::collections::HashMap;
 
-use api::DockerApiClient;
+use crate::api::request::Request;
+use crate::api::DockerApiClient;
 
 use serde_json;
 
-use errors::DockerApiError;
+use crate::errors::DockerApiError;
+use crate::logs::{self, Logs};
+use crate::streaming::ResponseStream;
+use crate::utils::api::null_as_default;
 
-#[derive(Serialize, Deserialize, Debug)]
+/// A container, as listed by the daemon.
+///
+/// Fields missing from the answers of older or newer daemons are left
+/// empty, and the ones not listed here are kept in `extra`.
+///
+/// * Created: Creation time, as a UNIX timestamp.
+/// * SizeRw, SizeRootFs: Sizes of the container, only sent when asked for.
+/// * HostConfig: Only the `NetworkMode` is sent.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
 pub struct Container {
     pub Id: String,
+    #[serde(deserialize_with = "null_as_default")]
     pub Names: Vec<String>,
     pub Image: String,
     pub ImageID: String,
     pub Command: String,
+    pub Created: i64,
     pub State: String,
     pub Status: String,
+    #[serde(deserialize_with = "null_as_default")]
     pub Ports: Vec<Port>,
     pub Labels: Option<HashMap<String, String>>,
-
-    #[serde(default)]
     pub SizeRw: Option<i64>,
-
-    #[serde(default)]
-    pub SizeRootFs: u64,
+    pub SizeRootFs: Option<i64>,
     pub HostConfig: HostConfig,
-    pub Mounts: Vec<Mounts>,
+    pub NetworkSettings: Option<SummaryNetworkSettings>,
+    #[serde(deserialize_with = "null_as_default")]
+    pub Mounts: Vec<MountPoint>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+/// A port of a listed container. Ports which are exposed but not published
+/// have neither `IP` nor `PublicPort`.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
 pub struct Port {
+    pub IP: Option<String>,
     pub PrivatePort: u32,
-    pub PublicPort: u32,
+    pub PublicPort: Option<u32>,
     pub Type: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+/// Networks a listed container is connected to, keyed by name.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct SummaryNetworkSettings {
+    pub Networks: Option<HashMap<String, EndpointSettings>>,
+}
+
+/// Structure for implementing Container Config, the body of a create
+/// container request. It is also the `Config` of inspected containers.
+/// Derives Default for being able to get started even with minimal
+/// config, the fields left to `None` are not sent to the daemon.
+///
+/// * ExposedPorts: Ports to expose, like `80/tcp`, with `EmptyObject` values.
+/// * Volumes: Paths of the anonymous volumes, with `EmptyObject` values.
+/// * StopTimeout: Seconds to wait for the container to stop before killing
+///   it.
+/// * HostConfig: Configuration depending on the host, like binds, port
+///   bindings or resource limits.
+/// * NetworkingConfig: Networks to connect the container to.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ContainerConfig {
+    pub Image: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Cmd: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Hostname: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Domainname: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub User: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub AttachStdin: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub AttachStdout: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub AttachStderr: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ExposedPorts: Option<HashMap<String, EmptyObject>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Tty: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub OpenStdin: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub StdinOnce: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Env: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Healthcheck: Option<HealthConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ArgsEscaped: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Entrypoint: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Volumes: Option<HashMap<String, EmptyObject>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub WorkingDir: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub NetworkDisabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub MacAddress: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub OnBuild: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Labels: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub StopSignal: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub StopTimeout: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Shell: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub HostConfig: Option<HostConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub NetworkingConfig: Option<NetworkingConfig>,
+}
+
+/// An empty JSON object, the value of the sets of `ExposedPorts` and
+/// `Volumes`.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct EmptyObject {}
+
+/// How to check that the container is healthy. Durations are in
+/// nanoseconds, and 0 inherits the value of the image.
+///
+/// * Test: `["NONE"]` disables the check of the image, `["CMD", args...]`
+///   runs a command and `["CMD-SHELL", command]` runs it with the shell.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct HealthConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Test: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Interval: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Timeout: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Retries: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub StartPeriod: Option<i64>,
+}
+
+/// Container configuration which depends on the host, like binds, port
+/// bindings, restart policy, resource limits or capabilities.
+///
+/// * Binds: Volume bindings, like `/host:/container:ro` or `volume:/data`.
+/// * PortBindings: Host ports the container ports are bound to, keyed by
+///   container port like `80/tcp`.
+/// * NetworkMode: `bridge`, `host`, `none`, `container:<name|id>` or the
+///   name of a network.
+/// * Resources: Resource limits, flattened in the host config.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct HostConfig {
-    pub NetworkMode: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Binds: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ContainerIDFile: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub LogConfig: Option<LogConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub NetworkMode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub PortBindings: Option<HashMap<String, Vec<PortBinding>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub RestartPolicy: Option<RestartPolicy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub AutoRemove: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub VolumeDriver: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub VolumesFrom: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Mounts: Option<Vec<Mount>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub CapAdd: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub CapDrop: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub CgroupnsMode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Dns: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub DnsOptions: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub DnsSearch: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ExtraHosts: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub GroupAdd: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub IpcMode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Cgroup: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Links: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub OomScoreAdj: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub PidMode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Privileged: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub PublishAllPorts: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ReadonlyRootfs: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub SecurityOpt: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub StorageOpt: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Tmpfs: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub UTSMode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub UsernsMode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ShmSize: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Sysctls: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Runtime: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Isolation: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Init: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub MaskedPaths: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ReadonlyPaths: Option<Vec<String>>,
+    #[serde(flatten)]
+    pub Resources: Resources,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct Mounts {
-    #[serde(default)]
-    pub Name: Option<String>,
-    pub Source: String,
-    pub Destination: String,
-    #[serde(default)]
-    pub Driver: String,
-    pub Mode: String,
-    pub RW: bool,
-    pub Propagation: String,
+/// Resource limits of a container. Memory sizes are in bytes, and
+/// `NanoCpus` is in units of 10<sup>-9</sup> CPUs.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Resources {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub CpuShares: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Memory: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub CgroupParent: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub BlkioWeight: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub BlkioWeightDevice: Option<Vec<WeightDevice>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub BlkioDeviceReadBps: Option<Vec<ThrottleDevice>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub BlkioDeviceWriteBps: Option<Vec<ThrottleDevice>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub BlkioDeviceReadIOps: Option<Vec<ThrottleDevice>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub BlkioDeviceWriteIOps: Option<Vec<ThrottleDevice>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub CpuPeriod: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub CpuQuota: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub CpuRealtimePeriod: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub CpuRealtimeRuntime: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub CpusetCpus: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub CpusetMems: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Devices: Option<Vec<DeviceMapping>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub DeviceCgroupRules: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub DeviceRequests: Option<Vec<DeviceRequest>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub KernelMemoryTCP: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub MemoryReservation: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub MemorySwap: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub MemorySwappiness: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub NanoCpus: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub OomKillDisable: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub PidsLimit: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Ulimits: Option<Vec<Ulimit>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub CpuCount: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub CpuPercent: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub IOMaximumIOps: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub IOMaximumBandwidth: Option<i64>,
 }
 
-/// Structure for implementing Container Config
-/// Derives Default fot being able to get started even with minimal
-/// config.
-#[derive(Serialize, Deserialize, Debug, Default)]
-pub struct ContainerConfig {
-    pub Image: String,
-    pub Cmd: Vec<String>,
-
-    pub Hostname: String,
-    pub Domainname: String,
-    pub User: String,
-    pub AttachStdin: bool,
-    pub AttachStdout: bool,
-    pub AttachStderr: bool,
-    pub Tty: bool,
-    pub OpenStdin: bool,
-    pub StdinOnce: bool,
-    pub Env: Vec<String>,
-    pub Entrypoint: Option<String>,
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct WeightDevice {
+    pub Path: String,
+    pub Weight: u16,
+}
+
+/// Rate limit of a device, in bytes or IO operations per second.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ThrottleDevice {
+    pub Path: String,
+    pub Rate: u64,
+}
+
+/// A device of the host made available in the container.
+///
+/// * CgroupPermissions: Combination of `r`, `w` and `m`, like `rwm`.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct DeviceMapping {
+    pub PathOnHost: String,
+    pub PathInContainer: String,
+    pub CgroupPermissions: String,
+}
+
+/// A request for devices handled by a driver, like GPUs.
+///
+/// * Count: Number of devices, -1 for all of them.
+/// * Capabilities: Lists of capabilities the driver must support, like
+///   `[["gpu"]]`.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct DeviceRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Driver: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Count: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub DeviceIDs: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Capabilities: Option<Vec<Vec<String>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Options: Option<HashMap<String, String>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Ulimit {
+    pub Name: String,
+    pub Soft: i64,
+    pub Hard: i64,
+}
+
+/// Logging driver of the container, like `json-file`, along with its
+/// options.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct LogConfig {
+    pub Type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Config: Option<HashMap<String, String>>,
+}
+
+/// A host port a container port is bound to. A missing `HostPort` picks a
+/// free port, and a missing `HostIp` binds every interface.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct PortBinding {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub HostIp: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub HostPort: Option<String>,
+}
+
+/// What to do when the container exits.
+///
+/// * Name: Empty, `no`, `always`, `unless-stopped` or `on-failure`.
+/// * MaximumRetryCount: Number of restarts before giving up, for
+///   `on-failure` only.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct RestartPolicy {
+    pub Name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub MaximumRetryCount: Option<i64>,
+}
+
+/// A mount of the container.
+///
+/// * Type: `bind`, `volume`, `tmpfs` or `npipe`.
+/// * Source: Path on the host for binds, name of the volume for volumes.
+/// * Target: Path in the container.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Mount {
+    pub Target: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Source: Option<String>,
+    pub Type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ReadOnly: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Consistency: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub BindOptions: Option<BindOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub VolumeOptions: Option<VolumeOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub TmpfsOptions: Option<TmpfsOptions>,
+}
+
+/// * Propagation: `private`, `rprivate`, `shared`, `rshared`, `slave` or
+///   `rslave`.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct BindOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Propagation: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub NonRecursive: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct VolumeOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub NoCopy: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub Labels: Option<HashMap<String, String>>,
-    pub WorkingDir: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub DriverConfig: Option<VolumeDriverConfig>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct VolumeDriverConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Options: Option<HashMap<String, String>>,
+}
+
+/// * SizeBytes: Size of the tmpfs mount, unlimited when missing.
+/// * Mode: Permissions of the tmpfs mount, like `0o1777`.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct TmpfsOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub SizeBytes: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Mode: Option<u32>,
+}
+
+/// Networks to connect the container to when creating it, keyed by
+/// network name.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct NetworkingConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub EndpointsConfig: Option<HashMap<String, EndpointSettings>>,
+}
+
+/// Settings of the connection of a container to a network. Only the
+/// `IPAMConfig`, `Links`, `Aliases` and `DriverOpts` are used when
+/// connecting, the rest is filled by the daemon.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct EndpointSettings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub IPAMConfig: Option<EndpointIPAMConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Links: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Aliases: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub NetworkID: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub EndpointID: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Gateway: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub IPAddress: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub IPPrefixLen: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub IPv6Gateway: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub GlobalIPv6Address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub GlobalIPv6PrefixLen: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub MacAddress: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub DriverOpts: Option<HashMap<String, String>>,
+}
+
+/// Static addresses of a container on a network.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct EndpointIPAMConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub IPv4Address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub IPv6Address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub LinkLocalIPs: Option<Vec<String>>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -81,7 +510,13 @@ pub struct CreateContainerResponse {
     pub Id: String,
 }
 
-#[derive(Serialize, Deserialize, Debug, Default)]
+/// State of an inspected container.
+///
+/// * Status: `created`, `running`, `paused`, `restarting`, `removing`,
+///   `exited` or `dead`.
+/// * Health: Result of the healthchecks, for containers which have one.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
 pub struct ContainerState {
     pub Status: String,
     pub Running: bool,
@@ -90,14 +525,108 @@ pub struct ContainerState {
     pub OOMKilled: bool,
     pub Dead: bool,
     pub Pid: u64,
-    pub ExitCode: u64,
+    pub ExitCode: i64,
     pub Error: String,
     pub StartedAt: String,
     pub FinishedAt: String,
+    pub Health: Option<Health>,
 }
 
-/// * To use HostConfig use serde_json
-#[derive(Serialize, Deserialize, Debug, Default)]
+/// Health of a container, along with the last healthcheck results.
+///
+/// * Status: `none`, `starting`, `healthy` or `unhealthy`.
+/// * FailingStreak: Number of consecutive failed healthchecks.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct Health {
+    pub Status: String,
+    pub FailingStreak: u64,
+    pub Log: Option<Vec<HealthcheckResult>>,
+}
+
+/// A run of the healthcheck, with the output it printed.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct HealthcheckResult {
+    pub Start: String,
+    pub End: String,
+    pub ExitCode: i64,
+    pub Output: String,
+}
+
+/// A mount point of an inspected container.
+///
+/// * Type: `bind`, `volume`, `tmpfs` or `npipe`.
+/// * Name: Name of the volume, empty for the other types.
+/// * Source: Path of the mount on the host.
+/// * Destination: Path of the mount in the container.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct MountPoint {
+    pub Type: String,
+    pub Name: String,
+    pub Source: String,
+    pub Destination: String,
+    pub Driver: String,
+    pub Mode: String,
+    pub RW: bool,
+    pub Propagation: String,
+}
+
+/// Storage driver of a container, with driver specific data like the
+/// directories of an overlay.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct GraphDriverData {
+    pub Name: String,
+    pub Data: Option<HashMap<String, String>>,
+}
+
+/// Network settings of an inspected container.
+///
+/// * Ports: Host ports bound to each exposed port of the container, keyed
+///   by container port like `80/tcp`. Ports which are exposed but not
+///   published are `None`.
+/// * Networks: Networks the container is connected to, keyed by name.
+/// * IPAddress, Gateway, ...: Settings on the default bridge network, which
+///   are also found in `Networks`.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct NetworkSettings {
+    pub Bridge: String,
+    pub SandboxID: String,
+    pub SandboxKey: String,
+    pub HairpinMode: bool,
+    pub LinkLocalIPv6Address: String,
+    pub LinkLocalIPv6PrefixLen: i64,
+    pub Ports: Option<HashMap<String, Option<Vec<PortBinding>>>>,
+    pub SecondaryIPAddresses: Option<Vec<Address>>,
+    pub SecondaryIPv6Addresses: Option<Vec<Address>>,
+    pub EndpointID: String,
+    pub Gateway: String,
+    pub GlobalIPv6Address: String,
+    pub GlobalIPv6PrefixLen: i64,
+    pub IPAddress: String,
+    pub IPPrefixLen: i64,
+    pub IPv6Gateway: String,
+    pub MacAddress: String,
+    pub Networks: Option<HashMap<String, EndpointSettings>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct Address {
+    pub Addr: String,
+    pub PrefixLen: i64,
+}
+
+/// Low level information about a container, as returned by inspect.
+/// Fields missing from the answers of older daemons are left empty.
+///
+/// * ExecIDs: IDs of the exec instances running in the container.
+/// * SizeRw, SizeRootFs: Sizes of the container, only sent when asked for.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
 pub struct ContainerDetails {
     pub Id: String,
     pub Created: String,
@@ -116,9 +645,210 @@ pub struct ContainerDetails {
     pub MountLabel: String,
     pub ProcessLabel: String,
     pub AppArmorProfile: String,
-    pub ExecIDs: Option<String>,
-    pub HostConfig: serde_json::Value,
+    pub ExecIDs: Option<Vec<String>>,
+    pub HostConfig: HostConfig,
+    pub GraphDriver: GraphDriverData,
+    pub SizeRw: Option<i64>,
+    pub SizeRootFs: Option<i64>,
+    pub Mounts: Vec<MountPoint>,
     pub Config: ContainerConfig,
+    pub NetworkSettings: NetworkSettings,
+}
+
+/// Options of `Containers::remove_container`, all of them are off by
+/// default.
+///
+/// * force: Kills the container first if it is running.
+/// * volumes: Removes the anonymous volumes of the container along with it.
+/// * link: Removes the link named after the container, rather than the
+///   container itself.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RemoveContainerOptions {
+    pub force: bool,
+    pub volumes: bool,
+    pub link: bool,
+}
+
+impl RemoveContainerOptions {
+    /// Sets whether a running container is killed before being removed.
+    pub fn force(mut self, force: bool) -> RemoveContainerOptions {
+        self.force = force;
+        self
+    }
+
+    /// Sets whether the anonymous volumes are removed too.
+    pub fn volumes(mut self, volumes: bool) -> RemoveContainerOptions {
+        self.volumes = volumes;
+        self
+    }
+
+    /// Sets whether the link named after the container is removed instead.
+    pub fn link(mut self, link: bool) -> RemoveContainerOptions {
+        self.link = link;
+        self
+    }
+
+    pub(crate) fn request(&self, id: &str) -> Request {
+        Request::delete(format!("/containers/{}", id))
+            .query("force", self.force)
+            .query("v", self.volumes)
+            .query("link", self.link)
+    }
+}
+
+/// Options of `Containers::get_container_logs`, both streams are read by
+/// default.
+///
+/// * stdout, stderr: Streams to read the logs of.
+/// * since, until: Only the logs written from `since` and before `until`,
+///   as UNIX timestamps.
+/// * tail: Only the last lines of the logs, all of them when `None`.
+/// * timestamps: Starts each line with the time it was written at, in RFC
+///   3339 format with nanoseconds.
+/// * follow: Keeps reading the logs as they are written, until the
+///   container stops.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LogsOptions {
+    pub stdout: bool,
+    pub stderr: bool,
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+    pub tail: Option<u64>,
+    pub timestamps: bool,
+    pub follow: bool,
+}
+
+impl Default for LogsOptions {
+    fn default() -> LogsOptions {
+        LogsOptions {
+            stdout: true,
+            stderr: true,
+            since: None,
+            until: None,
+            tail: None,
+            timestamps: false,
+            follow: false,
+        }
+    }
+}
+
+impl LogsOptions {
+    /// Sets whether the logs of stdout are read.
+    pub fn stdout(mut self, stdout: bool) -> LogsOptions {
+        self.stdout = stdout;
+        self
+    }
+
+    /// Sets whether the logs of stderr are read.
+    pub fn stderr(mut self, stderr: bool) -> LogsOptions {
+        self.stderr = stderr;
+        self
+    }
+
+    /// Sets the UNIX timestamp from which logs are read.
+    pub fn since(mut self, since: Option<i64>) -> LogsOptions {
+        self.since = since;
+        self
+    }
+
+    /// Sets the UNIX timestamp until which logs are read.
+    pub fn until(mut self, until: Option<i64>) -> LogsOptions {
+        self.until = until;
+        self
+    }
+
+    /// Sets the number of lines read from the end of the logs.
+    pub fn tail(mut self, tail: Option<u64>) -> LogsOptions {
+        self.tail = tail;
+        self
+    }
+
+    /// Sets whether each line starts with the time it was written at.
+    pub fn timestamps(mut self, timestamps: bool) -> LogsOptions {
+        self.timestamps = timestamps;
+        self
+    }
+
+    /// Sets whether the logs keep being read as they are written.
+    pub fn follow(mut self, follow: bool) -> LogsOptions {
+        self.follow = follow;
+        self
+    }
+
+    pub(crate) fn request(&self, id: &str) -> Request {
+        let tail = match self.tail {
+            Some(tail) => tail.to_string(),
+            None => "all".to_string(),
+        };
+
+        Request::get(format!("/containers/{}/logs", id))
+            .query("stdout", self.stdout)
+            .query("stderr", self.stderr)
+            .query_opt("since", self.since)
+            .query_opt("until", self.until)
+            .query("tail", tail)
+            .query("timestamps", self.timestamps)
+            .query("follow", self.follow)
+    }
+}
+
+/// Request for an action changing the status of a container, like `stop`,
+/// with its query parameters percent-encoded.
+pub(crate) fn container_action_request(
+    action: &str,
+    id: &str,
+    params: &[(&str, &str)],
+) -> Request {
+    let api_endpoint =
+        format!("/containers/{id}/{action}", id = id, action = action);
+
+    let mut request = Request::post(api_endpoint);
+    for &(key, value) in params {
+        request = request.query(key, value);
+    }
+    request
+}
+
+/// Sends an action changing the status of a container, see
+/// `Containers::manipulate_container_status`.
+fn container_action<C: DockerApiClient + ?Sized>(
+    client: &C,
+    action: &str,
+    id: &str,
+    params: &[(&str, &str)],
+) -> Result<String, DockerApiError> {
+    let request = container_action_request(action, id, params);
+    client.send_request_expecting(request, 204)?;
+    Ok(format!("Container {} successful", action))
+}
+
+/// Maps the errors of a removal to the ones specific to containers.
+pub(crate) fn remove_container_error(
+    id: &str,
+    options: &RemoveContainerOptions,
+    err: DockerApiError,
+) -> DockerApiError {
+    match err {
+        DockerApiError::NotFound(_, _, message) => {
+            DockerApiError::NoSuchContainer(id.to_string(), message)
+        }
+        // Other conflicts, like a removal already in progress, are left as
+        // they are.
+        DockerApiError::Conflict(_, _, message)
+            if !options.force && !options.link && is_running(&message) =>
+        {
+            DockerApiError::ContainerRunning(id.to_string(), message)
+        }
+        err => err,
+    }
+}
+
+/// Whether the message of a conflict says the container is running, in the
+/// words of daemons before 20.10 or of later ones.
+fn is_running(message: &str) -> bool {
+    let message = message.to_ascii_lowercase();
+    message.contains("running container")
+        || message.contains("container is running")
 }
 
 #[derive(Serialize, Deserialize, Debug, Default)]
@@ -128,39 +858,14 @@ pub struct ContainerFsChange {
 }
 
 pub trait Containers: DockerApiClient {
-    /// Get Containers from the API endpoint with the method and query_param.
+    /// Get Containers with the provided list request.
     /// Helper function for Container trait.
     fn get_containers(
         &self,
-        api_endpoint: &str,
-        method: &str,
-        query_param: &str,
+        request: Request,
     ) -> Result<Vec<Container>, DockerApiError> {
-        let json_resp =
-            match self.get_response_from_api(api_endpoint, method, query_param)
-            {
-                Ok(resp) => {
-                    if resp.status_code == 200 {
-                        resp.body
-                    } else {
-                        return Err(DockerApiError::InvalidApiResponseError(
-                            resp.status_code,
-                            resp.body,
-                        ));
-                    }
-                }
-                Err(err) => return Err(err),
-            };
-
-        let containers: Vec<Container> = match serde_json::from_str(&json_resp)
-        {
-            Ok(info) => info,
-            Err(err) => {
-                return Err(DockerApiError::JsonDeserializationError(err))
-            }
-        };
-
-        Ok(containers)
+        let resp = self.send_request_expecting(request, 200)?;
+        resp.json()
     }
 
     /// List all the running containers
@@ -168,7 +873,7 @@ pub trait Containers: DockerApiClient {
     ///
     /// # Example
     ///
-    /// ```rust
+    /// ```rust,no_run
     /// extern crate rust_docker;
     ///
     /// use rust_docker::api::containers::Containers;
@@ -191,15 +896,11 @@ pub trait Containers: DockerApiClient {
         &self,
         limit: Option<u32>,
     ) -> Result<Vec<Container>, DockerApiError> {
-        let api_endpoint = "/containers/json";
-        let method = "GET";
-
-        let query_params = match limit {
-            Some(limit) => format!("?size=true&limit={}", limit),
-            None => "?size=true".to_string(),
-        };
+        let request = Request::get("/containers/json")
+            .query("size", true)
+            .query_opt("limit", limit);
 
-        self.get_containers(api_endpoint, method, &query_params)
+        self.get_containers(request)
     }
 
     /// List all containers whether running or stopped.
@@ -207,15 +908,12 @@ pub trait Containers: DockerApiClient {
         &self,
         limit: Option<u32>,
     ) -> Result<Vec<Container>, DockerApiError> {
-        let api_endpoint = "/containers/json";
-        let method = "GET";
-
-        let query_params = match limit {
-            Some(limit) => format!("?all=true&size=true&limit={}", limit),
-            None => "?all=true&size=true".to_string(),
-        };
+        let request = Request::get("/containers/json")
+            .query("all", true)
+            .query("size", true)
+            .query_opt("limit", limit);
 
-        self.get_containers(api_endpoint, method, &query_params)
+        self.get_containers(request)
     }
 
     /// List container with the filter provided, the filter can be looked from
@@ -226,18 +924,13 @@ pub trait Containers: DockerApiClient {
         filter: &str,
         limit: Option<u32>,
     ) -> Result<Vec<Container>, DockerApiError> {
-        let api_endpoint = "/containers/json";
-        let method = "GET";
-
-        let query_params = match limit {
-            Some(limit) => format!(
-                "?all=true&size=true&limit={}&filter={}",
-                limit, filter
-            ),
-            None => format!("?all=true&size=true&filter={}", filter),
-        };
+        let request = Request::get("/containers/json")
+            .query("all", true)
+            .query("size", true)
+            .query_opt("limit", limit)
+            .query("filters", filter);
 
-        self.get_containers(api_endpoint, method, &query_params)
+        self.get_containers(request)
     }
 
     /// Create a container from the ContainerConfig structure with the provided
@@ -250,22 +943,13 @@ pub trait Containers: DockerApiClient {
         name: &str,
         config: ContainerConfig,
     ) -> Result<CreateContainerResponse, DockerApiError> {
-        let api_endpoint = format!("/containers/create?name={}", name);
-        let method = "POST";
-        let body = match serde_json::to_string(&config) {
-            Ok(body) => body,
-            Err(err) => return Err(DockerApiError::JsonSerializationError(err)),
-        };
+        let request = Request::post("/containers/create")
+            .query("name", name)
+            .json(&config)?;
 
-        let resp = self.get_response_from_api(&api_endpoint, method, &body)?;
+        let resp = self.send_request_expecting(request, 201)?;
 
-        if resp.status_code != 201 {
-            return Err(DockerApiError::InvalidApiResponseError(
-                resp.status_code,
-                resp.body,
-            ));
-        }
-        match serde_json::from_str(&resp.body) {
+        match serde_json::from_slice(&resp.body) {
             Ok(info) => Ok(info),
             Err(err) => Err(DockerApiError::JsonDeserializationError(err)),
         }
@@ -274,12 +958,12 @@ pub trait Containers: DockerApiClient {
     /// Creates/Spawn docker container from the configuration provided. It only
     ///
     /// * Rust does not provide named arguments, so we are doing it this way
-    /// Currently rust structures does not have default values, so all the
-    /// values for the structure needs to be specified.
+    ///   Currently rust structures does not have default values, so all the
+    ///   values for the structure needs to be specified.
     ///
     /// # Example
     ///
-    /// ```rust
+    /// ```rust,no_run
     /// extern crate rust_docker;
     ///
     /// use rust_docker::api::containers::Containers;
@@ -309,7 +993,7 @@ pub trait Containers: DockerApiClient {
     ) -> Result<CreateContainerResponse, DockerApiError> {
         let config = ContainerConfig {
             Image: image.to_string(),
-            Cmd: cmd,
+            Cmd: Some(cmd),
             ..Default::default()
         };
 
@@ -321,7 +1005,7 @@ pub trait Containers: DockerApiClient {
     ///
     /// # Example
     ///
-    /// ```rust
+    /// ```rust,no_run
     /// extern crate rust_docker;
     ///
     /// use rust_docker::api::containers::Containers;
@@ -346,18 +1030,11 @@ pub trait Containers: DockerApiClient {
         id: &str,
     ) -> Result<ContainerDetails, DockerApiError> {
         let api_endpoint = format!("/containers/{id}/json", id = id);
-        let method = "GET";
 
-        let resp = self.get_response_from_api(&api_endpoint, method, "")?;
+        let resp =
+            self.send_request_expecting(Request::get(api_endpoint), 200)?;
 
-        if resp.status_code != 200 {
-            return Err(DockerApiError::InvalidApiResponseError(
-                resp.status_code,
-                resp.body,
-            ));
-        }
-
-        match serde_json::from_str(&resp.body) {
+        match serde_json::from_slice(&resp.body) {
             Ok(info) => Ok(info),
             Err(err) => Err(DockerApiError::JsonDeserializationError(err)),
         }
@@ -370,24 +1047,17 @@ pub trait Containers: DockerApiClient {
         id: &str,
     ) -> Result<Vec<ContainerFsChange>, DockerApiError> {
         let api_endpoint = format!("/containers/{id}/changes", id = id);
-        let method = "GET";
 
-        let resp = self.get_response_from_api(&api_endpoint, method, "")?;
+        let resp =
+            self.send_request_expecting(Request::get(api_endpoint), 200)?;
         // If the response is null, then there is no changes in the file
         // system so just return and empty vector. Serializing this will
         // result in error.
-        if resp.status_code != 200 {
-            return Err(DockerApiError::InvalidApiResponseError(
-                resp.status_code,
-                resp.body,
-            ));
-        }
-
-        if resp.body == "null" {
+        if resp.text()? == "null" {
             return Ok(Vec::new());
         }
 
-        match serde_json::from_str(&resp.body) {
+        match serde_json::from_slice(&resp.body) {
             Ok(info) => Ok(info),
             Err(err) => Err(DockerApiError::JsonDeserializationError(err)),
         }
@@ -408,9 +1078,12 @@ pub trait Containers: DockerApiClient {
     ///
     /// You can call any of these function or directly manipulate_container_status
     ///
+    /// `params` is the query string of the request, like `t=5`, which is
+    /// sent as it is.
+    ///
     /// # Example
     ///
-    /// ```rust
+    /// ```rust,no_run
     /// extern crate rust_docker;
     ///
     /// use rust_docker::api::containers::Containers;
@@ -444,32 +1117,23 @@ pub trait Containers: DockerApiClient {
         id: &str,
         params: &str,
     ) -> Result<String, DockerApiError> {
-        let api_endpoint = format!(
+        let mut api_endpoint = format!(
             "/containers/{id}/{action}",
             id = id,
             action = action
         );
-        let method = "GET";
-
-        let resp = self.get_response_from_api(&api_endpoint, method, params)?;
-
-        if resp.status_code == 204 {
-            Ok(format!("Container {} successful", action))
-        } else if resp.status_code == 304 {
-            Err(DockerApiError::ContainerError(format!(
-                "Container already {}ed",
-                action
-            )))
-        } else {
-            Err(DockerApiError::InvalidApiResponseError(
-                resp.status_code,
-                resp.body,
-            ))
+        // The query string is sent as it is, like `t=5`.
+        if !params.is_empty() {
+            api_endpoint.push('?');
+            api_endpoint.push_str(params);
         }
+
+        self.send_request_expecting(Request::post(api_endpoint), 204)?;
+        Ok(format!("Container {} successful", action))
     }
 
     fn start_container(&self, id: &str) -> Result<String, DockerApiError> {
-        self.manipulate_container_status("start", id, "")
+        container_action(self, "start", id, &[])
     }
 
     fn stop_container(
@@ -477,19 +1141,16 @@ pub trait Containers: DockerApiClient {
         id: &str,
         delay: Option<&str>,
     ) -> Result<String, DockerApiError> {
-        let param = match delay {
-            Some(d) => format!("t={}", d),
-            None => String::new(),
-        };
-        self.manipulate_container_status("stop", id, &param)
+        let params: Vec<_> = delay.map(|d| ("t", d)).into_iter().collect();
+        container_action(self, "stop", id, &params)
     }
 
     fn pause_container(&self, id: &str) -> Result<String, DockerApiError> {
-        self.manipulate_container_status("pause", id, "")
+        container_action(self, "pause", id, &[])
     }
 
     fn unpause_container(&self, id: &str) -> Result<String, DockerApiError> {
-        self.manipulate_container_status("unpause", id, "")
+        container_action(self, "unpause", id, &[])
     }
 
     fn restart_container(
@@ -497,11 +1158,8 @@ pub trait Containers: DockerApiClient {
         id: &str,
         delay: Option<&str>,
     ) -> Result<String, DockerApiError> {
-        let param = match delay {
-            Some(d) => format!("t={}", d),
-            None => String::new(),
-        };
-        self.manipulate_container_status("restart", id, &param)
+        let params: Vec<_> = delay.map(|d| ("t", d)).into_iter().collect();
+        container_action(self, "restart", id, &params)
     }
 
     fn kill_container(
@@ -509,11 +1167,9 @@ pub trait Containers: DockerApiClient {
         id: &str,
         signal: Option<&str>,
     ) -> Result<String, DockerApiError> {
-        let param = match signal {
-            Some(sig) => format!("signal={}", sig),
-            None => String::new(),
-        };
-        self.manipulate_container_status("kill", id, &param)
+        let params: Vec<_> =
+            signal.map(|sig| ("signal", sig)).into_iter().collect();
+        container_action(self, "kill", id, &params)
     }
 
     fn rename_container(
@@ -521,7 +1177,92 @@ pub trait Containers: DockerApiClient {
         id: &str,
         name: &str,
     ) -> Result<String, DockerApiError> {
-        let name_param = &format!("name={}", name);
-        self.manipulate_container_status("rename", id, name_param)
+        container_action(self, "rename", id, &[("name", name)])
+    }
+
+    /// Removes the container with the provided ID or name.
+    ///
+    /// Fails with `DockerApiError::NoSuchContainer` if it does not exist, and
+    /// with `DockerApiError::ContainerRunning` if it is running and the
+    /// removal is not forced.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// extern crate rust_docker;
+    ///
+    /// use rust_docker::api::containers::{Containers, RemoveContainerOptions};
+    /// use rust_docker::client::DockerClient;
+    /// use rust_docker::errors::DockerApiError;
+    ///
+    /// let client = DockerClient::new("unix:///var/run/docker.sock").unwrap();
+    ///
+    /// let options = RemoveContainerOptions::default().volumes(true);
+    /// match client.remove_container("f808ca...", options) {
+    ///     Ok(()) => println!("Container removed"),
+    ///     Err(DockerApiError::ContainerRunning(id, _)) => {
+    ///         println!("Stop {} first", id)
+    ///     }
+    ///     Err(err) => println!("An error occured : {}", err),
+    /// }
+    /// ```
+    fn remove_container(
+        &self,
+        id: &str,
+        options: RemoveContainerOptions,
+    ) -> Result<(), DockerApiError> {
+        match self.send_request_expecting(options.request(id), 204) {
+            Ok(_) => Ok(()),
+            Err(err) => Err(remove_container_error(id, &options, err)),
+        }
+    }
+
+    /// Reads the logs of the container with the provided ID or name, line
+    /// by line as they arrive, see `logs::Logs`. Dropping the logs stops
+    /// reading them, which is the way to stop following them.
+    ///
+    /// The read timeout of the client doesn't apply to followed logs.
+    ///
+    /// Fails with `DockerApiError::NoSuchContainer` if it does not exist.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// extern crate rust_docker;
+    ///
+    /// use rust_docker::api::containers::{Containers, LogsOptions};
+    /// use rust_docker::client::DockerClient;
+    ///
+    /// let client = DockerClient::new("unix:///var/run/docker.sock").unwrap();
+    ///
+    /// let options = LogsOptions::default().stdout(false).tail(Some(10));
+    /// for line in client.get_container_logs("f808ca...", options).unwrap() {
+    ///     println!("{}", String::from_utf8_lossy(line.unwrap().data()));
+    /// }
+    /// ```
+    fn get_container_logs(
+        &self,
+        id: &str,
+        options: LogsOptions,
+    ) -> Result<Logs<ResponseStream>, DockerApiError> {
+        let request = options.request(id);
+        let resp = match self.send_request_streaming(request, 200) {
+            Ok(resp) => resp,
+            Err(DockerApiError::NotFound(_, _, message)) => {
+                let id = id.to_string();
+                return Err(DockerApiError::NoSuchContainer(id, message));
+            }
+            Err(err) => return Err(err),
+        };
+
+        // Followed logs stay quiet for as long as the container does.
+        if options.follow {
+            if let Err(err) = resp.set_timeout(None) {
+                return Err(DockerApiError::StreamReadError(err));
+            }
+        }
+
+        let tty = logs::tty_from_content_type(resp.header("Content-Type"));
+        Ok(Logs::new(resp, tty))
     }
 }
@@ -2,25 +2,39 @@
 
 use std::collections::HashMap;
 
-use api::DockerApiClient;
-use utils;
+use crate::api::request::Request;
+use crate::api::DockerApiClient;
+use crate::utils;
 
 use serde_json;
 
-use errors::DockerApiError;
-
-#[derive(Serialize, Deserialize, Debug)]
+use crate::errors::DockerApiError;
+use crate::utils::api::null_as_default;
+
+/// An image, as listed by the daemon.
+///
+/// Fields missing from the answers of older or newer daemons are left
+/// empty, and the ones not listed here are kept in `extra`.
+///
+/// * RepoTags, RepoDigests: Untagged images have none.
+/// * VirtualSize: Same as `Size`, no longer sent from API 1.44.
+/// * SharedSize, Containers: -1 unless computed.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
 pub struct ImageCompactInfo {
     pub Id: String,
     pub ParentId: String,
+    #[serde(deserialize_with = "null_as_default")]
     pub RepoTags: Vec<String>,
     pub RepoDigests: Option<Vec<String>>,
     pub Created: u64,
     pub Size: u64,
-    pub VirtualSize: u64,
+    pub VirtualSize: Option<u64>,
     pub SharedSize: i64,
     pub Labels: Option<HashMap<String, String>>,
     pub Containers: i32,
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 pub trait Images: DockerApiClient {
@@ -30,7 +44,7 @@ pub trait Images: DockerApiClient {
     ///
     /// # Example
     ///
-    /// ```rust
+    /// ```rust,no_run
     /// extern crate rust_docker;
     ///
     /// use rust_docker::api::images::Images;
@@ -51,9 +65,6 @@ pub trait Images: DockerApiClient {
         &self,
         filter: Option<&str>,
     ) -> Result<Vec<ImageCompactInfo>, DockerApiError> {
-        let api_endpoint = "/images/json";
-        let method = "GET";
-
         let filter_val = filter.unwrap_or("");
         if !filter_val.is_empty() && !utils::api::validate_json_str(filter_val)
         {
@@ -62,19 +73,13 @@ pub trait Images: DockerApiClient {
             ));
         }
 
-        let query_params = &format!("?filter={}", filter_val);
+        let request = Request::get("/images/json")
+            .query_opt("filters", filter.filter(|f| !f.is_empty()));
 
-        let resp =
-            self.get_response_from_api(api_endpoint, method, query_params)?;
-        if resp.status_code != 200 {
-            return Err(DockerApiError::InvalidApiResponseError(
-                resp.status_code,
-                resp.body,
-            ));
-        }
+        let resp = self.send_request_expecting(request, 200)?;
 
         let images_info: Vec<ImageCompactInfo> =
-            match serde_json::from_str(&resp.body) {
+            match serde_json::from_slice(&resp.body) {
                 Ok(info) => info,
                 Err(err) => {
                     return Err(DockerApiError::JsonDeserializationError(err))
@@ -99,9 +104,7 @@ pub trait Images: DockerApiClient {
         let base_dir_path = match utils::file::get_validated_dir_path(base_dir)
         {
             Ok(base_dir_path) => base_dir_path,
-            Err(e) => {
-                Err(DockerApiError::MismatchedParametersError(e.description()))
-            }
+            Err(e) => return Err(DockerApiError::InvalidParametersError(e)),
         };
 
         let default_dockerfile_path = "Dockerfile";
@@ -110,22 +113,21 @@ pub trait Images: DockerApiClient {
             .as_path()
             .is_file()
         {
-            Err(DockerApiError::MismatchedParametersError(format!(
+            return Err(DockerApiError::InvalidParametersError(format!(
                 "No Dockerfile present in the root of the directory : {}",
-                base_dir_path
-            )))
+                base_dir_path.display()
+            )));
         }
 
         let tar_path = match utils::file::create_gzipped_tarball(
             base_dir_path.to_str().unwrap(),
         ) {
             Ok(tar_path) => tar_path,
-            Err(e) => {
-                Err(DockerApiError::MismatchedParametersError(e.description()))
-            }
+            Err(e) => return Err(DockerApiError::InvalidParametersError(e)),
         };
 
-        Ok(build_image_from_tarball(tar_path.to_str().unwrap()))
+        self.build_image_from_tarball(tar_path.to_str().unwrap());
+        Ok(())
     }
 
     fn build_image_from_tarball(&self, _tar_path: &str) {}
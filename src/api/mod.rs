@@ -1,10 +1,17 @@
 pub mod api_utils;
 pub mod containers;
 pub mod images;
+pub mod request;
 pub mod version;
 
-use errors::DockerApiError;
-use utils::api::Response;
+use std::io;
+
+use crate::api::request::Request;
+use crate::errors::DockerApiError;
+use crate::streaming::ResponseStream;
+use crate::transport::Timeouts;
+use crate::upgrade::Upgraded;
+use crate::utils::api::Response;
 
 /// Highest level trait for a DockerAPI client
 ///
@@ -14,35 +21,273 @@ use utils::api::Response;
 /// trait, so that any Client implementing the API should have a request
 /// method available.
 pub trait DockerApiClient {
+    /// Sends the request to the docker daemon and returns its response, or
+    /// an error if no response could be read.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// extern crate rust_docker;
+    ///
+    /// use rust_docker::api::request::Request;
+    /// use rust_docker::api::DockerApiClient;
+    /// use rust_docker::client::DockerClient;
+    ///
+    /// let client = DockerClient::new("unix:///var/run/docker.sock").unwrap();
+    ///
+    /// let request = Request::get("/containers/json")
+    ///     .query("all", true)
+    ///     .query("filters", "{\"status\":[\"exited\"]}");
+    /// match client.send_request(request) {
+    ///     Ok(resp) => println!("{}", resp.text().unwrap()),
+    ///     Err(err) => println!("An error occured : {}", err),
+    /// }
+    /// ```
+    fn send_request(
+        &self,
+        request: Request,
+    ) -> Result<Response, DockerApiError> {
+        self.send_request_with_timeouts(request, None)
+    }
+
+    /// Same as `send_request`, with the provided timeouts instead of the ones
+    /// of the client for this request only. This is meant for long running
+    /// endpoints like wait or logs.
+    ///
+    /// Returns `DockerApiError::Timeout` with the endpoint if the request
+    /// timed out.
+    fn send_request_with_timeouts(
+        &self,
+        request: Request,
+        timeouts: Option<Timeouts>,
+    ) -> Result<Response, DockerApiError> {
+        let api_version = self.api_version();
+        let raw_req = request.to_bytes(api_version.as_deref())?;
+
+        match self.request_with_timeouts(&raw_req, timeouts) {
+            Ok(resp) => Response::parse_http_response(resp),
+            Err(err) => Err(connection_error(request.endpoint(), err)),
+        }
+    }
+
+    /// Sends the request like `send_request`, any response with a status
+    /// other than `status_code` is turned into the matching error, like
+    /// `DockerApiError::NotFound`, see `DockerApiError::from_response`.
+    fn send_request_expecting(
+        &self,
+        request: Request,
+        status_code: usize,
+    ) -> Result<Response, DockerApiError> {
+        let method = request.method();
+        let endpoint = request.endpoint().to_string();
+
+        let resp = self.send_request(request)?;
+        if resp.status_code != status_code {
+            return Err(DockerApiError::from_response(method, &endpoint, resp));
+        }
+
+        Ok(resp)
+    }
+
+    /// Sends the request like `send_request_expecting`, handing the response
+    /// back as soon as its head was read, so that its body can be read as
+    /// the daemon sends it. This is meant for endpoints like pull, build or
+    /// events, see `ResponseStream::progress` and `ResponseStream::json`.
+    ///
+    /// The response is read off a connection of its own, see
+    /// `request_upgrade`. Clients which can't hand their connections over
+    /// send the request like any other, the body being then read from
+    /// memory once the whole response was received.
+    fn send_request_streaming(
+        &self,
+        request: Request,
+        status_code: usize,
+    ) -> Result<ResponseStream, DockerApiError> {
+        let method = request.method();
+        let endpoint = request.endpoint().to_string();
+
+        let api_version = self.api_version();
+        let raw_req = request.to_bytes(api_version.as_deref())?;
+
+        let stream = match self.request_upgrade(&raw_req, None) {
+            Ok(upgraded) => ResponseStream::from_upgraded(upgraded),
+            Err(ref err) if err.kind() == io::ErrorKind::Unsupported => self
+                .request_with_timeouts(&raw_req, None)
+                .and_then(ResponseStream::from_bytes),
+            Err(err) => Err(err),
+        };
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(err) => return Err(connection_error(&endpoint, err)),
+        };
+
+        if stream.status_code() != status_code {
+            let resp = match stream.into_response() {
+                Ok(resp) => resp,
+                Err(err) => return Err(connection_error(&endpoint, err)),
+            };
+            return Err(DockerApiError::from_response(method, &endpoint, resp));
+        }
+
+        Ok(stream)
+    }
+
+    /// Sends a request asking the daemon to upgrade the connection to a raw
+    /// stream, like attach or exec start, and hands the connection back once
+    /// upgraded. The `Connection: Upgrade` and `Upgrade: tcp` headers are set
+    /// on the request.
+    ///
+    /// Responses which don't upgrade the connection are turned into the
+    /// matching error, like `DockerApiError::NotFound`, see
+    /// `DockerApiError::from_response`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// extern crate rust_docker;
+    ///
+    /// use std::io::Read;
+    ///
+    /// use rust_docker::api::request::Request;
+    /// use rust_docker::api::DockerApiClient;
+    /// use rust_docker::client::DockerClient;
+    ///
+    /// let client = DockerClient::new("unix:///var/run/docker.sock").unwrap();
+    ///
+    /// let request = Request::post("/containers/f808ca/attach")
+    ///     .query("stream", true)
+    ///     .query("stdout", true);
+    /// let mut upgraded = client.send_upgrade_request(request).unwrap();
+    ///
+    /// let mut output = Vec::new();
+    /// upgraded.read_to_end(&mut output).unwrap();
+    /// ```
+    fn send_upgrade_request(
+        &self,
+        request: Request,
+    ) -> Result<Upgraded, DockerApiError> {
+        let method = request.method();
+        let endpoint = request.endpoint().to_string();
+
+        let request = request
+            .header("Connection", "Upgrade")
+            .header("Upgrade", "tcp");
+        let api_version = self.api_version();
+        let raw_req = request.to_bytes(api_version.as_deref())?;
+
+        let upgraded = match self.request_upgrade(&raw_req, None) {
+            Ok(upgraded) => upgraded,
+            Err(err) => return Err(connection_error(&endpoint, err)),
+        };
+
+        match upgraded.status_code() {
+            101 | 200 => Ok(upgraded),
+            _ => {
+                let resp = match upgraded.into_response() {
+                    Ok(resp) => Response::parse_http_response(resp)?,
+                    Err(err) => return Err(connection_error(&endpoint, err)),
+                };
+                Err(DockerApiError::from_response(method, &endpoint, resp))
+            }
+        }
+    }
+
     /// Just a helper function for the Containers DockerApiClient.
     /// It formats the API request using the given parameters, and using
     /// this request the docker daemon and sends back the response of the request
     /// if the request was successful else an err.
     ///
-    /// This assumes that the request method has been implemented properly
+    /// The body of GET requests is the query string, see
+    /// `api_utils::get_api_request`. Prefer `send_request` which encodes the
+    /// query parameters.
     fn get_response_from_api(
         &self,
         api_endpoint: &str,
         method: &str,
         body: &str,
     ) -> Result<Response, DockerApiError> {
-        let req = match api_utils::get_formatted_api_request(
+        self.get_response_from_api_with_timeouts(
             api_endpoint,
             method,
             body,
-        ) {
-            Some(req) => req,
-            None => return Err(DockerApiError::RequestPrepareError("Error")),
-        };
+            None,
+        )
+    }
 
-        match self.request(&req) {
-            Some(resp) => Response::parse_http_response(resp),
-            None => Err(DockerApiError::RequestError(
-                "Got no response from docker host.",
-            )),
+    /// Same as `get_response_from_api`, with the provided timeouts instead
+    /// of the ones of the client for this request only.
+    fn get_response_from_api_with_timeouts(
+        &self,
+        api_endpoint: &str,
+        method: &str,
+        body: &str,
+        timeouts: Option<Timeouts>,
+    ) -> Result<Response, DockerApiError> {
+        match api_utils::get_api_request(api_endpoint, method, body) {
+            Some(request) => self.send_request_with_timeouts(request, timeouts),
+            None => Err(DockerApiError::RequestPrepareError("Error")),
+        }
+    }
+
+    /// API version the client is pinned to, every endpoint is prefixed
+    /// with `/v{version}` when this is set.
+    ///
+    /// By default no version is used and the daemon picks its own.
+    fn api_version(&self) -> Option<String> {
+        None
+    }
+
+    /// Implement this function to use this trait, it writes the raw request
+    /// to the daemon and returns the raw response.
+    fn request(&self, request: &[u8]) -> Option<Vec<u8>>;
+
+    /// Sends the request like `request`, with the provided timeouts instead
+    /// of the ones of the client. The error is returned so that timeouts can
+    /// be told apart.
+    ///
+    /// Clients without timeouts can rely on the default implementation,
+    /// which ignores them.
+    fn request_with_timeouts(
+        &self,
+        request: &[u8],
+        _timeouts: Option<Timeouts>,
+    ) -> io::Result<Vec<u8>> {
+        match self.request(request) {
+            Some(resp) => Ok(resp),
+            None => Err(io::Error::other("Got no response from docker host.")),
         }
     }
 
-    /// Implement this function to use this trait.
-    fn request(&self, request: &str) -> Option<Vec<u8>>;
+    /// Writes the raw request to a connection of its own and hands the
+    /// connection back once the head of the response was read, see
+    /// `Upgraded::read_response`. The connection is never reused for other
+    /// requests. This is how upgrade requests and streaming responses are
+    /// sent.
+    ///
+    /// Clients which can't hand their connections over can rely on the
+    /// default implementation, which fails with `io::ErrorKind::Unsupported`.
+    fn request_upgrade(
+        &self,
+        _request: &[u8],
+        _timeouts: Option<Timeouts>,
+    ) -> io::Result<Upgraded> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "Connection upgrades are not supported by this client",
+        ))
+    }
+}
+
+/// Maps the error hit while sending a request to `endpoint`, timeouts being
+/// told apart from other connection errors.
+pub(crate) fn connection_error(
+    endpoint: &str,
+    err: io::Error,
+) -> DockerApiError {
+    match err.kind() {
+        io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock => {
+            DockerApiError::Timeout(endpoint.to_string())
+        }
+        _ => DockerApiError::ConnectionError(endpoint.to_string(), err),
+    }
 }
@@ -0,0 +1,308 @@
+//! Builder for the HTTP requests sent to the Engine API.
+use std::fmt;
+
+use serde::Serialize;
+use serde_json;
+
+use crate::errors::DockerApiError;
+
+/// HTTP methods used by the Engine API.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Method {
+    GET,
+    POST,
+    PUT,
+    DELETE,
+    HEAD,
+}
+
+impl Method {
+    /// Parses a method name like `GET`, case insensitively.
+    pub fn parse(method: &str) -> Option<Method> {
+        match method.to_ascii_uppercase().as_str() {
+            "GET" => Some(Method::GET),
+            "POST" => Some(Method::POST),
+            "PUT" => Some(Method::PUT),
+            "DELETE" => Some(Method::DELETE),
+            "HEAD" => Some(Method::HEAD),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Method::GET => "GET",
+            Method::POST => "POST",
+            Method::PUT => "PUT",
+            Method::DELETE => "DELETE",
+            Method::HEAD => "HEAD",
+        }
+    }
+}
+
+impl fmt::Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A request to the Engine API.
+///
+/// * method: HTTP method of the request.
+/// * endpoint: Path of the endpoint, like `/containers/json`, without the
+///   API version which is added by the client.
+/// * query: Query parameters, percent-encoded when the request is sent.
+/// * headers: Extra headers sent along with the request.
+/// * body: Raw body of the request.
+///
+/// # Example
+///
+/// ```rust
+/// extern crate rust_docker;
+///
+/// use rust_docker::api::request::Request;
+///
+/// let request = Request::delete("/containers/f808ca")
+///     .query("force", true)
+///     .query_opt("v", None::<bool>);
+/// assert_eq!(request.path(), "/containers/f808ca?force=true");
+///
+/// let request = Request::put("/containers/f808ca/archive")
+///     .query("path", "/srv/my app")
+///     .body(vec![0u8; 512], "application/x-tar");
+/// assert_eq!(request.path(), "/containers/f808ca/archive?path=%2Fsrv%2Fmy%20app");
+/// ```
+#[derive(Clone, Debug)]
+pub struct Request {
+    method: Method,
+    endpoint: String,
+    query: Vec<(String, String)>,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+    /// Name of the first header set with a CR, LF or NUL in it, which is
+    /// reported when the request is serialized.
+    invalid_header: Option<String>,
+}
+
+impl Request {
+    /// Creates a new Request to the provided endpoint.
+    pub fn new<S: Into<String>>(method: Method, endpoint: S) -> Request {
+        Request {
+            method,
+            endpoint: endpoint.into(),
+            query: Vec::new(),
+            headers: Vec::new(),
+            body: Vec::new(),
+            invalid_header: None,
+        }
+    }
+
+    pub fn get<S: Into<String>>(endpoint: S) -> Request {
+        Request::new(Method::GET, endpoint)
+    }
+
+    pub fn post<S: Into<String>>(endpoint: S) -> Request {
+        Request::new(Method::POST, endpoint)
+    }
+
+    pub fn put<S: Into<String>>(endpoint: S) -> Request {
+        Request::new(Method::PUT, endpoint)
+    }
+
+    pub fn delete<S: Into<String>>(endpoint: S) -> Request {
+        Request::new(Method::DELETE, endpoint)
+    }
+
+    pub fn head<S: Into<String>>(endpoint: S) -> Request {
+        Request::new(Method::HEAD, endpoint)
+    }
+
+    /// Adds a query parameter to the request.
+    pub fn query<V: ToString>(mut self, key: &str, value: V) -> Request {
+        self.query.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Adds a query parameter to the request if a value is provided.
+    pub fn query_opt<V: ToString>(
+        self,
+        key: &str,
+        value: Option<V>,
+    ) -> Request {
+        match value {
+            Some(value) => self.query(key, value),
+            None => self,
+        }
+    }
+
+    /// Sets a header of the request, replacing any previous value for it.
+    ///
+    /// Headers with a CR, LF or NUL in their name or value are left out, and
+    /// make `to_bytes` fail, as they would end the head of the request.
+    pub fn header<N: Into<String>, V: Into<String>>(
+        mut self,
+        name: N,
+        value: V,
+    ) -> Request {
+        let name = name.into();
+        let value = value.into();
+        if !is_header_safe(&name) || !is_header_safe(&value) {
+            if self.invalid_header.is_none() {
+                self.invalid_header = Some(name);
+            }
+            return self;
+        }
+
+        self.headers
+            .retain(|(key, _)| !key.eq_ignore_ascii_case(&name));
+        self.headers.push((name, value));
+        self
+    }
+
+    /// Sets the raw body of the request along with its content type.
+    pub fn body<B: Into<Vec<u8>>>(
+        self,
+        body: B,
+        content_type: &str,
+    ) -> Request {
+        let mut request = self.header("Content-Type", content_type);
+        request.body = body.into();
+        request
+    }
+
+    /// Sets the body of the request to the JSON serialization of `body`.
+    pub fn json<T: Serialize>(
+        self,
+        body: &T,
+    ) -> Result<Request, DockerApiError> {
+        match serde_json::to_vec(body) {
+            Ok(body) => Ok(self.body(body, "application/json")),
+            Err(err) => Err(DockerApiError::JsonSerializationError(err)),
+        }
+    }
+
+    pub fn method(&self) -> Method {
+        self.method
+    }
+
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    /// Headers set on the request, in the order they were set.
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    pub fn body_bytes(&self) -> &[u8] {
+        &self.body
+    }
+
+    /// The endpoint along with the percent-encoded query string.
+    pub fn path(&self) -> String {
+        let mut path = self.endpoint.clone();
+        for (i, (key, value)) in self.query.iter().enumerate() {
+            let separator = if i == 0 && !self.endpoint.contains('?') {
+                '?'
+            } else {
+                '&'
+            };
+            path.push(separator);
+            path.push_str(&percent_encode(key));
+            path.push('=');
+            path.push_str(&percent_encode(value));
+        }
+
+        path
+    }
+
+    /// Serializes the request to be written to the connection, with the
+    /// endpoint prefixed by `/v{api_version}` if provided.
+    ///
+    /// The `Host` and `Content-Length` headers are added unless they were
+    /// set on the request. Returns `DockerApiError::InvalidParametersError`
+    /// if a header with a CR, LF or NUL in it was set, or if the path holds
+    /// a space or a control character, like an id with a line break in it,
+    /// as it would end the request line.
+    pub fn to_bytes(
+        &self,
+        api_version: Option<&str>,
+    ) -> Result<Vec<u8>, DockerApiError> {
+        if let Some(ref name) = self.invalid_header {
+            return Err(DockerApiError::InvalidParametersError(format!(
+                "The header `{}` holds a line break or a NUL byte",
+                name.escape_debug()
+            )));
+        }
+
+        let path = match api_version {
+            Some(version) => format!("/v{}{}", version, self.path()),
+            None => self.path(),
+        };
+        if !is_path_safe(&path) {
+            return Err(DockerApiError::InvalidParametersError(format!(
+                "The path `{}` holds a space or a control character",
+                path.escape_debug()
+            )));
+        }
+
+        let mut head = format!("{} {} HTTP/1.1\r\n", self.method, path);
+        if !self.has_header("Host") {
+            head.push_str("Host: docker\r\n");
+        }
+        for (name, value) in &self.headers {
+            head.push_str(&format!("{}: {}\r\n", name, value));
+        }
+
+        let has_body = match self.method {
+            Method::POST | Method::PUT => true,
+            _ => !self.body.is_empty(),
+        };
+        if has_body && !self.has_header("Content-Length") {
+            head.push_str(&format!("Content-Length: {}\r\n", self.body.len()));
+        }
+        head.push_str("\r\n");
+
+        let mut bytes = head.into_bytes();
+        bytes.extend_from_slice(&self.body);
+        Ok(bytes)
+    }
+
+    fn has_header(&self, name: &str) -> bool {
+        self.headers
+            .iter()
+            .any(|(key, _)| key.eq_ignore_ascii_case(name))
+    }
+}
+
+/// Whether a header name or value can be written in the head of a request
+/// without ending it.
+fn is_header_safe(value: &str) -> bool {
+    !value.contains(['\r', '\n', '\0'])
+}
+
+/// Whether a path can be written on the request line without ending it.
+fn is_path_safe(path: &str) -> bool {
+    !path.chars().any(|c| c == ' ' || c.is_control())
+}
+
+/// Percent-encodes a query parameter, only the unreserved characters of
+/// RFC 3986 are left as they are.
+pub fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z'
+            | b'a'..=b'z'
+            | b'0'..=b'9'
+            | b'-'
+            | b'.'
+            | b'_'
+            | b'~' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+
+    encoded
+}
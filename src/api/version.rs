@@ -1,14 +1,136 @@
-use api::api_utils::get_formatted_api_request;
-use api::DockerApiClient;
-use utils::api::Response;
+#![allow(non_snake_case)]
+use std::collections::HashMap;
+
+use serde_json;
+
+use crate::api::request::Request;
+use crate::api::DockerApiClient;
+use crate::errors::DockerApiError;
+use crate::utils::api::Response;
+
+/// What the daemon sends back along with the answer to `/_ping`.
+///
+/// * api_version: Highest API version supported, like `1.41`.
+/// * os_type: Operating system the daemon runs on, `linux` or `windows`.
+/// * experimental: Whether experimental features are enabled.
+/// * builder_version: Default builder, `1` for the classic builder and `2`
+///   for BuildKit.
+///
+/// Older daemons don't send every header, the missing ones are `None`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PingInfo {
+    pub api_version: Option<String>,
+    pub os_type: Option<String>,
+    pub experimental: bool,
+    pub builder_version: Option<String>,
+}
+
+impl PingInfo {
+    /// Reads the headers of a response to `/_ping`.
+    pub fn from_response(resp: &Response) -> PingInfo {
+        let header = |name| resp.header(name).map(|value| value.to_string());
+
+        PingInfo {
+            api_version: header("API-Version"),
+            os_type: header("OSType"),
+            experimental: resp.header("Docker-Experimental") == Some("true"),
+            builder_version: header("Builder-Version"),
+        }
+    }
+}
+
+/// Version of the daemon and of its components, as returned by `/version`.
+/// Fields missing from the answers of older daemons are left empty.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct VersionInfo {
+    pub Platform: Option<Platform>,
+    pub Components: Vec<ComponentVersion>,
+    pub Version: String,
+    pub ApiVersion: String,
+    pub MinAPIVersion: String,
+    pub GitCommit: String,
+    pub GoVersion: String,
+    pub Os: String,
+    pub Arch: String,
+    pub KernelVersion: String,
+    pub Experimental: bool,
+    pub BuildTime: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct Platform {
+    pub Name: String,
+}
+
+/// Version of a component of the daemon, like `Engine` or `containerd`.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct ComponentVersion {
+    pub Name: String,
+    pub Version: String,
+    pub Details: Option<HashMap<String, String>>,
+}
+
+/// System wide information about the daemon, as returned by `/info`.
+/// Fields missing from the answers of older daemons are left empty, and
+/// the ones not listed here are ignored.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct SystemInfo {
+    pub ID: String,
+    pub Name: String,
+    pub ServerVersion: String,
+    pub Containers: u64,
+    pub ContainersRunning: u64,
+    pub ContainersPaused: u64,
+    pub ContainersStopped: u64,
+    pub Images: u64,
+    pub Driver: String,
+    pub DriverStatus: Option<Vec<Vec<String>>>,
+    pub DockerRootDir: String,
+    pub MemoryLimit: bool,
+    pub SwapLimit: bool,
+    pub CpuCfsPeriod: bool,
+    pub CpuCfsQuota: bool,
+    pub IPv4Forwarding: bool,
+    pub Debug: bool,
+    pub NFd: i64,
+    pub NGoroutines: i64,
+    pub NEventsListener: i64,
+    pub SystemTime: String,
+    pub LoggingDriver: String,
+    pub CgroupDriver: String,
+    pub CgroupVersion: String,
+    pub KernelVersion: String,
+    pub OperatingSystem: String,
+    pub OSVersion: String,
+    pub OSType: String,
+    pub Architecture: String,
+    pub NCPU: u64,
+    pub MemTotal: u64,
+    pub IndexServerAddress: String,
+    pub HttpProxy: String,
+    pub HttpsProxy: String,
+    pub NoProxy: String,
+    pub Labels: Option<Vec<String>>,
+    pub ExperimentalBuild: bool,
+    pub LiveRestoreEnabled: bool,
+    pub Isolation: String,
+    pub DefaultRuntime: String,
+    pub SecurityOptions: Option<Vec<String>>,
+    pub Warnings: Option<Vec<String>>,
+}
 
 pub trait Version: DockerApiClient {
     /// Get version info for Docker
-    /// Returns a JSON serialized string containing this information
+    /// Returns the JSON body of `/info` as it was sent, use `info` to get it
+    /// deserialized.
     ///
     /// # Example
     ///
-    /// ```rust
+    /// ```rust,no_run
     /// extern crate rust_docker;
     ///
     /// use rust_docker::api::containers::Containers;
@@ -29,24 +151,105 @@ pub trait Version: DockerApiClient {
     /// }
     /// ```
     fn get_version_info(&self) -> Result<String, String> {
-        let api_endpoint = "/info";
-        let method = "GET";
-
-        let req = match get_formatted_api_request(api_endpoint, method, "") {
-            Some(req) => req,
-            None => return Err("Error while preparing request".to_string()),
-        };
-
-        let resp = match self.request(&req) {
-            Some(resp) => match Response::parse_http_response(resp) {
-                Ok(res) => res.body,
-                Err(_err) => {
-                    return Err("Response body was not valid".to_string())
-                }
+        match self.send_request(Request::get("/info")) {
+            Ok(resp) => match resp.text() {
+                Ok(text) => Ok(text.to_string()),
+                Err(err) => Err(err.to_string()),
             },
-            None => return Err("Got no response from docker host.".to_string()),
-        };
+            Err(err) => Err(err.to_string()),
+        }
+    }
+
+    /// Checks that the daemon answers, and returns what it tells about
+    /// itself in the headers of its answer.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// extern crate rust_docker;
+    ///
+    /// use rust_docker::api::version::Version;
+    /// use rust_docker::client::DockerClient;
+    ///
+    /// let client = DockerClient::new("unix:///var/run/docker.sock").unwrap();
+    ///
+    /// let ping = client.ping().unwrap();
+    /// println!("API version : {:?}", ping.api_version);
+    /// println!("OS type : {:?}", ping.os_type);
+    /// ```
+    fn ping(&self) -> Result<PingInfo, DockerApiError> {
+        let resp = self.send_request_expecting(Request::get("/_ping"), 200)?;
+
+        Ok(PingInfo::from_response(&resp))
+    }
+
+    /// Version of the daemon, its API and its components.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// extern crate rust_docker;
+    ///
+    /// use rust_docker::api::version::Version;
+    /// use rust_docker::client::DockerClient;
+    ///
+    /// let client = DockerClient::new("unix:///var/run/docker.sock").unwrap();
+    ///
+    /// let version = client.version().unwrap();
+    /// println!(
+    ///     "Docker {} (API {} to {})",
+    ///     version.Version, version.MinAPIVersion, version.ApiVersion
+    /// );
+    /// for component in version.Components {
+    ///     println!("{} {}", component.Name, component.Version);
+    /// }
+    /// ```
+    fn version(&self) -> Result<VersionInfo, DockerApiError> {
+        self.send_request_expecting(Request::get("/version"), 200)?
+            .json()
+    }
+
+    /// System wide information about the daemon, like the number of
+    /// containers and images or the storage driver.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// extern crate rust_docker;
+    ///
+    /// use rust_docker::api::version::Version;
+    /// use rust_docker::client::DockerClient;
+    ///
+    /// let client = DockerClient::new("unix:///var/run/docker.sock").unwrap();
+    ///
+    /// let info = client.info().unwrap();
+    /// println!("{} containers running", info.ContainersRunning);
+    /// ```
+    fn info(&self) -> Result<SystemInfo, DockerApiError> {
+        self.send_request_expecting(Request::get("/info"), 200)?
+            .json()
+    }
+
+    /// Highest API version supported by the daemon, like `1.41`.
+    ///
+    /// This is the `API-Version` header sent back by `/_ping`, older daemons
+    /// which don't send it are asked for the `ApiVersion` of `/version`.
+    fn get_api_version(&self) -> Result<String, DockerApiError> {
+        let resp = self.send_request(Request::get("/_ping"))?;
+        if let Some(version) = PingInfo::from_response(&resp).api_version {
+            return Ok(version);
+        }
+
+        let resp =
+            self.send_request_expecting(Request::get("/version"), 200)?;
+
+        let version: serde_json::Value = resp.json()?;
 
-        Ok(resp)
+        match version["ApiVersion"].as_str() {
+            Some(version) => Ok(version.to_string()),
+            None => Err(DockerApiError::HTTPResponseParseError(
+                "No ApiVersion in the version of the daemon",
+            )),
+        }
     }
 }
@@ -0,0 +1,461 @@
+//! An asynchronous client for communicating with the docker server
+use std::io;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpStream, UnixStream};
+use tokio::sync::Notify;
+use tokio::time;
+
+use crate::asynchronous::containers::AsyncContainers;
+use crate::asynchronous::images::AsyncImages;
+use crate::asynchronous::transport::{self, AsyncStream, TimedStream};
+use crate::asynchronous::version::AsyncVersion;
+use crate::asynchronous::AsyncDockerApiClient;
+use crate::client::SendError;
+use crate::errors::DockerClientError;
+use crate::pool::DEFAULT_POOL_SIZE;
+use crate::transport::Timeouts;
+use crate::utils;
+
+/// A structure defining an asynchronous Client to interact with the docker
+/// API, the counterpart of `client::DockerClient`.
+///
+/// Like the blocking client, each request takes a keep-alive connection from
+/// a bounded pool shared by the clones of the client.
+///
+/// * protocol: Underlying protocol we are using(UNIX by default.)
+/// * api_version: API version requests are pinned to, if any.
+/// * timeouts: Timeouts applied to every request, see `Timeouts`.
+/// * pool: Connections to the docker daemon.
+#[derive(Clone)]
+pub struct AsyncDockerClient {
+    protocol: ConnectionProtocol,
+    api_version: Option<String>,
+    timeouts: Timeouts,
+    pool: Arc<AsyncConnectionPool>,
+}
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Debug)]
+enum ConnectionProtocol {
+    UNIX(String),
+    TCP(String),
+}
+
+impl ConnectionProtocol {
+    /// Opens a new connection to the docker daemon, giving up once
+    /// `timeout` has passed.
+    async fn connect(
+        &self,
+        timeout: Option<Duration>,
+    ) -> Result<Box<dyn AsyncStream>, DockerClientError> {
+        let timeout = match timeout {
+            Some(timeout) => timeout,
+            None => return self.connect_now().await,
+        };
+
+        match time::timeout(timeout, self.connect_now()).await {
+            Ok(stream) => stream,
+            Err(_elapsed) => Err(DockerClientError::ConnectionTimeout(
+                self.address().to_string(),
+            )),
+        }
+    }
+
+    async fn connect_now(
+        &self,
+    ) -> Result<Box<dyn AsyncStream>, DockerClientError> {
+        let stream: io::Result<Box<dyn AsyncStream>> = match *self {
+            ConnectionProtocol::UNIX(ref path) => {
+                match UnixStream::connect(path).await {
+                    Ok(sock) => Ok(Box::new(sock)),
+                    Err(err) => Err(err),
+                }
+            }
+            ConnectionProtocol::TCP(ref addr) => {
+                match TcpStream::connect(addr.as_str()).await {
+                    Ok(sock) => Ok(Box::new(sock)),
+                    Err(err) => Err(err),
+                }
+            }
+        };
+
+        stream.map_err(|_err| {
+            DockerClientError::SocketConnectionError(self.address().to_string())
+        })
+    }
+
+    fn address(&self) -> &str {
+        match *self {
+            ConnectionProtocol::UNIX(ref addr)
+            | ConnectionProtocol::TCP(ref addr) => addr,
+        }
+    }
+}
+
+/// A bounded pool of non-blocking connections, the counterpart of
+/// `pool::ConnectionPool` which waits for a connection without blocking the
+/// thread.
+struct AsyncConnectionPool {
+    state: Mutex<PoolState>,
+    available: Notify,
+}
+
+struct PoolState {
+    idle: Vec<Box<dyn AsyncStream>>,
+    open: usize,
+    max_size: usize,
+}
+
+/// A connection slot checked out of the pool. The slot is given up unless
+/// a connection is checked back in through it, even when the request is
+/// dropped half way through.
+struct PooledSlot<'a> {
+    pool: &'a AsyncConnectionPool,
+    returned: bool,
+}
+
+impl<'a> PooledSlot<'a> {
+    fn checkin(mut self, stream: Box<dyn AsyncStream>) {
+        self.returned = true;
+        self.pool.checkin(stream);
+    }
+}
+
+impl<'a> Drop for PooledSlot<'a> {
+    fn drop(&mut self) {
+        if !self.returned {
+            self.pool.discard();
+        }
+    }
+}
+
+impl AsyncConnectionPool {
+    fn new(max_size: usize) -> AsyncConnectionPool {
+        AsyncConnectionPool {
+            state: Mutex::new(PoolState {
+                idle: Vec::new(),
+                open: 0,
+                max_size: max_size.max(1),
+            }),
+            available: Notify::new(),
+        }
+    }
+
+    fn set_max_size(&self, max_size: usize) {
+        let mut state = self.lock();
+        state.max_size = max_size.max(1);
+        while state.open > state.max_size && !state.idle.is_empty() {
+            state.idle.pop();
+            state.open -= 1;
+        }
+        drop(state);
+        self.available.notify_one();
+    }
+
+    /// Checks a connection out of the pool, reusing an idle one along with
+    /// `true` if possible, and opening a new one otherwise. Waits while the
+    /// pool is exhausted.
+    async fn checkout(
+        &self,
+        protocol: &ConnectionProtocol,
+        connect_timeout: Option<Duration>,
+    ) -> Result<(PooledSlot<'_>, Box<dyn AsyncStream>, bool), DockerClientError>
+    {
+        loop {
+            let notified = self.available.notified();
+            {
+                let mut state = self.lock();
+                if let Some(stream) = state.idle.pop() {
+                    return Ok((self.slot(), stream, true));
+                }
+
+                if state.open < state.max_size {
+                    state.open += 1;
+                    break;
+                }
+            }
+            notified.await;
+        }
+
+        // The slot is given up if connecting fails.
+        let slot = self.slot();
+        let stream = protocol.connect(connect_timeout).await?;
+        Ok((slot, stream, false))
+    }
+
+    fn slot(&self) -> PooledSlot<'_> {
+        PooledSlot {
+            pool: self,
+            returned: false,
+        }
+    }
+
+    fn checkin(&self, stream: Box<dyn AsyncStream>) {
+        let mut state = self.lock();
+        if state.open > state.max_size {
+            state.open -= 1;
+        } else {
+            state.idle.push(stream);
+        }
+        drop(state);
+        self.available.notify_one();
+    }
+
+    fn discard(&self) {
+        self.lock().open -= 1;
+        self.available.notify_one();
+    }
+
+    fn add(&self, stream: Box<dyn AsyncStream>) {
+        let mut state = self.lock();
+        state.open += 1;
+        state.idle.push(stream);
+    }
+
+    fn lock(&self) -> MutexGuard<'_, PoolState> {
+        // A panic while holding the lock can't leave the state inconsistent,
+        // so keep using it.
+        match self.state.lock() {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+impl AsyncDockerClient {
+    /// Creates a new AsyncDockerClient object connected to the docker daemon.
+    ///
+    /// The address can either be a unix domain socket address or a TCP
+    /// address to the daemon.
+    ///
+    /// * unix:///var/run/docker.sock
+    /// * tcp://127.0.0.1:2375
+    pub async fn new<S: Into<String>>(
+        connection_addr: S,
+    ) -> Result<AsyncDockerClient, DockerClientError> {
+        let connection_addr = connection_addr.into();
+
+        let protocol = if connection_addr.starts_with("tcp://") {
+            match utils::api::validate_tcp_socket_address(&connection_addr) {
+                Some(addr_comps) => {
+                    ConnectionProtocol::TCP(addr_comps[1].to_string())
+                }
+                None => {
+                    return Err(DockerClientError::InvalidTargetAddress(
+                        connection_addr,
+                    ))
+                }
+            }
+        } else {
+            match utils::api::validate_unix_socket_address(&connection_addr) {
+                Some(addr_comps) => {
+                    ConnectionProtocol::UNIX(addr_comps[1].to_string())
+                }
+                None => {
+                    return Err(DockerClientError::InvalidTargetAddress(
+                        connection_addr,
+                    ))
+                }
+            }
+        };
+
+        // Open a first connection so an unreachable daemon is reported
+        // right away.
+        let pool = AsyncConnectionPool::new(DEFAULT_POOL_SIZE);
+        pool.add(protocol.connect(None).await?);
+
+        Ok(AsyncDockerClient {
+            protocol,
+            api_version: None,
+            timeouts: Timeouts::default(),
+            pool: Arc::new(pool),
+        })
+    }
+
+    /// Pins every request made by the client to the provided API version,
+    /// like `1.37`, instead of letting the daemon pick its default.
+    pub fn with_api_version<S: Into<String>>(
+        mut self,
+        version: S,
+    ) -> Result<AsyncDockerClient, DockerClientError> {
+        let version = version.into();
+        match utils::api::validate_api_version(&version) {
+            Some(v) => self.api_version = Some(v.to_string()),
+            None => return Err(DockerClientError::InvalidApiVersion(version)),
+        };
+
+        Ok(self)
+    }
+
+    /// Pins every request made by the client to the highest API version
+    /// supported by both the daemon and the client, the same way the docker
+    /// CLI does. Versions from `api_utils::MIN_API_VERSION` up to
+    /// `api_utils::MAX_API_VERSION` are supported.
+    ///
+    /// The daemon is pinged to find out the API version it supports, which
+    /// fails with `DockerClientError::ApiVersionNegotiationError` if it can't
+    /// be reached or is too old.
+    pub async fn negotiate_api_version(
+        mut self,
+    ) -> Result<AsyncDockerClient, DockerClientError> {
+        // Ping the daemon without any version, it may not support ours.
+        self.api_version = None;
+        let daemon_version = match self.get_api_version().await {
+            Ok(version) => version,
+            Err(err) => {
+                return Err(DockerClientError::ApiVersionNegotiationError(
+                    err.to_string(),
+                ))
+            }
+        };
+
+        match utils::api::negotiate_api_version(&daemon_version) {
+            Some(version) => self.api_version = Some(version),
+            None => {
+                return Err(DockerClientError::ApiVersionNegotiationError(
+                    format!(
+                        "the daemon API version `{}` is not supported",
+                        daemon_version
+                    ),
+                ))
+            }
+        };
+
+        Ok(self)
+    }
+
+    /// Sets the timeouts applied to every request made by the client, see
+    /// `DockerClient::with_timeouts`. Expired requests fail with
+    /// `DockerApiError::Timeout`.
+    ///
+    /// The timeouts rely on the timer of the tokio runtime, which must be
+    /// enabled when any is set.
+    pub fn with_timeouts(mut self, timeouts: Timeouts) -> AsyncDockerClient {
+        self.timeouts = timeouts;
+        self
+    }
+
+    /// Timeouts applied to every request made by the client.
+    pub fn timeouts(&self) -> Timeouts {
+        self.timeouts
+    }
+
+    /// Limits the number of connections the client opens to the daemon at
+    /// once(`pool::DEFAULT_POOL_SIZE` by default.) Requests wait for a
+    /// connection to be returned once the limit is reached.
+    ///
+    /// The pool is shared with the clones of the client, so the limit applies
+    /// to all of them.
+    pub fn with_pool_size(self, max_size: usize) -> AsyncDockerClient {
+        self.pool.set_max_size(max_size);
+        self
+    }
+
+    /// Sends the request over the connection and reads the response back.
+    /// The connection goes back to the pool if the daemon kept it alive.
+    async fn send(
+        &self,
+        slot: PooledSlot<'_>,
+        stream: Box<dyn AsyncStream>,
+        request: &[u8],
+        read_timeout: Option<Duration>,
+    ) -> Result<Vec<u8>, SendError> {
+        let mut stream = TimedStream::new(stream, read_timeout);
+        if let Err(err) = stream.write_all(request).await {
+            return Err(SendError::Write(err));
+        }
+
+        let head_request = request.starts_with(b"HEAD ");
+        let mut reader = BufReader::new(stream);
+        match reader.fill_buf().await {
+            Ok([]) => {
+                return Err(SendError::NoResponse(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "The connection was closed before any response",
+                )))
+            }
+            Ok(_) => {}
+            Err(err) => return Err(SendError::NoResponse(err)),
+        }
+        let resp = transport::read_http_response(&mut reader, head_request);
+        let (raw_resp, keep_alive) = match resp.await {
+            Ok(resp) => resp,
+            Err(err) => return Err(SendError::Read(err)),
+        };
+
+        // Anything buffered past the response would be mistaken for the
+        // response to the next request.
+        if keep_alive && reader.buffer().is_empty() {
+            slot.checkin(reader.into_inner().into_inner());
+        }
+
+        Ok(raw_resp)
+    }
+}
+
+#[async_trait]
+impl AsyncDockerApiClient for AsyncDockerClient {
+    fn api_version(&self) -> Option<String> {
+        self.api_version.clone()
+    }
+
+    async fn request(&self, request: &[u8]) -> io::Result<Vec<u8>> {
+        self.request_with_timeouts(request, None).await
+    }
+
+    async fn request_with_timeouts(
+        &self,
+        request: &[u8],
+        timeouts: Option<Timeouts>,
+    ) -> io::Result<Vec<u8>> {
+        let timeouts = timeouts.unwrap_or(self.timeouts);
+        let exchange = async {
+            loop {
+                let checkout =
+                    self.pool.checkout(&self.protocol, timeouts.connect).await;
+                let (slot, stream, reused) = match checkout {
+                    Ok(checkout) => checkout,
+                    Err(DockerClientError::ConnectionTimeout(addr)) => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::TimedOut,
+                            addr,
+                        ))
+                    }
+                    Err(err) => return Err(io::Error::other(err.to_string())),
+                };
+
+                match self.send(slot, stream, request, timeouts.read).await {
+                    Ok(raw_resp) => return Ok(raw_resp),
+                    // The daemon may have closed the idle connection in the
+                    // meantime, try again on another one unless the request
+                    // may have been acted on.
+                    Err(ref err) if reused && err.can_retry(request) => {
+                        continue
+                    }
+                    Err(err) => return Err(err.into_inner()),
+                }
+            }
+        };
+
+        // The connection is given up along with the request once it expires.
+        let timeout = match timeouts.request {
+            Some(timeout) => timeout,
+            None => return exchange.await,
+        };
+        match time::timeout(timeout, exchange).await {
+            Ok(resp) => resp,
+            Err(_elapsed) => Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "Request timed out",
+            )),
+        }
+    }
+}
+
+impl AsyncVersion for AsyncDockerClient {}
+impl AsyncContainers for AsyncDockerClient {}
+impl AsyncImages for AsyncDockerClient {}
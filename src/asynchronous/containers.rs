@@ -0,0 +1,233 @@
+use async_trait::async_trait;
+
+use crate::api::containers::{
+    container_action_request, remove_container_error, Container,
+    ContainerConfig, ContainerDetails, ContainerFsChange,
+    CreateContainerResponse, RemoveContainerOptions,
+};
+use crate::api::request::Request;
+use crate::asynchronous::AsyncDockerApiClient;
+use crate::errors::DockerApiError;
+
+/// Asynchronous counterpart of `api::containers::Containers`, see it for the
+/// documentation of each method.
+#[async_trait]
+pub trait AsyncContainers: AsyncDockerApiClient {
+    /// Get Containers with the provided list request.
+    /// Helper function for AsyncContainers trait.
+    async fn get_containers(
+        &self,
+        request: Request,
+    ) -> Result<Vec<Container>, DockerApiError> {
+        let resp = self.send_request_expecting(request, 200).await?;
+        resp.json()
+    }
+
+    /// List all the running containers
+    /// Return an instance of Vector of container
+    async fn list_running_containers(
+        &self,
+        limit: Option<u32>,
+    ) -> Result<Vec<Container>, DockerApiError> {
+        let request = Request::get("/containers/json")
+            .query("size", true)
+            .query_opt("limit", limit);
+
+        self.get_containers(request).await
+    }
+
+    /// List all containers whether running or stopped.
+    async fn list_all_containers(
+        &self,
+        limit: Option<u32>,
+    ) -> Result<Vec<Container>, DockerApiError> {
+        let request = Request::get("/containers/json")
+            .query("all", true)
+            .query("size", true)
+            .query_opt("limit", limit);
+
+        self.get_containers(request).await
+    }
+
+    /// List container with the filter provided, the filter can be looked from
+    /// Docker engine official API documentation.
+    /// https://docs.docker.com/engine/api/v1.37/#operation/ContainerList
+    async fn get_container_details_with_filter(
+        &self,
+        filter: &str,
+        limit: Option<u32>,
+    ) -> Result<Vec<Container>, DockerApiError> {
+        let request = Request::get("/containers/json")
+            .query("all", true)
+            .query("size", true)
+            .query_opt("limit", limit)
+            .query("filters", filter);
+
+        self.get_containers(request).await
+    }
+
+    /// Create a container from the ContainerConfig structure with the provided
+    /// `name`.
+    async fn create_container(
+        &self,
+        name: &str,
+        config: ContainerConfig,
+    ) -> Result<CreateContainerResponse, DockerApiError> {
+        let request = Request::post("/containers/create")
+            .query("name", name)
+            .json(&config)?;
+
+        let resp = self.send_request_expecting(request, 201).await?;
+        resp.json()
+    }
+
+    /// Creates a container from an image and a command only.
+    async fn create_container_minimal(
+        &self,
+        name: &str,
+        image: &str,
+        cmd: Vec<String>,
+    ) -> Result<CreateContainerResponse, DockerApiError> {
+        let config = ContainerConfig {
+            Image: image.to_string(),
+            Cmd: Some(cmd),
+            ..Default::default()
+        };
+
+        self.create_container(name, config).await
+    }
+
+    /// Inspects the container with the provided ID
+    /// Returns Low level information about the container.
+    async fn inspect_container(
+        &self,
+        id: &str,
+    ) -> Result<ContainerDetails, DockerApiError> {
+        let api_endpoint = format!("/containers/{id}/json", id = id);
+
+        let resp = self
+            .send_request_expecting(Request::get(api_endpoint), 200)
+            .await?;
+        resp.json()
+    }
+
+    /// Gives the changes done to the filesystem of the docker container as a
+    /// list of files with the kind of changes.
+    async fn get_container_filesystem_changes(
+        &self,
+        id: &str,
+    ) -> Result<Vec<ContainerFsChange>, DockerApiError> {
+        let api_endpoint = format!("/containers/{id}/changes", id = id);
+
+        let resp = self
+            .send_request_expecting(Request::get(api_endpoint), 200)
+            .await?;
+        // A null body means there are no changes in the filesystem.
+        if resp.text()? == "null" {
+            return Ok(Vec::new());
+        }
+
+        resp.json()
+    }
+
+    /// Function to manipulate container status, the parent function for all
+    /// the commands which result in a status change of the container.
+    async fn manipulate_container_status(
+        &self,
+        action: &str,
+        id: &str,
+        params: &str,
+    ) -> Result<String, DockerApiError> {
+        let mut api_endpoint =
+            format!("/containers/{id}/{action}", id = id, action = action);
+        if !params.is_empty() {
+            api_endpoint.push('?');
+            api_endpoint.push_str(params);
+        }
+
+        self.send_request_expecting(Request::post(api_endpoint), 204)
+            .await?;
+        Ok(format!("Container {} successful", action))
+    }
+
+    async fn start_container(
+        &self,
+        id: &str,
+    ) -> Result<String, DockerApiError> {
+        container_action(self, "start", id, &[]).await
+    }
+
+    async fn stop_container(
+        &self,
+        id: &str,
+        delay: Option<&str>,
+    ) -> Result<String, DockerApiError> {
+        let params: Vec<_> = delay.map(|d| ("t", d)).into_iter().collect();
+        container_action(self, "stop", id, &params).await
+    }
+
+    async fn pause_container(
+        &self,
+        id: &str,
+    ) -> Result<String, DockerApiError> {
+        container_action(self, "pause", id, &[]).await
+    }
+
+    async fn unpause_container(
+        &self,
+        id: &str,
+    ) -> Result<String, DockerApiError> {
+        container_action(self, "unpause", id, &[]).await
+    }
+
+    async fn restart_container(
+        &self,
+        id: &str,
+        delay: Option<&str>,
+    ) -> Result<String, DockerApiError> {
+        let params: Vec<_> = delay.map(|d| ("t", d)).into_iter().collect();
+        container_action(self, "restart", id, &params).await
+    }
+
+    async fn kill_container(
+        &self,
+        id: &str,
+        signal: Option<&str>,
+    ) -> Result<String, DockerApiError> {
+        let params: Vec<_> =
+            signal.map(|sig| ("signal", sig)).into_iter().collect();
+        container_action(self, "kill", id, &params).await
+    }
+
+    async fn rename_container(
+        &self,
+        id: &str,
+        name: &str,
+    ) -> Result<String, DockerApiError> {
+        container_action(self, "rename", id, &[("name", name)]).await
+    }
+
+    async fn remove_container(
+        &self,
+        id: &str,
+        options: RemoveContainerOptions,
+    ) -> Result<(), DockerApiError> {
+        match self.send_request_expecting(options.request(id), 204).await {
+            Ok(_) => Ok(()),
+            Err(err) => Err(remove_container_error(id, &options, err)),
+        }
+    }
+}
+
+/// Sends an action changing the status of a container, see
+/// `AsyncContainers::manipulate_container_status`.
+async fn container_action<C: AsyncDockerApiClient + ?Sized>(
+    client: &C,
+    action: &str,
+    id: &str,
+    params: &[(&str, &str)],
+) -> Result<String, DockerApiError> {
+    let request = container_action_request(action, id, params);
+    client.send_request_expecting(request, 204).await?;
+    Ok(format!("Container {} successful", action))
+}
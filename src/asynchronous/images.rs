@@ -0,0 +1,36 @@
+use async_trait::async_trait;
+
+use crate::api::images::ImageCompactInfo;
+use crate::api::request::Request;
+use crate::asynchronous::AsyncDockerApiClient;
+use crate::errors::DockerApiError;
+use crate::utils;
+
+/// Asynchronous counterpart of `api::images::Images`.
+///
+/// `build_image` is left out until the blocking one uploads the build
+/// context to the daemon.
+#[async_trait]
+pub trait AsyncImages: AsyncDockerApiClient {
+    /// Only images from final layer is listed in the image by default.
+    /// filter corresponds to a JSON encoded string of filters as mentioned
+    /// in the https://docs.docker.com/engine/api/v1.37/#operation/ImageList
+    async fn list_images(
+        &self,
+        filter: Option<&str>,
+    ) -> Result<Vec<ImageCompactInfo>, DockerApiError> {
+        let filter_val = filter.unwrap_or("");
+        if !filter_val.is_empty() && !utils::api::validate_json_str(filter_val)
+        {
+            return Err(DockerApiError::MismatchedParametersError(
+                "The provided filter is not a valid JSON.",
+            ));
+        }
+
+        let request = Request::get("/images/json")
+            .query_opt("filters", filter.filter(|f| !f.is_empty()));
+
+        let resp = self.send_request_expecting(request, 200).await?;
+        resp.json()
+    }
+}
@@ -0,0 +1,148 @@
+//! Asynchronous counterpart of the docker API, built on tokio.
+//!
+//! This module is only available with the `tokio` cargo feature. It mirrors
+//! the blocking API, `AsyncDockerClient` plays the role of `DockerClient` and
+//! the `AsyncContainers`, `AsyncImages` and `AsyncVersion` traits provide the
+//! same methods as their blocking counterparts, returning futures instead.
+//!
+//! Only the unix socket and plain TCP transports are supported, TLS and SSH
+//! are only available with the blocking `DockerClient`.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use rust_docker::asynchronous::containers::AsyncContainers;
+//! use rust_docker::asynchronous::AsyncDockerClient;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let client = AsyncDockerClient::new("unix:///var/run/docker.sock")
+//!         .await
+//!         .unwrap();
+//!
+//!     match client.list_running_containers(None).await {
+//!         Ok(containers) => println!("{:?}", containers),
+//!         Err(err) => println!("An error occured : {}", err),
+//!     }
+//! }
+//! ```
+pub mod client;
+pub mod containers;
+pub mod images;
+pub mod transport;
+pub mod version;
+
+pub use self::client::AsyncDockerClient;
+
+use std::io;
+
+use async_trait::async_trait;
+
+use crate::api::api_utils;
+use crate::api::connection_error;
+use crate::api::request::Request;
+use crate::errors::DockerApiError;
+use crate::transport::Timeouts;
+use crate::utils::api::Response;
+
+/// Highest level trait for an asynchronous DockerAPI client, the counterpart
+/// of `api::DockerApiClient`.
+///
+/// To implement this trait the only required method is `request`.
+#[async_trait]
+pub trait AsyncDockerApiClient: Send + Sync {
+    /// Sends the request to the docker daemon and returns its response, or
+    /// an error if no response could be read. Connection errors are turned
+    /// into `DockerApiError::ConnectionError` with the IO error as their
+    /// source.
+    async fn send_request(
+        &self,
+        request: Request,
+    ) -> Result<Response, DockerApiError> {
+        self.send_request_with_timeouts(request, None).await
+    }
+
+    /// Same as `send_request`, with the provided timeouts instead of the ones
+    /// of the client for this request only, see
+    /// `DockerApiClient::send_request_with_timeouts`.
+    ///
+    /// Returns `DockerApiError::Timeout` with the endpoint if the request
+    /// timed out.
+    async fn send_request_with_timeouts(
+        &self,
+        request: Request,
+        timeouts: Option<Timeouts>,
+    ) -> Result<Response, DockerApiError> {
+        let api_version = self.api_version();
+        let raw_req = request.to_bytes(api_version.as_deref())?;
+
+        match self.request_with_timeouts(&raw_req, timeouts).await {
+            Ok(resp) => Response::parse_http_response(resp),
+            Err(err) => Err(connection_error(request.endpoint(), err)),
+        }
+    }
+
+    /// Sends the request like `send_request`, any response with a status
+    /// other than `status_code` is turned into the matching error, see
+    /// `DockerApiError::from_response`.
+    async fn send_request_expecting(
+        &self,
+        request: Request,
+        status_code: usize,
+    ) -> Result<Response, DockerApiError> {
+        let method = request.method();
+        let endpoint = request.endpoint().to_string();
+
+        let resp = self.send_request(request).await?;
+        if resp.status_code != status_code {
+            return Err(DockerApiError::from_response(method, &endpoint, resp));
+        }
+
+        Ok(resp)
+    }
+
+    /// Formats the API request using the given parameters, sends it to the
+    /// docker daemon and returns the response of the request if the request
+    /// was successful else an err.
+    ///
+    /// The body of GET requests is the query string, see
+    /// `api_utils::get_api_request`. Prefer `send_request` which encodes the
+    /// query parameters.
+    async fn get_response_from_api(
+        &self,
+        api_endpoint: &str,
+        method: &str,
+        body: &str,
+    ) -> Result<Response, DockerApiError> {
+        match api_utils::get_api_request(api_endpoint, method, body) {
+            Some(request) => self.send_request(request).await,
+            None => Err(DockerApiError::RequestPrepareError("Error")),
+        }
+    }
+
+    /// API version the client is pinned to, every endpoint is prefixed
+    /// with `/v{version}` when this is set.
+    ///
+    /// By default no version is used and the daemon picks its own.
+    fn api_version(&self) -> Option<String> {
+        None
+    }
+
+    /// Implement this function to use this trait, it writes the raw request
+    /// to the daemon and returns the raw response. The error is returned so
+    /// that timeouts can be told apart.
+    async fn request(&self, request: &[u8]) -> io::Result<Vec<u8>>;
+
+    /// Sends the request like `request`, with the provided timeouts instead
+    /// of the ones of the client.
+    ///
+    /// Clients without timeouts can rely on the default implementation,
+    /// which ignores them.
+    async fn request_with_timeouts(
+        &self,
+        request: &[u8],
+        _timeouts: Option<Timeouts>,
+    ) -> io::Result<Vec<u8>> {
+        self.request(request).await
+    }
+}
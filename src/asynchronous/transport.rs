@@ -0,0 +1,217 @@
+//! Non-blocking transports used by the AsyncDockerClient.
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use tokio::io::AsyncWrite;
+use tokio::io::ReadBuf;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncReadExt};
+use tokio::time::{self, Sleep};
+
+use crate::utils::api::{self as api_utils, BodyFraming};
+
+/// A non-blocking bidirectional byte stream connected to the docker daemon,
+/// the counterpart of `transport::Stream`.
+pub trait AsyncStream: AsyncRead + AsyncWrite + Send + Unpin {}
+
+impl<T: AsyncRead + AsyncWrite + Send + Unpin> AsyncStream for T {}
+
+/// A stream failing with `io::ErrorKind::TimedOut` once a read or write
+/// waited on the daemon for longer than the timeout, the counterpart of
+/// `transport::Stream::set_timeout`.
+pub(crate) struct TimedStream<S> {
+    inner: S,
+    timeout: Option<Duration>,
+    /// Expires once the read or write in progress has waited too long.
+    sleep: Option<Pin<Box<Sleep>>>,
+}
+
+impl<S> TimedStream<S> {
+    pub(crate) fn new(inner: S, timeout: Option<Duration>) -> TimedStream<S> {
+        TimedStream {
+            inner,
+            timeout,
+            sleep: None,
+        }
+    }
+
+    pub(crate) fn into_inner(self) -> S {
+        self.inner
+    }
+
+    /// Hands back the result of the read or write once ready, or an error
+    /// once it waited for longer than the timeout.
+    fn poll_timeout<T>(
+        &mut self,
+        cx: &mut Context<'_>,
+        poll: Poll<io::Result<T>>,
+    ) -> Poll<io::Result<T>> {
+        if poll.is_ready() {
+            self.sleep = None;
+            return poll;
+        }
+
+        let timeout = match self.timeout {
+            Some(timeout) => timeout,
+            None => return poll,
+        };
+        let sleep = self
+            .sleep
+            .get_or_insert_with(|| Box::pin(time::sleep(timeout)));
+        match sleep.as_mut().poll(cx) {
+            Poll::Ready(()) => {
+                self.sleep = None;
+                Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    "Timed out waiting on the docker daemon",
+                )))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for TimedStream<S> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let poll = Pin::new(&mut this.inner).poll_read(cx, buf);
+        this.poll_timeout(cx, poll)
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for TimedStream<S> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let poll = Pin::new(&mut this.inner).poll_write(cx, buf);
+        this.poll_timeout(cx, poll)
+    }
+
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let poll = Pin::new(&mut this.inner).poll_flush(cx);
+        this.poll_timeout(cx, poll)
+    }
+
+    fn poll_shutdown(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+    }
+}
+
+/// Reads a single HTTP/1.1 response from the reader, see
+/// `utils::api::read_http_response` which this mirrors.
+pub async fn read_http_response<R: AsyncBufRead + Unpin>(
+    reader: &mut R,
+    head_request: bool,
+) -> io::Result<(Vec<u8>, bool)> {
+    loop {
+        let mut raw_resp: Vec<u8> = Vec::new();
+        let (status_code, headers) =
+            read_http_head(reader, &mut raw_resp).await?;
+
+        if status_code / 100 == 1 && status_code != 101 {
+            continue;
+        }
+
+        let keep_alive =
+            api_utils::is_keep_alive(&raw_resp, status_code, &headers);
+        match api_utils::body_framing(status_code, &headers, head_request)? {
+            BodyFraming::Empty => {}
+            BodyFraming::Chunked => {
+                read_chunked_body(reader, &mut raw_resp).await?
+            }
+            BodyFraming::Length(len) => {
+                read_exact_len(reader, len, &mut raw_resp).await?
+            }
+            BodyFraming::UntilClose => {
+                // The body ends with the connection, which can't be reused.
+                reader.read_to_end(&mut raw_resp).await?;
+                return Ok((raw_resp, false));
+            }
+        }
+
+        return Ok((raw_resp, keep_alive));
+    }
+}
+
+async fn read_http_head<R: AsyncBufRead + Unpin>(
+    reader: &mut R,
+    raw_resp: &mut Vec<u8>,
+) -> io::Result<(usize, std::collections::HashMap<String, String>)> {
+    let start = raw_resp.len();
+    loop {
+        let line_start = raw_resp.len();
+        if reader.read_until(b'\n', raw_resp).await? == 0 {
+            return Err(api_utils::unexpected_eof(
+                "Connection closed before the end of the response head",
+            ));
+        }
+
+        if api_utils::is_empty_line(&raw_resp[line_start..]) {
+            break;
+        }
+    }
+
+    api_utils::parse_raw_http_head(&raw_resp[start..])
+}
+
+async fn read_chunked_body<R: AsyncBufRead + Unpin>(
+    reader: &mut R,
+    raw_resp: &mut Vec<u8>,
+) -> io::Result<()> {
+    loop {
+        let line_start = raw_resp.len();
+        if reader.read_until(b'\n', raw_resp).await? == 0 {
+            return Err(api_utils::unexpected_eof(
+                "Connection closed before the last chunk",
+            ));
+        }
+
+        let size = api_utils::parse_chunk_size_line(&raw_resp[line_start..])?;
+        if size == 0 {
+            // Skip over the trailer up to the final empty line.
+            loop {
+                let line_start = raw_resp.len();
+                if reader.read_until(b'\n', raw_resp).await? == 0
+                    || api_utils::is_empty_line(&raw_resp[line_start..])
+                {
+                    return Ok(());
+                }
+            }
+        }
+
+        // Chunk data followed by its CRLF.
+        read_exact_len(reader, size as u64 + 2, raw_resp).await?;
+        api_utils::check_chunk_end(&raw_resp[raw_resp.len() - 2..])?;
+    }
+}
+
+async fn read_exact_len<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    len: u64,
+    raw_resp: &mut Vec<u8>,
+) -> io::Result<()> {
+    let read = reader.take(len).read_to_end(raw_resp).await?;
+    if (read as u64) < len {
+        return Err(api_utils::unexpected_eof(
+            "Connection closed before the end of the response body",
+        ));
+    }
+
+    Ok(())
+}
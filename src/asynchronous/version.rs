@@ -0,0 +1,69 @@
+use async_trait::async_trait;
+use serde_json;
+
+use crate::api::request::Request;
+use crate::api::version::{PingInfo, SystemInfo, VersionInfo};
+use crate::asynchronous::AsyncDockerApiClient;
+use crate::errors::DockerApiError;
+
+/// Asynchronous counterpart of `api::version::Version`.
+#[async_trait]
+pub trait AsyncVersion: AsyncDockerApiClient {
+    /// Get version info for Docker
+    /// Returns the JSON body of `/info` as it was sent, use `info` to get it
+    /// deserialized.
+    async fn get_version_info(&self) -> Result<String, String> {
+        match self.send_request(Request::get("/info")).await {
+            Ok(resp) => match resp.text() {
+                Ok(text) => Ok(text.to_string()),
+                Err(err) => Err(err.to_string()),
+            },
+            Err(err) => Err(err.to_string()),
+        }
+    }
+
+    /// Checks that the daemon answers, see `api::version::Version::ping`.
+    async fn ping(&self) -> Result<PingInfo, DockerApiError> {
+        let resp = self
+            .send_request_expecting(Request::get("/_ping"), 200)
+            .await?;
+
+        Ok(PingInfo::from_response(&resp))
+    }
+
+    /// Version of the daemon, its API and its components.
+    async fn version(&self) -> Result<VersionInfo, DockerApiError> {
+        let resp = self
+            .send_request_expecting(Request::get("/version"), 200)
+            .await?;
+        resp.json()
+    }
+
+    /// System wide information about the daemon.
+    async fn info(&self) -> Result<SystemInfo, DockerApiError> {
+        let resp = self
+            .send_request_expecting(Request::get("/info"), 200)
+            .await?;
+        resp.json()
+    }
+
+    /// Highest API version supported by the daemon, see
+    /// `api::version::Version::get_api_version`.
+    async fn get_api_version(&self) -> Result<String, DockerApiError> {
+        let resp = self.send_request(Request::get("/_ping")).await?;
+        if let Some(version) = PingInfo::from_response(&resp).api_version {
+            return Ok(version);
+        }
+
+        let resp = self
+            .send_request_expecting(Request::get("/version"), 200)
+            .await?;
+        let version: serde_json::Value = resp.json()?;
+        match version["ApiVersion"].as_str() {
+            Some(version) => Ok(version.to_string()),
+            None => Err(DockerApiError::HTTPResponseParseError(
+                "No ApiVersion in the version of the daemon",
+            )),
+        }
+    }
+}
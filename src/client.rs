@@ -1,38 +1,163 @@
 //! A client for communicating with the docker server
-use std::io::Read;
-use std::io::Write;
+use std::io;
+use std::io::{BufRead, BufReader};
+use std::io::{Read, Write};
 use std::os::unix::net::UnixStream;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use api::containers::Containers;
-use api::images::Images;
-use api::version::Version;
-use api::DockerApiClient;
+use crate::api::containers::Containers;
+use crate::api::images::Images;
+use crate::api::version::Version;
+use crate::api::DockerApiClient;
 
-use errors::DockerClientError;
-use utils;
+use crate::context::ContextStore;
+use crate::errors::DockerClientError;
+use crate::pool::{ConnectionPool, DEFAULT_POOL_SIZE};
+use crate::transport::{
+    self, SshConfig, SshStream, Stream, Timeouts, TlsConfig, TlsStream,
+};
+use crate::upgrade::Upgraded;
+use crate::utils;
+
+/// Default address of the docker daemon, used when `DOCKER_HOST` is not set.
+pub const DEFAULT_DOCKER_HOST: &str = "unix:///var/run/docker.sock";
 
 /// A structure defining a Client to interact with the docker API
 ///
-/// * unix_socket: UnixStream connection for docker socket.
+/// The client can be shared between threads, each request checks a
+/// connection out of a bounded pool of keep-alive connections to the
+/// daemon. Clones of the client share the same pool.
+///
 /// * protocol: Underlying protocol we are using(UNIX by default.)
+/// * api_version: API version requests are pinned to, if any.
+/// * timeouts: Timeouts applied to every request.
+/// * pool: Connections to the docker daemon.
+#[derive(Clone)]
 pub struct DockerClient {
-    socket: UnixStream,
     protocol: ConnectionProtocol,
+    api_version: Option<String>,
+    timeouts: Timeouts,
+    pool: Arc<ConnectionPool>,
 }
 
+/// Protocol used to reach the daemon, along with everything needed to open
+/// new connections to it.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Clone, Debug)]
 enum ConnectionProtocol {
-    UNIX,
+    UNIX(String),
+    TCP(String),
+    TLS(String, TlsConfig),
+    SSH(Option<String>, String, Option<u16>, SshConfig),
+}
+
+impl ConnectionProtocol {
+    /// Opens a new connection to the docker daemon, giving up after
+    /// `timeout` if provided.
+    fn connect(
+        &self,
+        timeout: Option<Duration>,
+    ) -> Result<Box<dyn Stream>, DockerClientError> {
+        match *self {
+            ConnectionProtocol::UNIX(ref path) => {
+                match UnixStream::connect(path) {
+                    Ok(sock) => Ok(Box::new(sock)),
+                    Err(_err) => Err(DockerClientError::SocketConnectionError(
+                        path.to_string(),
+                    )),
+                }
+            }
+            ConnectionProtocol::TCP(ref addr) => {
+                Ok(Box::new(transport::connect_tcp(addr, timeout)?))
+            }
+            ConnectionProtocol::TLS(ref addr, ref tls_config) => {
+                let tcp_socket = transport::connect_tcp(addr, timeout)?;
+                Ok(Box::new(TlsStream::connect(addr, tcp_socket, tls_config)?))
+            }
+            ConnectionProtocol::SSH(ref user, ref host, port, ref config) => {
+                let user = user.as_ref().map(|u| u.as_str());
+                Ok(Box::new(SshStream::connect(user, host, port, config)?))
+            }
+        }
+    }
+}
+
+/// A connection checked out of the pool, applying the read timeout and the
+/// deadline of the request to every read and write on it.
+struct TimedStream {
+    inner: Box<dyn Stream>,
+    read_timeout: Option<Duration>,
+    deadline: Option<Instant>,
+}
+
+impl TimedStream {
+    fn arm(&mut self) -> io::Result<()> {
+        let timeout = match self.deadline {
+            Some(deadline) => {
+                let now = Instant::now();
+                if now >= deadline {
+                    return Err(io::Error::new(
+                        io::ErrorKind::TimedOut,
+                        "Request timed out",
+                    ));
+                }
+
+                let remaining = deadline - now;
+                Some(self.read_timeout.map_or(remaining, |t| t.min(remaining)))
+            }
+            None => self.read_timeout,
+        };
+
+        self.inner.set_timeout(timeout)
+    }
+}
+
+impl Read for TimedStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.arm()?;
+        self.inner.read(buf)
+    }
+}
+
+impl Write for TimedStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.arm()?;
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
 }
 
 impl DockerClient {
-    /// Creates a new DockerClient object connected to docker's unix domain socket.
-    pub fn new(
-        connection_addr: &'static str,
+    /// Creates a new DockerClient object connected to the docker daemon.
+    ///
+    /// The address can either be a unix domain socket address, a TCP
+    /// address or an SSH address to the daemon.
+    ///
+    /// * unix:///var/run/docker.sock
+    /// * tcp://127.0.0.1:2375
+    /// * ssh://user@docker-host
+    pub fn new<S: Into<String>>(
+        connection_addr: S,
     ) -> Result<DockerClient, DockerClientError> {
+        let connection_addr = connection_addr.into();
+        if connection_addr.starts_with("tcp://") {
+            return DockerClient::new_tcp(&connection_addr);
+        }
+        if connection_addr.starts_with("ssh://") {
+            return DockerClient::new_ssh(
+                connection_addr,
+                SshConfig::default(),
+            );
+        }
+
         // Check if the provided unix socket address is valid and return
         // components for the socket.
         let addr_components =
-            match utils::api::validate_unix_socket_address(connection_addr) {
+            match utils::api::validate_unix_socket_address(&connection_addr) {
                 Some(addr_comps) => addr_comps,
                 None => {
                     return Err(DockerClientError::InvalidTargetAddress(
@@ -41,19 +166,9 @@ impl DockerClient {
                 }
             };
 
-        // Try connecting to the docker socket address
-        let unix_socket = match UnixStream::connect(addr_components[1]) {
-            Ok(sock) => sock,
-            Err(_err) => {
-                return Err(DockerClientError::SocketConnectionError(
-                    addr_components[1],
-                ))
-            }
-        };
-
         // Check if the protocol is unix or not.
         let protocol = match addr_components[0] {
-            "unix" => ConnectionProtocol::UNIX,
+            "unix" => ConnectionProtocol::UNIX(addr_components[1].to_string()),
             _ => {
                 return Err(DockerClientError::InvalidTargetAddress(
                     connection_addr,
@@ -61,72 +176,480 @@ impl DockerClient {
             }
         };
 
-        let docker_client = DockerClient {
-            socket: unix_socket,
-            protocol: protocol,
+        // Try connecting to the docker socket address
+        DockerClient::connect(protocol)
+    }
+
+    /// Creates a new DockerClient object connected to a docker daemon
+    /// listening on a TCP address like `tcp://127.0.0.1:2375`.
+    fn new_tcp(
+        connection_addr: &str,
+    ) -> Result<DockerClient, DockerClientError> {
+        let addr = DockerClient::validate_tcp_address(connection_addr)?;
+        DockerClient::connect(ConnectionProtocol::TCP(addr))
+    }
+
+    /// Creates a new DockerClient object connected to a docker daemon
+    /// listening on a TLS secured TCP address like `tcp://127.0.0.1:2376`.
+    ///
+    /// The daemon certificate is verified against the CA in the provided
+    /// TlsConfig, and the client certificate is presented to the daemon.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// extern crate rust_docker;
+    ///
+    /// use rust_docker::client::DockerClient;
+    /// use rust_docker::transport::TlsConfig;
+    ///
+    /// let tls = TlsConfig::from_cert_path("/home/user/.docker");
+    /// let client = match DockerClient::new_tls("tcp://docker-host:2376", tls) {
+    ///     Ok(a) => a,
+    ///     Err(err) => {
+    ///         println!("{}", err);
+    ///         std::process::exit(1);
+    ///     }
+    /// };
+    /// ```
+    pub fn new_tls<S: Into<String>>(
+        connection_addr: S,
+        tls_config: TlsConfig,
+    ) -> Result<DockerClient, DockerClientError> {
+        let addr = DockerClient::validate_tcp_address(&connection_addr.into())?;
+        DockerClient::connect(ConnectionProtocol::TLS(addr, tls_config))
+    }
+
+    /// Creates a new DockerClient object connected to a remote docker daemon
+    /// over SSH, with an address like `ssh://user@docker-host:2222`.
+    ///
+    /// The ssh client is spawned running `docker system dial-stdio` on the
+    /// remote host, so docker must be installed there and the user must be
+    /// allowed to reach the daemon.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// extern crate rust_docker;
+    ///
+    /// use rust_docker::client::DockerClient;
+    /// use rust_docker::transport::SshConfig;
+    ///
+    /// let ssh = SshConfig::default().option("-i").option("/home/user/.ssh/ci");
+    /// let client = match DockerClient::new_ssh("ssh://ci@build-host", ssh) {
+    ///     Ok(a) => a,
+    ///     Err(err) => {
+    ///         println!("{}", err);
+    ///         std::process::exit(1);
+    ///     }
+    /// };
+    /// ```
+    pub fn new_ssh<S: Into<String>>(
+        connection_addr: S,
+        ssh_config: SshConfig,
+    ) -> Result<DockerClient, DockerClientError> {
+        let connection_addr = connection_addr.into();
+        let (user, host, port) =
+            match utils::api::validate_ssh_address(&connection_addr) {
+                Some(addr_comps) => addr_comps,
+                None => {
+                    return Err(DockerClientError::InvalidTargetAddress(
+                        connection_addr,
+                    ))
+                }
+            };
+
+        DockerClient::connect(ConnectionProtocol::SSH(
+            user.map(|u| u.to_string()),
+            host.to_string(),
+            port,
+            ssh_config,
+        ))
+    }
+
+    /// Creates a new DockerClient object from the environment, the same way
+    /// the docker CLI does.
+    ///
+    /// * DOCKER_HOST: Address of the daemon, defaults to the local unix socket.
+    ///   `ssh://` hosts use the default SshConfig.
+    /// * DOCKER_API_VERSION: Pin requests to this API version.
+    /// * DOCKER_CERT_PATH: Directory with `ca.pem`, `cert.pem` and `key.pem`,
+    ///   defaults to `~/.docker`.
+    /// * DOCKER_TLS_VERIFY: When set to a non-empty value TLS is used and the
+    ///   daemon certificate is verified.
+    /// * DOCKER_TLS: When set to a non-empty value TLS is used without
+    ///   verifying the daemon certificate.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// extern crate rust_docker;
+    ///
+    /// use rust_docker::client::DockerClient;
+    ///
+    /// let client = match DockerClient::from_env() {
+    ///     Ok(a) => a,
+    ///     Err(err) => {
+    ///         println!("{}", err);
+    ///         std::process::exit(1);
+    ///     }
+    /// };
+    /// ```
+    pub fn from_env() -> Result<DockerClient, DockerClientError> {
+        let tls_verify =
+            utils::env::get_non_empty("DOCKER_TLS_VERIFY").is_some();
+        let use_tls =
+            tls_verify || utils::env::get_non_empty("DOCKER_TLS").is_some();
+
+        let host = utils::env::get_non_empty("DOCKER_HOST")
+            .unwrap_or_else(|| DEFAULT_DOCKER_HOST.to_string());
+        let host = utils::env::with_default_tcp_port(&host, use_tls);
+
+        let client = if use_tls && host.starts_with("tcp://") {
+            let tls_config =
+                TlsConfig::from_cert_path(utils::env::docker_cert_path())
+                    .verify_peer(tls_verify);
+            DockerClient::new_tls(host, tls_config)?
+        } else {
+            DockerClient::new(host)?
         };
 
-        Ok(docker_client)
+        match utils::env::get_non_empty("DOCKER_API_VERSION") {
+            Some(version) => client.with_api_version(version),
+            None => Ok(client),
+        }
+    }
+
+    /// Creates a new DockerClient object for the docker context currently in
+    /// use by the docker CLI, see `context::ContextStore::current`.
+    pub fn from_current_context() -> Result<DockerClient, DockerClientError> {
+        ContextStore::from_env().current()?.client()
     }
-}
 
-/// Implement clone for the DockerClient structure.
-/// The clone here is not true clone, the unix_socket cloned
-/// still refers to the stream and change to one of the two will
-/// propogate the changes to other.
-impl Clone for DockerClient {
-    fn clone(&self) -> DockerClient {
-        let sock = self
-            .socket
-            .try_clone()
-            .expect("Error while trying to clone the socket");
-
-        let protocol = match self.protocol {
-            ConnectionProtocol::UNIX => ConnectionProtocol::UNIX,
+    /// Pins every request made by the client to the provided API version,
+    /// like `1.37`, instead of letting the daemon pick its default.
+    pub fn with_api_version<S: Into<String>>(
+        mut self,
+        version: S,
+    ) -> Result<DockerClient, DockerClientError> {
+        let version = version.into();
+        match utils::api::validate_api_version(&version) {
+            Some(v) => self.api_version = Some(v.to_string()),
+            None => return Err(DockerClientError::InvalidApiVersion(version)),
+        };
+
+        Ok(self)
+    }
+
+    /// Pins every request made by the client to the highest API version
+    /// supported by both the daemon and the client, the same way the docker
+    /// CLI does. Versions from `api_utils::MIN_API_VERSION` up to
+    /// `api_utils::MAX_API_VERSION` are supported.
+    ///
+    /// The daemon is pinged to find out the API version it supports, which
+    /// fails with `DockerClientError::ApiVersionNegotiationError` if it can't
+    /// be reached or is too old.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// extern crate rust_docker;
+    ///
+    /// use rust_docker::client::DockerClient;
+    ///
+    /// let client = DockerClient::new("unix:///var/run/docker.sock")
+    ///     .and_then(|client| client.negotiate_api_version())
+    ///     .unwrap();
+    /// ```
+    pub fn negotiate_api_version(
+        mut self,
+    ) -> Result<DockerClient, DockerClientError> {
+        // Ping the daemon without any version, it may not support ours.
+        self.api_version = None;
+        let daemon_version = match self.get_api_version() {
+            Ok(version) => version,
+            Err(err) => {
+                return Err(DockerClientError::ApiVersionNegotiationError(
+                    err.to_string(),
+                ))
+            }
         };
 
-        let docker_client_clone = DockerClient {
-            socket: sock,
-            protocol: protocol,
+        match utils::api::negotiate_api_version(&daemon_version) {
+            Some(version) => self.api_version = Some(version),
+            None => {
+                return Err(DockerClientError::ApiVersionNegotiationError(
+                    format!(
+                        "the daemon API version `{}` is not supported",
+                        daemon_version
+                    ),
+                ))
+            }
         };
 
-        return docker_client_clone;
+        Ok(self)
+    }
+
+    /// Applies the provided timeouts to every request made by the client,
+    /// expired requests fail with `DockerApiError::Timeout`.
+    ///
+    /// Long running requests can override them with
+    /// `get_response_from_api_with_timeouts`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// extern crate rust_docker;
+    ///
+    /// use std::time::Duration;
+    ///
+    /// use rust_docker::client::DockerClient;
+    /// use rust_docker::transport::Timeouts;
+    ///
+    /// let timeouts = Timeouts::default()
+    ///     .connect(Some(Duration::from_secs(5)))
+    ///     .request(Some(Duration::from_secs(30)));
+    /// let client = DockerClient::new("unix:///var/run/docker.sock")
+    ///     .unwrap()
+    ///     .with_timeouts(timeouts);
+    /// ```
+    pub fn with_timeouts(mut self, timeouts: Timeouts) -> DockerClient {
+        self.timeouts = timeouts;
+        self
+    }
+
+    /// Timeouts applied to every request made by the client.
+    pub fn timeouts(&self) -> Timeouts {
+        self.timeouts
+    }
+
+    /// Limits the number of connections the client opens to the daemon at
+    /// once(`pool::DEFAULT_POOL_SIZE` by default.) Requests wait for a
+    /// connection to be checked back in once the limit is reached.
+    ///
+    /// The pool is shared with the clones of the client, so the limit applies
+    /// to all of them.
+    pub fn with_pool_size(self, max_size: usize) -> DockerClient {
+        self.pool.set_max_size(max_size);
+        self
+    }
+
+    /// Opens a first connection to the daemon, so that an unreachable daemon
+    /// is reported right away, and keeps it in the pool for the first request.
+    fn connect(
+        protocol: ConnectionProtocol,
+    ) -> Result<DockerClient, DockerClientError> {
+        let pool = ConnectionPool::new(DEFAULT_POOL_SIZE);
+        pool.add(protocol.connect(None)?);
+
+        Ok(DockerClient {
+            protocol,
+            api_version: None,
+            timeouts: Timeouts::default(),
+            pool: Arc::new(pool),
+        })
+    }
+
+    /// Validates the `tcp://` address and returns its `host:port` part.
+    fn validate_tcp_address(
+        connection_addr: &str,
+    ) -> Result<String, DockerClientError> {
+        match utils::api::validate_tcp_socket_address(connection_addr) {
+            Some(addr_comps) => Ok(addr_comps[1].to_string()),
+            None => Err(DockerClientError::InvalidTargetAddress(
+                connection_addr.to_string(),
+            )),
+        }
+    }
+
+    /// Sends the request over a connection checked out of the pool and reads
+    /// the response back. The connection goes back to the pool if the daemon
+    /// kept it alive, and is closed otherwise.
+    fn send(
+        &self,
+        mut stream: TimedStream,
+        request: &[u8],
+    ) -> Result<Vec<u8>, SendError> {
+        if let Err(err) = stream.write_all(request) {
+            self.pool.discard();
+            return Err(SendError::Write(err));
+        }
+        trace!("Wrote {} bytes to the docker daemon", request.len());
+
+        // Read exactly one response, the connection is kept alive by the
+        // daemon so we can't rely on it being closed.
+        let head_request = request.starts_with(b"HEAD ");
+        let mut reader = BufReader::new(stream);
+        let started = match reader.fill_buf() {
+            Ok(buf) => !buf.is_empty(),
+            Err(err) => {
+                self.pool.discard();
+                return Err(SendError::NoResponse(err));
+            }
+        };
+        if !started {
+            self.pool.discard();
+            return Err(SendError::NoResponse(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "The connection was closed before any response",
+            )));
+        }
+
+        match utils::api::read_http_response(&mut reader, head_request) {
+            Ok((raw_resp, keep_alive)) => {
+                // Anything buffered past the response would be mistaken for
+                // the response to the next request.
+                if keep_alive && reader.buffer().is_empty() {
+                    self.pool.checkin(reader.into_inner().inner);
+                } else {
+                    self.pool.discard();
+                }
+                Ok(raw_resp)
+            }
+            Err(err) => {
+                self.pool.discard();
+                Err(SendError::Read(err))
+            }
+        }
+    }
+}
+
+/// How far a request went before its connection failed, which tells whether
+/// it can be sent again.
+pub(crate) enum SendError {
+    /// The request could not be written whole, the daemon can't have acted
+    /// on it.
+    Write(io::Error),
+    /// The connection failed before any byte of the response was read, the
+    /// daemon may have acted on the request.
+    NoResponse(io::Error),
+    /// The response could not be read.
+    Read(io::Error),
+}
+
+impl SendError {
+    /// Whether the request can be sent again on another connection, after
+    /// the daemon closed the pooled connection it was sent on.
+    pub(crate) fn can_retry(&self, request: &[u8]) -> bool {
+        match *self {
+            SendError::Write(ref err) => is_stale_connection(err),
+            SendError::NoResponse(ref err) => {
+                is_stale_connection(err) && is_idempotent(request)
+            }
+            SendError::Read(_) => false,
+        }
+    }
+
+    pub(crate) fn into_inner(self) -> io::Error {
+        match self {
+            SendError::Write(err)
+            | SendError::NoResponse(err)
+            | SendError::Read(err) => err,
+        }
     }
 }
 
 impl DockerApiClient for DockerClient {
-    fn request(&self, request: &str) -> Option<Vec<u8>> {
-        let mut client = self.socket.try_clone().unwrap();
+    fn api_version(&self) -> Option<String> {
+        self.api_version.clone()
+    }
 
-        let buf = request.as_bytes();
-        match client.write_all(buf) {
-            Ok(_) => println!("Wrote all data to socket"),
-            Err(_) => return None,
-        };
+    fn request(&self, request: &[u8]) -> Option<Vec<u8>> {
+        self.request_with_timeouts(request, None).ok()
+    }
+
+    fn request_with_timeouts(
+        &self,
+        request: &[u8],
+        timeouts: Option<Timeouts>,
+    ) -> io::Result<Vec<u8>> {
+        let timeouts = timeouts.unwrap_or(self.timeouts);
+        let deadline = timeouts.request.map(|timeout| Instant::now() + timeout);
 
-        // Can't figure out why read_to_end was not working here. :/
-        const BUFFER_SIZE: usize = 1024;
-        let mut buffer: [u8; BUFFER_SIZE] = [0; BUFFER_SIZE];
-        let mut raw_resp: Vec<u8> = Vec::new();
         loop {
-            let len = match client.read(&mut buffer) {
-                Ok(len) => len,
-                Err(_) => return None,
+            let checkout = self
+                .pool
+                .checkout(|| self.protocol.connect(timeouts.connect));
+            let (stream, reused) = match checkout {
+                Ok(checkout) => checkout,
+                Err(DockerClientError::ConnectionTimeout(addr)) => {
+                    return Err(io::Error::new(io::ErrorKind::TimedOut, addr))
+                }
+                Err(err) => return Err(io::Error::other(err.to_string())),
             };
 
-            for i in 0..len {
-                raw_resp.push(buffer[i]);
+            let stream = TimedStream {
+                inner: stream,
+                read_timeout: timeouts.read,
+                deadline,
+            };
+            match self.send(stream, request) {
+                Ok(raw_resp) => return Ok(raw_resp),
+                // The daemon may have closed the idle connection in the
+                // meantime, try again on another one unless the request may
+                // have been acted on.
+                Err(err) if reused && err.can_retry(request) => {
+                    let err = err.into_inner();
+                    debug!("Pooled connection was closed ({}), retrying", err);
+                    continue;
+                }
+                Err(err) => return Err(err.into_inner()),
             }
+        }
+    }
 
-            if len < BUFFER_SIZE {
-                break;
+    /// Upgraded connections are opened aside from the pool, so they don't
+    /// count towards its size. The request timeout only applies until the
+    /// connection is upgraded, the read timeout applies for as long as it is
+    /// used, see `Upgraded::set_timeout`.
+    fn request_upgrade(
+        &self,
+        request: &[u8],
+        timeouts: Option<Timeouts>,
+    ) -> io::Result<Upgraded> {
+        let timeouts = timeouts.unwrap_or(self.timeouts);
+        let mut stream = match self.protocol.connect(timeouts.connect) {
+            Ok(stream) => stream,
+            Err(DockerClientError::ConnectionTimeout(addr)) => {
+                return Err(io::Error::new(io::ErrorKind::TimedOut, addr))
             }
-        }
+            Err(err) => return Err(io::Error::other(err.to_string())),
+        };
+
+        let handshake_timeout = match (timeouts.read, timeouts.request) {
+            (Some(read), Some(request)) => Some(read.min(request)),
+            (read, request) => read.or(request),
+        };
+        stream.set_timeout(handshake_timeout)?;
+        stream.write_all(request)?;
+        trace!("Wrote {} bytes to upgrade a connection", request.len());
 
-        Some(raw_resp)
+        let upgraded = Upgraded::read_response(stream)?;
+        upgraded.set_timeout(timeouts.read)?;
+        Ok(upgraded)
     }
 }
 
+/// Errors hit when using a connection the daemon already closed.
+fn is_stale_connection(err: &io::Error) -> bool {
+    matches!(
+        err.kind(),
+        io::ErrorKind::UnexpectedEof
+            | io::ErrorKind::BrokenPipe
+            | io::ErrorKind::ConnectionReset
+            | io::ErrorKind::ConnectionAborted
+    )
+}
+
+/// Whether sending the raw request twice has the same effect as sending it
+/// once, like GET or DELETE requests. Requests like creating or starting a
+/// container are never sent again.
+fn is_idempotent(request: &[u8]) -> bool {
+    [&b"GET "[..], b"HEAD ", b"PUT ", b"DELETE "]
+        .iter()
+        .any(|method| request.starts_with(method))
+}
+
 impl Version for DockerClient {}
 impl Containers for DockerClient {}
 impl Images for DockerClient {}
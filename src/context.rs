@@ -0,0 +1,310 @@
+//! Support for the docker CLI context store.
+//!
+//! The docker CLI keeps the endpoints it can talk to as named contexts in
+//! its configuration directory(`~/.docker` by default.)
+//!
+//! * config.json: The `currentContext` in use.
+//! * contexts/meta/<sha256 of name>/meta.json: Endpoint of each context.
+//! * contexts/tls/<sha256 of name>/docker: TLS material of each context.
+//!
+//! The `default` context is not stored anywhere, it is the daemon described
+//! by the environment(`DOCKER_HOST` and friends.)
+#![allow(non_snake_case)]
+use std::collections::HashMap;
+use std::fs;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use openssl::sha::sha256;
+use serde::de::DeserializeOwned;
+use serde_json;
+
+use crate::client::{DockerClient, DEFAULT_DOCKER_HOST};
+use crate::errors::DockerClientError;
+use crate::transport::TlsConfig;
+use crate::utils;
+
+/// Name of the implicit context described by the environment.
+pub const DEFAULT_CONTEXT: &str = "default";
+
+#[derive(Deserialize, Debug, Default)]
+struct CliConfig {
+    #[serde(default)]
+    currentContext: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ContextMetadata {
+    Name: String,
+    #[serde(default)]
+    Metadata: Option<ContextDescription>,
+    #[serde(default)]
+    Endpoints: HashMap<String, ContextEndpoint>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ContextDescription {
+    #[serde(default)]
+    Description: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ContextEndpoint {
+    #[serde(default)]
+    Host: Option<String>,
+    #[serde(default)]
+    SkipTLSVerify: bool,
+}
+
+/// A docker context resolved from the context store.
+///
+/// * name: Name of the context.
+/// * description: Description given to the context, if any.
+/// * host: Address of the docker endpoint of the context.
+/// * tls: TLS material stored for the context, if any.
+#[derive(Clone, Debug)]
+pub struct DockerContext {
+    pub name: String,
+    pub description: Option<String>,
+    pub host: String,
+    pub tls: Option<TlsConfig>,
+}
+
+impl DockerContext {
+    /// Creates a new DockerClient connected to the docker endpoint of the
+    /// context.
+    ///
+    /// The `default` context is built from the environment, exactly like
+    /// `DockerClient::from_env`.
+    pub fn client(&self) -> Result<DockerClient, DockerClientError> {
+        if self.name == DEFAULT_CONTEXT {
+            return DockerClient::from_env();
+        }
+
+        let use_tls = self.tls.is_some();
+        let host = utils::env::with_default_tcp_port(&self.host, use_tls);
+        match self.tls {
+            Some(ref tls) if host.starts_with("tcp://") => {
+                DockerClient::new_tls(host, tls.clone())
+            }
+            _ => DockerClient::new(host),
+        }
+    }
+}
+
+/// The context store of the docker CLI.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// extern crate rust_docker;
+///
+/// use rust_docker::context::ContextStore;
+///
+/// let store = ContextStore::from_env();
+/// for context in store.list().unwrap() {
+///     println!("{} : {}", context.name, context.host);
+/// }
+///
+/// // Connect to the same daemon `docker context use` has selected.
+/// let client = store.current().unwrap().client().unwrap();
+/// ```
+pub struct ContextStore {
+    config_dir: PathBuf,
+}
+
+impl ContextStore {
+    /// Creates a new ContextStore for the provided docker configuration
+    /// directory.
+    pub fn new<P: AsRef<Path>>(config_dir: P) -> ContextStore {
+        ContextStore {
+            config_dir: config_dir.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Creates a new ContextStore for the configuration directory used by
+    /// the docker CLI, `DOCKER_CONFIG` if set and `~/.docker` otherwise.
+    pub fn from_env() -> ContextStore {
+        ContextStore::new(utils::env::docker_config_dir())
+    }
+
+    /// Name of the context in use, resolved in the same order as the
+    /// docker CLI does.
+    ///
+    /// * DOCKER_CONTEXT, if set.
+    /// * `default` if DOCKER_HOST is set.
+    /// * `currentContext` from config.json.
+    /// * `default` otherwise.
+    pub fn current_context_name(&self) -> Result<String, DockerClientError> {
+        if let Some(name) = utils::env::get_non_empty("DOCKER_CONTEXT") {
+            return Ok(name);
+        }
+
+        if utils::env::get_non_empty("DOCKER_HOST").is_some() {
+            return Ok(DEFAULT_CONTEXT.to_string());
+        }
+
+        let config_path = self.config_dir.join("config.json");
+        if !config_path.is_file() {
+            return Ok(DEFAULT_CONTEXT.to_string());
+        }
+
+        let config: CliConfig = read_json(&config_path)?;
+        match config.currentContext {
+            Some(ref name) if !name.is_empty() => Ok(name.to_string()),
+            _ => Ok(DEFAULT_CONTEXT.to_string()),
+        }
+    }
+
+    /// Returns the context in use.
+    pub fn current(&self) -> Result<DockerContext, DockerClientError> {
+        let name = self.current_context_name()?;
+        self.get(&name)
+    }
+
+    /// Returns the context with the provided name.
+    pub fn get(&self, name: &str) -> Result<DockerContext, DockerClientError> {
+        if name == DEFAULT_CONTEXT {
+            return Ok(ContextStore::default_context());
+        }
+
+        let meta_path = self
+            .config_dir
+            .join("contexts")
+            .join("meta")
+            .join(context_dir_name(name))
+            .join("meta.json");
+        if !meta_path.is_file() {
+            return Err(DockerClientError::ContextNotFound(name.to_string()));
+        }
+
+        let meta: ContextMetadata = read_json(&meta_path)?;
+        self.context_from_metadata(meta)
+    }
+
+    /// Lists all the contexts in the store, starting with the `default`
+    /// one, like `docker context ls`.
+    pub fn list(&self) -> Result<Vec<DockerContext>, DockerClientError> {
+        let mut contexts = Vec::new();
+
+        let meta_dir = self.config_dir.join("contexts").join("meta");
+        if meta_dir.is_dir() {
+            let entries =
+                match fs::read_dir(&meta_dir) {
+                    Ok(entries) => entries,
+                    Err(err) => {
+                        return Err(DockerClientError::ContextStoreError(
+                            format!("{} : {}", meta_dir.display(), err),
+                        ))
+                    }
+                };
+
+            for entry in entries {
+                let meta_path = match entry {
+                    Ok(entry) => entry.path().join("meta.json"),
+                    Err(_) => continue,
+                };
+                if !meta_path.is_file() {
+                    continue;
+                }
+
+                let meta: ContextMetadata = read_json(&meta_path)?;
+                contexts.push(self.context_from_metadata(meta)?);
+            }
+        }
+
+        contexts.sort_by(|a, b| a.name.cmp(&b.name));
+        contexts.insert(0, ContextStore::default_context());
+
+        Ok(contexts)
+    }
+
+    fn default_context() -> DockerContext {
+        let host = utils::env::get_non_empty("DOCKER_HOST")
+            .unwrap_or_else(|| DEFAULT_DOCKER_HOST.to_string());
+
+        DockerContext {
+            name: DEFAULT_CONTEXT.to_string(),
+            description: Some("Current DOCKER_HOST based configuration".into()),
+            host,
+            tls: None,
+        }
+    }
+
+    fn context_from_metadata(
+        &self,
+        meta: ContextMetadata,
+    ) -> Result<DockerContext, DockerClientError> {
+        let endpoint = match meta.Endpoints.get("docker") {
+            Some(endpoint) => endpoint,
+            None => {
+                return Err(DockerClientError::ContextStoreError(format!(
+                    "context `{}` has no docker endpoint",
+                    meta.Name
+                )))
+            }
+        };
+
+        let host = match endpoint.Host {
+            Some(ref host) if !host.is_empty() => host.to_string(),
+            _ => DEFAULT_DOCKER_HOST.to_string(),
+        };
+
+        let tls_dir = self
+            .config_dir
+            .join("contexts")
+            .join("tls")
+            .join(context_dir_name(&meta.Name))
+            .join("docker");
+        // Contexts of daemons which don't authenticate their clients only
+        // hold the CA certificate.
+        let tls = if !tls_dir.is_dir() {
+            None
+        } else if tls_dir.join("cert.pem").exists()
+            && tls_dir.join("key.pem").exists()
+        {
+            Some(TlsConfig::from_cert_path(&tls_dir))
+        } else {
+            Some(TlsConfig::from_ca(tls_dir.join("ca.pem")))
+        };
+        let tls = tls.map(|tls| tls.verify_peer(!endpoint.SkipTLSVerify));
+
+        Ok(DockerContext {
+            description: meta.Metadata.and_then(|m| m.Description),
+            name: meta.Name,
+            host,
+            tls,
+        })
+    }
+}
+
+/// The context store keeps each context in a directory named after the hex
+/// encoded SHA-256 digest of the context name.
+pub fn context_dir_name(name: &str) -> String {
+    sha256(name.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T, DockerClientError> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(err) => {
+            return Err(DockerClientError::ContextStoreError(format!(
+                "{} : {}",
+                path.display(),
+                err
+            )))
+        }
+    };
+
+    match serde_json::from_reader(file) {
+        Ok(val) => Ok(val),
+        Err(err) => Err(DockerClientError::ContextStoreError(format!(
+            "{} : {}",
+            path.display(),
+            err
+        ))),
+    }
+}
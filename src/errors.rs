@@ -1,66 +1,229 @@
+use std::io;
+
 use serde_json;
 
+use crate::api::request::Method;
+use crate::utils::api::Response;
+
 quick_error! {
     #[derive(Debug)]
     pub enum DockerClientError {
-        InvalidTargetAddress(addr: &'static str) {
-            description("The target address is not valid")
+        /// The target address is not valid.
+        InvalidTargetAddress(addr: String) {
             display("The target address `{}` is not valid", addr)
         }
 
-        SocketConnectionError(addr: &'static str) {
-            description("Could not connect to docker socket.")
+        /// Could not connect to docker socket.
+        SocketConnectionError(addr: String) {
             display("Error while connection to docker socket at {}", addr)
         }
+
+        /// The provided TLS configuration is not valid.
+        TlsConfigError(msg: String) {
+            display("Invalid TLS configuration : {}", msg)
+        }
+
+        /// TLS handshake with docker daemon failed.
+        TlsHandshakeError(addr: String, msg: String) {
+            display("TLS handshake with {} failed : {}", addr, msg)
+        }
+
+        /// Could not verify the docker daemon certificate.
+        TlsVerificationError(addr: String, msg: String) {
+            display("Certificate verification for {} failed : {}", addr, msg)
+        }
+
+        /// Could not run the ssh client.
+        SshCommandError(cmd: String, msg: String) {
+            display("Error while running ssh client `{}` : {}", cmd, msg)
+        }
+
+        /// The requested API version is not valid.
+        InvalidApiVersion(version: String) {
+            display("The API version `{}` is not valid", version)
+        }
+
+        /// Could not negotiate the API version with the daemon.
+        ApiVersionNegotiationError(msg: String) {
+            display("Could not negotiate the API version : {}", msg)
+        }
+
+        /// The docker context does not exist.
+        ContextNotFound(name: String) {
+            display("The docker context `{}` does not exist", name)
+        }
+
+        /// Timed out while connecting to the docker daemon.
+        ConnectionTimeout(addr: String) {
+            display("Timed out while connecting to : {}", addr)
+        }
+
+        /// Error while reading the docker context store.
+        ContextStoreError(msg: String) {
+            display("Error while reading the docker context store : {}", msg)
+        }
     }
 }
 
 quick_error! {
     #[derive(Debug)]
     pub enum DockerApiError {
+        /// Provided parameters are not valid.
         MismatchedParametersError(msg: &'static str) {
-            description("Provided parameters are not valid")
             display("Malformed parameters : {}", msg)
         }
 
+        /// Provided parameters are not valid.
+        InvalidParametersError(msg: String) {
+            display("Invalid parameters : {}", msg)
+        }
+
+        /// Error while serializing JSON.
         JsonSerializationError(err: serde_json::Error) {
-            description("Error while serializing JSON")
+            source(err)
             display("JSON Serialization error : {}", err)
         }
 
+        /// Error while deserializing JSON.
         JsonDeserializationError(err: serde_json::Error) {
-            description("Error while deserializing JSON")
+            source(err)
             display("JSON Deserialization error : {}", err)
         }
 
+        /// An error occured while preparing request.
         RequestPrepareError(_err: &'static str) {
-            description("An error occured while preparing request")
             display("Error while preparing request")
         }
 
+        /// Request not handled properly.
         RequestError(msg: &'static str) {
-            description("Request not handled properly")
             display("RequestError : {}", msg)
         }
 
+        /// Error while parsing response.
         HTTPResponseParseError(err: &'static str) {
-            description("Error while parsing response")
             display("Error while parsing response : {}", err)
         }
 
+        /// Response from Docker API is not valid.
         InvalidApiResponseError(status: usize, body: String) {
-            description("Response from Docker API is not valid")
             display("Invalid API response, status_code : {}, body: {}", status, body)
         }
 
+        /// The request to Docker API could not be handled.
         ApiRequestError(msg: &'static str) {
-            description("The request to Docker API could not be handled")
             display("Docker API server Error : {}", msg)
         }
 
+        /// The container in context faced some error.
         ContainerError(msg: String) {
-            description("The container in context faced some error")
             display("ContainerError : {}", msg)
         }
+
+        /// The request to Docker API timed out.
+        Timeout(endpoint: String) {
+            display("Request to {} timed out", endpoint)
+        }
+
+        /// No response could be read from the docker daemon.
+        ConnectionError(endpoint: String, err: io::Error) {
+            source(err)
+            display("No response from docker daemon for {} : {}", endpoint, err)
+        }
+
+        /// The response stream could not be read past the head.
+        StreamReadError(err: io::Error) {
+            source(err)
+            display("Error while reading the response stream : {}", err)
+        }
+
+        /// The daemon reported an error in the middle of a response stream,
+        /// like a failed pull, along with its code if any.
+        StreamError(code: Option<i64>, message: String) {
+            display("Error reported by the docker daemon : {}", message)
+        }
+
+        /// The daemon had nothing to do, like starting a running container.
+        NotModified(method: Method, endpoint: String, message: String) {
+            display("Not modified ({} {}) : {}", method, endpoint, message)
+        }
+
+        /// The parameters of the request were rejected by the daemon.
+        BadParameter(method: Method, endpoint: String, message: String) {
+            display("Bad parameter ({} {}) : {}", method, endpoint, message)
+        }
+
+        /// The requested object, like a container or an image, does not exist.
+        NotFound(method: Method, endpoint: String, message: String) {
+            display("Not found ({} {}) : {}", method, endpoint, message)
+        }
+
+        /// The request conflicts with the state of the object, like removing
+        /// a running container.
+        Conflict(method: Method, endpoint: String, message: String) {
+            display("Conflict ({} {}) : {}", method, endpoint, message)
+        }
+
+        /// The container can't be removed while it is running, unless the
+        /// removal is forced.
+        ContainerRunning(id: String, message: String) {
+            display("Container {} is running : {}", id, message)
+        }
+
+        /// The container does not exist.
+        NoSuchContainer(id: String, message: String) {
+            display("No such container {} : {}", id, message)
+        }
+
+        /// The daemon failed while handling the request.
+        ServerError(method: Method, endpoint: String, message: String) {
+            display("Docker daemon error ({} {}) : {}", method, endpoint, message)
+        }
+    }
+}
+
+impl DockerApiError {
+    /// Maps an unexpected response of the daemon to the matching error,
+    /// along with the `message` the daemon sent back in its JSON body.
+    ///
+    /// Statuses without a dedicated variant give
+    /// `DockerApiError::InvalidApiResponseError`.
+    pub fn from_response(
+        method: Method,
+        endpoint: &str,
+        resp: Response,
+    ) -> DockerApiError {
+        let message = error_message(&resp);
+        let endpoint = endpoint.to_string();
+
+        match resp.status_code {
+            304 => DockerApiError::NotModified(method, endpoint, message),
+            400 => DockerApiError::BadParameter(method, endpoint, message),
+            404 => DockerApiError::NotFound(method, endpoint, message),
+            409 => DockerApiError::Conflict(method, endpoint, message),
+            500..=599 => DockerApiError::ServerError(method, endpoint, message),
+            status => {
+                let body = String::from_utf8_lossy(&resp.body);
+                DockerApiError::InvalidApiResponseError(status, body.into())
+            }
+        }
+    }
+}
+
+/// Error message of a response, the daemon sends it as `{"message": ...}`
+/// but proxies in between may answer with plain text or nothing at all.
+fn error_message(resp: &Response) -> String {
+    let body = String::from_utf8_lossy(&resp.body);
+    let body = body.trim();
+    if let Ok(value) = serde_json::from_str::<serde_json::Value>(body) {
+        if let Some(message) = value["message"].as_str() {
+            return message.to_string();
+        }
+    }
+
+    if body.is_empty() {
+        format!("status code {}", resp.status_code)
+    } else {
+        body.to_string()
     }
 }
@@ -1,7 +1,14 @@
 //! docker-rs is a rust library to interact with Docker API
 //!
-//! * Currently the only method to connect to docker is through unix
-//! socket.
+//! * The client can connect to docker either through the unix socket
+//!   or over a TCP address, optionally secured with TLS.
+//! * An asynchronous client built on tokio is available with the `tokio`
+//!   cargo feature, see the `asynchronous` module.
+//! * An in-memory fake daemon to test code built on the API without docker
+//!   is available with the `testing` cargo feature, see the `testing` module.
+#[macro_use]
+extern crate log;
+
 #[macro_use]
 extern crate quick_error;
 
@@ -9,15 +16,34 @@ extern crate quick_error;
 extern crate serde_derive;
 
 extern crate flate2;
+extern crate openssl;
 extern crate serde;
 extern crate serde_json;
 extern crate tar;
 extern crate uuid;
 
+#[cfg(feature = "tokio")]
+extern crate async_trait;
+#[cfg(feature = "tokio")]
+extern crate tokio;
+
 pub mod api;
+#[cfg(feature = "tokio")]
+pub mod asynchronous;
 pub mod client;
+pub mod context;
 pub mod errors;
-pub mod file;
+pub mod logs;
+pub mod middleware;
+pub mod multiplexed;
+pub mod pool;
+pub mod progress;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod streaming;
+pub mod transport;
+pub mod upgrade;
 pub mod utils;
 
-pub use client::DockerClient;
+pub use crate::client::DockerClient;
+pub use crate::utils::file;
@@ -0,0 +1,279 @@
+//! Reading the logs of containers line by line as they arrive.
+//!
+//! `Containers::get_container_logs` returns the logs as `Logs`, which reads
+//! them off the connection as the daemon sends them. The output of
+//! containers without a TTY is demultiplexed and split in lines, the output
+//! of containers with a TTY is handed back as it arrives.
+//!
+//! Dropping `Logs` closes the connection, which is how to stop following
+//! the logs early.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! extern crate rust_docker;
+//!
+//! use rust_docker::api::containers::{Containers, LogsOptions};
+//! use rust_docker::client::DockerClient;
+//! use rust_docker::logs::LogLine;
+//!
+//! let client = DockerClient::new("unix:///var/run/docker.sock").unwrap();
+//!
+//! let options = LogsOptions::default().tail(Some(100)).follow(true);
+//! for line in client.get_container_logs("web", options).unwrap() {
+//!     match line.unwrap() {
+//!         LogLine::StdErr(data) => {
+//!             eprintln!("{}", String::from_utf8_lossy(&data))
+//!         }
+//!         line => println!("{}", String::from_utf8_lossy(line.data())),
+//!     }
+//! }
+//! ```
+use std::collections::VecDeque;
+use std::io;
+use std::io::{Chain, Cursor, Read};
+
+use crate::errors::DockerApiError;
+use crate::multiplexed::{Demultiplexer, Frame, StreamType, FRAME_HEADER_SIZE};
+
+/// Size of the chunks the output of containers with a TTY is read by.
+const RAW_CHUNK_SIZE: usize = 8192;
+
+/// A line of the logs of a container without a TTY, with its trailing
+/// newline removed, or a chunk of the output of a container with a TTY.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LogLine {
+    StdOut(Vec<u8>),
+    StdErr(Vec<u8>),
+    /// Output of a container with a TTY, where stdout and stderr can't be
+    /// told apart. It is not split in lines.
+    Console(Vec<u8>),
+}
+
+impl LogLine {
+    /// Stream the line belongs to, `None` for the output of a TTY.
+    pub fn stream(&self) -> Option<StreamType> {
+        match *self {
+            LogLine::StdOut(_) => Some(StreamType::StdOut),
+            LogLine::StdErr(_) => Some(StreamType::StdErr),
+            LogLine::Console(_) => None,
+        }
+    }
+
+    pub fn data(&self) -> &[u8] {
+        match *self {
+            LogLine::StdOut(ref data)
+            | LogLine::StdErr(ref data)
+            | LogLine::Console(ref data) => data,
+        }
+    }
+
+    pub fn into_data(self) -> Vec<u8> {
+        match self {
+            LogLine::StdOut(data)
+            | LogLine::StdErr(data)
+            | LogLine::Console(data) => data,
+        }
+    }
+}
+
+/// The bytes read to tell the output of a TTY apart, put back in front of
+/// the rest.
+type Peeked<R> = Chain<Cursor<Vec<u8>>, R>;
+
+enum Source<R> {
+    /// Nothing was read yet, the first bytes tell whether the output is
+    /// multiplexed.
+    Unknown(R),
+    Multiplexed(Demultiplexer<Peeked<R>>),
+    Raw(Peeked<R>),
+}
+
+/// Logs of a container read off any reader, like a
+/// `streaming::ResponseStream`, see the module documentation.
+///
+/// It is also an iterator over the lines, ending with the logs.
+pub struct Logs<R> {
+    source: Option<Source<R>>,
+    lines: VecDeque<LogLine>,
+    stdout: Vec<u8>,
+    stderr: Vec<u8>,
+}
+
+impl<R: Read> Logs<R> {
+    /// Creates a new Logs reading the output of a container off `reader`.
+    ///
+    /// `tty` tells whether the container has a TTY, when it is `None` the
+    /// first bytes of the output are looked at to find out. Nothing is
+    /// handed back until 8 bytes were read then.
+    pub fn new(reader: R, tty: Option<bool>) -> Logs<R> {
+        let source = match tty {
+            Some(true) => Source::Raw(Cursor::new(Vec::new()).chain(reader)),
+            Some(false) => {
+                let reader = Cursor::new(Vec::new()).chain(reader);
+                Source::Multiplexed(Demultiplexer::new(reader))
+            }
+            None => Source::Unknown(reader),
+        };
+
+        Logs {
+            source: Some(source),
+            lines: VecDeque::new(),
+            stdout: Vec::new(),
+            stderr: Vec::new(),
+        }
+    }
+
+    /// Reads the next line, or `None` once the logs ended.
+    ///
+    /// Lines are handed back as soon as their newline was read, the last
+    /// line of each stream once the logs ended. The logs end after an error
+    /// reading them.
+    pub fn next_line(&mut self) -> Result<Option<LogLine>, DockerApiError> {
+        loop {
+            if let Some(line) = self.lines.pop_front() {
+                return Ok(Some(line));
+            }
+
+            let source = match self.source.take() {
+                Some(source) => source,
+                None => return Ok(None),
+            };
+            match self.read_source(source) {
+                Ok(source) => self.source = source,
+                Err(err) => return Err(DockerApiError::StreamReadError(err)),
+            }
+        }
+    }
+
+    /// Reads from the source once, queueing the lines read. Returns the
+    /// source to read the next lines from, `None` once the logs ended.
+    fn read_source(
+        &mut self,
+        source: Source<R>,
+    ) -> io::Result<Option<Source<R>>> {
+        match source {
+            Source::Unknown(reader) => peek(reader).map(Some),
+            Source::Multiplexed(mut frames) => match frames.read_frame()? {
+                Some(Frame::StdErr(data)) => {
+                    split_lines(
+                        &mut self.stderr,
+                        &data,
+                        &mut self.lines,
+                        LogLine::StdErr,
+                    );
+                    Ok(Some(Source::Multiplexed(frames)))
+                }
+                Some(frame) => {
+                    let data = frame.into_data();
+                    split_lines(
+                        &mut self.stdout,
+                        &data,
+                        &mut self.lines,
+                        LogLine::StdOut,
+                    );
+                    Ok(Some(Source::Multiplexed(frames)))
+                }
+                None => {
+                    if !self.stdout.is_empty() {
+                        let line = self.stdout.split_off(0);
+                        self.lines.push_back(LogLine::StdOut(line));
+                    }
+                    if !self.stderr.is_empty() {
+                        let line = self.stderr.split_off(0);
+                        self.lines.push_back(LogLine::StdErr(line));
+                    }
+                    Ok(None)
+                }
+            },
+            Source::Raw(mut reader) => {
+                let mut data = vec![0; RAW_CHUNK_SIZE];
+                let len = loop {
+                    match reader.read(&mut data) {
+                        Ok(len) => break len,
+                        Err(ref err)
+                            if err.kind() == io::ErrorKind::Interrupted => {}
+                        Err(err) => return Err(err),
+                    }
+                };
+                if len == 0 {
+                    return Ok(None);
+                }
+
+                data.truncate(len);
+                self.lines.push_back(LogLine::Console(data));
+                Ok(Some(Source::Raw(reader)))
+            }
+        }
+    }
+}
+
+impl<R: Read> Iterator for Logs<R> {
+    type Item = Result<LogLine, DockerApiError>;
+
+    fn next(&mut self) -> Option<Result<LogLine, DockerApiError>> {
+        self.next_line().transpose()
+    }
+}
+
+/// Whether the container has a TTY according to the content type of its
+/// logs, daemons only tell from API 1.42.
+pub(crate) fn tty_from_content_type(
+    content_type: Option<&str>,
+) -> Option<bool> {
+    match content_type {
+        Some(t) if t.starts_with("application/vnd.docker.raw-stream") => {
+            Some(true)
+        }
+        Some(t)
+            if t.starts_with("application/vnd.docker.multiplexed-stream") =>
+        {
+            Some(false)
+        }
+        _ => None,
+    }
+}
+
+/// Reads the first bytes of the output to tell whether it is multiplexed,
+/// the output of a TTY hardly ever starting like a frame header does.
+fn peek<R: Read>(mut reader: R) -> io::Result<Source<R>> {
+    let mut header = [0; FRAME_HEADER_SIZE];
+    let mut read = 0;
+    while read < FRAME_HEADER_SIZE {
+        match reader.read(&mut header[read..]) {
+            Ok(0) => break,
+            Ok(len) => read += len,
+            Err(ref err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+
+    let multiplexed = read == FRAME_HEADER_SIZE
+        && StreamType::from_byte(header[0]).is_some()
+        && header[1..4] == [0, 0, 0];
+    let peeked = Cursor::new(header[..read].to_vec()).chain(reader);
+    if multiplexed {
+        Ok(Source::Multiplexed(Demultiplexer::new(peeked)))
+    } else {
+        Ok(Source::Raw(peeked))
+    }
+}
+
+/// Appends `data` to the partial line of a stream, queueing every line it
+/// completes.
+fn split_lines<F>(
+    partial: &mut Vec<u8>,
+    data: &[u8],
+    lines: &mut VecDeque<LogLine>,
+    line: F,
+) where
+    F: Fn(Vec<u8>) -> LogLine,
+{
+    partial.extend_from_slice(data);
+    while let Some(end) = partial.iter().position(|&byte| byte == b'\n') {
+        let mut rest = partial.split_off(end + 1);
+        std::mem::swap(partial, &mut rest);
+        rest.pop();
+        lines.push_back(line(rest));
+    }
+}
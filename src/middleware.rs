@@ -0,0 +1,339 @@
+//! Middlewares observing and transforming the traffic of a client.
+//!
+//! A `Middleware` sees every request sent through `send_request`, along with
+//! the response of the daemon, and can change both. Streaming and upgrade
+//! requests, like pull or attach, are read as they arrive instead, so
+//! middlewares only get to change the request, see `Middleware::prepare`.
+//!
+//! Middlewares are added to any `DockerApiClient` by wrapping it in a
+//! `MiddlewareClient`, they are called in the order they were added, each
+//! one handing the request over to the next one and eventually to the
+//! wrapped client.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! extern crate rust_docker;
+//!
+//! use std::time::Instant;
+//!
+//! use rust_docker::api::containers::Containers;
+//! use rust_docker::api::request::Request;
+//! use rust_docker::client::DockerClient;
+//! use rust_docker::errors::DockerApiError;
+//! use rust_docker::middleware::{Logger, MiddlewareClient, Next, SetHeader};
+//! use rust_docker::utils::api::Response;
+//!
+//! fn timing(request: Request, next: Next) -> Result<Response, DockerApiError> {
+//!     let start = Instant::now();
+//!     let resp = next.run(request);
+//!     println!("Took {:?}", start.elapsed());
+//!     resp
+//! }
+//!
+//! let client = DockerClient::new("unix:///var/run/docker.sock").unwrap();
+//! let client = MiddlewareClient::new(client)
+//!     .with(SetHeader::new("X-Registry-Auth", "eyJ1c2VybmFtZSI6ImphbmUifQ=="))
+//!     .with(Logger::default())
+//!     .with(timing);
+//!
+//! let containers = client.list_all_containers(None).unwrap();
+//! ```
+use std::sync::Arc;
+use std::time::Instant;
+
+use log::Level;
+
+use crate::api::containers::Containers;
+use crate::api::images::Images;
+use crate::api::request::Request;
+use crate::api::version::Version;
+use crate::api::DockerApiClient;
+use crate::errors::DockerApiError;
+use crate::streaming::ResponseStream;
+use crate::transport::Timeouts;
+use crate::upgrade::Upgraded;
+use crate::utils::api::Response;
+
+/// A hook around the requests sent to the daemon.
+///
+/// Implementations get the request before it is sent and must call
+/// `next.run` to carry on with it, unless they answer it themselves. The
+/// response, or error, of the daemon is returned by `next.run`.
+///
+/// Plain functions and closures with the signature of `handle` are
+/// middlewares too.
+pub trait Middleware: Send + Sync {
+    fn handle(
+        &self,
+        request: Request,
+        next: Next<'_>,
+    ) -> Result<Response, DockerApiError>;
+
+    /// Changes a request sent with `send_request_streaming` or
+    /// `send_upgrade_request`, like pull, push, logs or attach, before it is
+    /// sent. `handle` isn't called for these requests, their response being
+    /// read as it arrives.
+    ///
+    /// The request is left as it is by default.
+    fn prepare(&self, request: Request) -> Request {
+        request
+    }
+}
+
+impl<F> Middleware for F
+where
+    F: Fn(Request, Next<'_>) -> Result<Response, DockerApiError> + Send + Sync,
+{
+    fn handle(
+        &self,
+        request: Request,
+        next: Next<'_>,
+    ) -> Result<Response, DockerApiError> {
+        self(request, next)
+    }
+}
+
+/// The rest of the middleware chain, ending with the wrapped client.
+pub struct Next<'a> {
+    client: &'a dyn DockerApiClient,
+    middlewares: &'a [Arc<dyn Middleware>],
+    timeouts: Option<Timeouts>,
+}
+
+impl<'a> Next<'a> {
+    /// Hands the request over to the next middleware, or sends it to the
+    /// daemon once every middleware has been called.
+    pub fn run(self, request: Request) -> Result<Response, DockerApiError> {
+        match self.middlewares.split_first() {
+            Some((middleware, rest)) => {
+                let next = Next {
+                    client: self.client,
+                    middlewares: rest,
+                    timeouts: self.timeouts,
+                };
+                middleware.handle(request, next)
+            }
+            None => self
+                .client
+                .send_request_with_timeouts(request, self.timeouts),
+        }
+    }
+
+    /// Timeouts the request is sent with, `None` if the ones of the client
+    /// are used.
+    pub fn timeouts(&self) -> Option<Timeouts> {
+        self.timeouts
+    }
+}
+
+/// A `DockerApiClient` sending every request through a chain of
+/// middlewares before handing it to the wrapped client.
+///
+/// Requests sent with `send_request` go through `Middleware::handle`, the
+/// ones sent with `send_request_streaming` or `send_upgrade_request` through
+/// `Middleware::prepare`. Raw requests sent with `request` are handed to the
+/// wrapped client as they are.
+#[derive(Clone)]
+pub struct MiddlewareClient<C> {
+    client: C,
+    middlewares: Vec<Arc<dyn Middleware>>,
+}
+
+impl<C: DockerApiClient> MiddlewareClient<C> {
+    /// Wraps `client` without any middleware.
+    pub fn new(client: C) -> MiddlewareClient<C> {
+        MiddlewareClient {
+            client,
+            middlewares: Vec::new(),
+        }
+    }
+
+    /// Adds a middleware at the end of the chain, it is called after the
+    /// ones added before it.
+    pub fn with<M: Middleware + 'static>(
+        mut self,
+        middleware: M,
+    ) -> MiddlewareClient<C> {
+        self.middlewares.push(Arc::new(middleware));
+        self
+    }
+
+    /// Hands the request to every middleware in turn, see
+    /// `Middleware::prepare`.
+    fn prepare(&self, request: Request) -> Request {
+        self.middlewares
+            .iter()
+            .fold(request, |request, middleware| middleware.prepare(request))
+    }
+
+    /// The wrapped client.
+    pub fn get_ref(&self) -> &C {
+        &self.client
+    }
+
+    /// Gives back the wrapped client.
+    pub fn into_inner(self) -> C {
+        self.client
+    }
+}
+
+impl<C: DockerApiClient> DockerApiClient for MiddlewareClient<C> {
+    fn send_request_with_timeouts(
+        &self,
+        request: Request,
+        timeouts: Option<Timeouts>,
+    ) -> Result<Response, DockerApiError> {
+        let next = Next {
+            client: &self.client,
+            middlewares: &self.middlewares,
+            timeouts,
+        };
+        next.run(request)
+    }
+
+    fn send_request_streaming(
+        &self,
+        request: Request,
+        status_code: usize,
+    ) -> Result<ResponseStream, DockerApiError> {
+        let request = self.prepare(request);
+        self.client.send_request_streaming(request, status_code)
+    }
+
+    fn send_upgrade_request(
+        &self,
+        request: Request,
+    ) -> Result<Upgraded, DockerApiError> {
+        let request = self.prepare(request);
+        self.client.send_upgrade_request(request)
+    }
+
+    fn api_version(&self) -> Option<String> {
+        self.client.api_version()
+    }
+
+    fn request(&self, request: &[u8]) -> Option<Vec<u8>> {
+        self.client.request(request)
+    }
+
+    fn request_with_timeouts(
+        &self,
+        request: &[u8],
+        timeouts: Option<Timeouts>,
+    ) -> std::io::Result<Vec<u8>> {
+        self.client.request_with_timeouts(request, timeouts)
+    }
+
+    fn request_upgrade(
+        &self,
+        request: &[u8],
+        timeouts: Option<Timeouts>,
+    ) -> std::io::Result<Upgraded> {
+        self.client.request_upgrade(request, timeouts)
+    }
+}
+
+impl<C: DockerApiClient> Version for MiddlewareClient<C> {}
+impl<C: DockerApiClient> Containers for MiddlewareClient<C> {}
+impl<C: DockerApiClient> Images for MiddlewareClient<C> {}
+
+/// Sets a header on every request, like the `X-Registry-Auth` credentials
+/// of a registry.
+pub struct SetHeader {
+    name: String,
+    value: String,
+}
+
+impl SetHeader {
+    pub fn new<N: Into<String>, V: Into<String>>(
+        name: N,
+        value: V,
+    ) -> SetHeader {
+        SetHeader {
+            name: name.into(),
+            value: value.into(),
+        }
+    }
+}
+
+impl Middleware for SetHeader {
+    fn handle(
+        &self,
+        request: Request,
+        next: Next<'_>,
+    ) -> Result<Response, DockerApiError> {
+        next.run(request.header(self.name.as_str(), self.value.as_str()))
+    }
+
+    fn prepare(&self, request: Request) -> Request {
+        request.header(self.name.as_str(), self.value.as_str())
+    }
+}
+
+/// Logs every request through the `log` facade, along with the status of
+/// the response and how long it took. Streaming and upgrade requests are
+/// logged as they are sent.
+///
+/// Only the method and the endpoint are logged, the query string, headers
+/// and bodies may hold credentials.
+pub struct Logger {
+    level: Level,
+}
+
+impl Logger {
+    /// Creates a new Logger logging requests at `level`.
+    pub fn new(level: Level) -> Logger {
+        Logger { level }
+    }
+}
+
+impl Default for Logger {
+    fn default() -> Logger {
+        Logger::new(Level::Info)
+    }
+}
+
+impl Middleware for Logger {
+    fn handle(
+        &self,
+        request: Request,
+        next: Next<'_>,
+    ) -> Result<Response, DockerApiError> {
+        let method = request.method();
+        let endpoint = request.endpoint().to_string();
+        let start = Instant::now();
+
+        let resp = next.run(request);
+        match resp {
+            Ok(ref resp) => log!(
+                self.level,
+                "{} {} -> {} in {:?}",
+                method,
+                endpoint,
+                resp.status_code,
+                start.elapsed()
+            ),
+            Err(ref err) => log!(
+                self.level,
+                "{} {} failed in {:?} : {}",
+                method,
+                endpoint,
+                start.elapsed(),
+                err
+            ),
+        }
+
+        resp
+    }
+
+    fn prepare(&self, request: Request) -> Request {
+        log!(
+            self.level,
+            "{} {} (streaming)",
+            request.method(),
+            request.endpoint()
+        );
+        request
+    }
+}
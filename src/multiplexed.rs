@@ -0,0 +1,244 @@
+//! Decoding of the multiplexed stdout/stderr streams of containers.
+//!
+//! The output of containers without a TTY, from the logs, attach and exec
+//! endpoints, is sent as a single stream of frames. Each frame starts with
+//! an 8 bytes header, the stream it belongs to followed by 3 zero bytes and
+//! the length of its data as a big endian `u32`:
+//!
+//! ```text
+//! [STREAM, 0, 0, 0, SIZE1, SIZE2, SIZE3, SIZE4] DATA
+//! ```
+//!
+//! Containers with a TTY send their output as it is, without any framing.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! extern crate rust_docker;
+//!
+//! use rust_docker::api::request::Request;
+//! use rust_docker::api::DockerApiClient;
+//! use rust_docker::client::DockerClient;
+//! use rust_docker::multiplexed::{Demultiplexer, Frame};
+//!
+//! let client = DockerClient::new("unix:///var/run/docker.sock").unwrap();
+//!
+//! let request = Request::post("/containers/f808ca/attach")
+//!     .query("stream", true)
+//!     .query("stdout", true)
+//!     .query("stderr", true);
+//! let upgraded = client.send_upgrade_request(request).unwrap();
+//!
+//! for frame in Demultiplexer::new(upgraded) {
+//!     match frame.unwrap() {
+//!         Frame::StdErr(data) => eprint!("{}", String::from_utf8_lossy(&data)),
+//!         frame => print!("{}", String::from_utf8_lossy(frame.data())),
+//!     }
+//! }
+//! ```
+use std::io;
+use std::io::{Read, Write};
+
+/// Size of the header starting every frame.
+pub const FRAME_HEADER_SIZE: usize = 8;
+
+/// Stream a frame belongs to, as found in the first byte of its header.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StreamType {
+    StdIn = 0,
+    StdOut = 1,
+    StdErr = 2,
+}
+
+impl StreamType {
+    /// Parses the stream byte of a frame header.
+    pub fn from_byte(byte: u8) -> Option<StreamType> {
+        match byte {
+            0 => Some(StreamType::StdIn),
+            1 => Some(StreamType::StdOut),
+            2 => Some(StreamType::StdErr),
+            _ => None,
+        }
+    }
+}
+
+/// A frame of a multiplexed stream, along with its data.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Frame {
+    StdIn(Vec<u8>),
+    StdOut(Vec<u8>),
+    StdErr(Vec<u8>),
+}
+
+impl Frame {
+    /// Creates a new frame of `stream` with the provided data.
+    pub fn new(stream: StreamType, data: Vec<u8>) -> Frame {
+        match stream {
+            StreamType::StdIn => Frame::StdIn(data),
+            StreamType::StdOut => Frame::StdOut(data),
+            StreamType::StdErr => Frame::StdErr(data),
+        }
+    }
+
+    /// Stream the frame belongs to.
+    pub fn stream(&self) -> StreamType {
+        match *self {
+            Frame::StdIn(_) => StreamType::StdIn,
+            Frame::StdOut(_) => StreamType::StdOut,
+            Frame::StdErr(_) => StreamType::StdErr,
+        }
+    }
+
+    pub fn data(&self) -> &[u8] {
+        match *self {
+            Frame::StdIn(ref data)
+            | Frame::StdOut(ref data)
+            | Frame::StdErr(ref data) => data,
+        }
+    }
+
+    pub fn into_data(self) -> Vec<u8> {
+        match self {
+            Frame::StdIn(data) | Frame::StdOut(data) | Frame::StdErr(data) => {
+                data
+            }
+        }
+    }
+}
+
+/// Reads the frames of a multiplexed stream off any reader, like the body
+/// of a logs response or an upgraded attach connection.
+///
+/// Frames are read one at a time as they arrive, so the output of long
+/// running processes can be followed. It is also an iterator over the
+/// frames, ending with the stream.
+pub struct Demultiplexer<R> {
+    reader: R,
+}
+
+impl<R: Read> Demultiplexer<R> {
+    /// Creates a new Demultiplexer reading frames off `reader`.
+    pub fn new(reader: R) -> Demultiplexer<R> {
+        Demultiplexer { reader }
+    }
+
+    /// Reads the next frame, or `None` once the stream ended.
+    ///
+    /// A stream ending in the middle of a frame fails with
+    /// `io::ErrorKind::UnexpectedEof`, and a header with an unknown stream
+    /// fails with `io::ErrorKind::InvalidData`, which usually means the
+    /// stream is not multiplexed because the container has a TTY.
+    pub fn read_frame(&mut self) -> io::Result<Option<Frame>> {
+        let mut header = [0; FRAME_HEADER_SIZE];
+        let mut read = 0;
+        while read < FRAME_HEADER_SIZE {
+            match self.reader.read(&mut header[read..]) {
+                Ok(0) if read == 0 => return Ok(None),
+                Ok(0) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "Stream ended in the middle of a frame header",
+                    ))
+                }
+                Ok(len) => read += len,
+                Err(ref err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+
+        let stream = match StreamType::from_byte(header[0]) {
+            Some(stream) => stream,
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Unknown stream {} in frame header", header[0]),
+                ))
+            }
+        };
+
+        let len =
+            u32::from_be_bytes([header[4], header[5], header[6], header[7]])
+                as u64;
+        let mut data = Vec::new();
+        if (&mut self.reader).take(len).read_to_end(&mut data)? as u64 != len {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "Stream ended in the middle of a frame",
+            ));
+        }
+
+        Ok(Some(Frame::new(stream, data)))
+    }
+
+    /// Copies the data of every frame to `stdout` or `stderr` until the
+    /// stream ends. Stdin frames are copied to `stdout`, like the docker CLI
+    /// does.
+    pub fn copy_to<O: Write, E: Write>(
+        &mut self,
+        stdout: &mut O,
+        stderr: &mut E,
+    ) -> io::Result<()> {
+        while let Some(frame) = self.read_frame()? {
+            match frame {
+                Frame::StdErr(data) => stderr.write_all(&data)?,
+                Frame::StdIn(data) | Frame::StdOut(data) => {
+                    stdout.write_all(&data)?
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads the stream until it ends and returns the stdout and stderr
+    /// output as strings. Invalid UTF-8 sequences are replaced with
+    /// `U+FFFD`.
+    pub fn read_to_strings(&mut self) -> io::Result<(String, String)> {
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        self.copy_to(&mut stdout, &mut stderr)?;
+
+        Ok((
+            String::from_utf8_lossy(&stdout).into_owned(),
+            String::from_utf8_lossy(&stderr).into_owned(),
+        ))
+    }
+
+    /// Gives back the wrapped reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: Read> Iterator for Demultiplexer<R> {
+    type Item = io::Result<Frame>;
+
+    fn next(&mut self) -> Option<io::Result<Frame>> {
+        self.read_frame().transpose()
+    }
+}
+
+/// Writes `data` to `writer` as frames of `stream`, split in as many frames
+/// as its length requires. Nothing is written for empty data.
+pub fn write_frame<W: Write>(
+    writer: &mut W,
+    stream: StreamType,
+    data: &[u8],
+) -> io::Result<()> {
+    for chunk in data.chunks(u32::MAX as usize) {
+        let len = (chunk.len() as u32).to_be_bytes();
+        writer.write_all(&[
+            stream as u8,
+            0,
+            0,
+            0,
+            len[0],
+            len[1],
+            len[2],
+            len[3],
+        ])?;
+        writer.write_all(chunk)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,145 @@
+//! A bounded pool of keep-alive connections to the docker daemon.
+use std::sync::{Condvar, Mutex, MutexGuard};
+
+use crate::transport::Stream;
+
+/// Maximum number of connections a DockerClient opens by default.
+pub const DEFAULT_POOL_SIZE: usize = 8;
+
+struct PoolState {
+    idle: Vec<Box<dyn Stream>>,
+    open: usize,
+    max_size: usize,
+}
+
+/// A bounded pool of connections to the docker daemon.
+///
+/// Every request checks a connection out of the pool, reusing an idle
+/// keep-alive connection when there is one and opening a new one otherwise.
+/// Once `max_size` connections are open, further requests wait for one of
+/// them to be checked back in.
+pub struct ConnectionPool {
+    state: Mutex<PoolState>,
+    available: Condvar,
+}
+
+impl ConnectionPool {
+    /// Creates a new empty ConnectionPool which opens at most `max_size`
+    /// connections at once.
+    pub fn new(max_size: usize) -> ConnectionPool {
+        ConnectionPool {
+            state: Mutex::new(PoolState {
+                idle: Vec::new(),
+                open: 0,
+                max_size: max_size.max(1),
+            }),
+            available: Condvar::new(),
+        }
+    }
+
+    /// Maximum number of connections the pool opens at once.
+    pub fn max_size(&self) -> usize {
+        self.lock().max_size
+    }
+
+    /// Changes the maximum number of connections the pool opens at once.
+    /// Connections already open above the new limit are closed as they are
+    /// checked back in.
+    pub fn set_max_size(&self, max_size: usize) {
+        let mut state = self.lock();
+        state.max_size = max_size.max(1);
+        while state.open > state.max_size && !state.idle.is_empty() {
+            state.idle.pop();
+            state.open -= 1;
+        }
+        drop(state);
+        self.available.notify_all();
+    }
+
+    /// Number of idle connections waiting to be reused.
+    pub fn idle(&self) -> usize {
+        self.lock().idle.len()
+    }
+
+    /// Checks a connection out of the pool.
+    ///
+    /// Returns an idle connection along with `true` if one is available,
+    /// otherwise a new connection is opened with `connect` and returned
+    /// along with `false`. Blocks while the pool is exhausted.
+    pub fn checkout<F, E>(
+        &self,
+        connect: F,
+    ) -> Result<(Box<dyn Stream>, bool), E>
+    where
+        F: FnOnce() -> Result<Box<dyn Stream>, E>,
+    {
+        let mut state = self.lock();
+        loop {
+            if let Some(stream) = state.idle.pop() {
+                return Ok((stream, true));
+            }
+
+            if state.open < state.max_size {
+                break;
+            }
+
+            state = match self.available.wait(state) {
+                Ok(state) => state,
+                Err(poisoned) => poisoned.into_inner(),
+            };
+        }
+
+        // Don't hold the lock while connecting, other requests may still
+        // reuse idle connections in the meantime.
+        state.open += 1;
+        drop(state);
+
+        match connect() {
+            Ok(stream) => Ok((stream, false)),
+            Err(err) => {
+                self.discard();
+                Err(err)
+            }
+        }
+    }
+
+    /// Returns a connection to the pool once the response to a request has
+    /// been read completely, so it can be reused by the next request.
+    pub fn checkin(&self, stream: Box<dyn Stream>) {
+        let mut state = self.lock();
+        if state.open > state.max_size {
+            state.open -= 1;
+        } else {
+            state.idle.push(stream);
+        }
+        drop(state);
+        self.available.notify_one();
+    }
+
+    /// Gives up a checked out connection which can't be reused, making room
+    /// for a new one.
+    pub fn discard(&self) {
+        let mut state = self.lock();
+        state.open -= 1;
+        drop(state);
+        self.available.notify_one();
+    }
+
+    /// Adds an already open connection to the pool as an idle connection.
+    pub fn add(&self, stream: Box<dyn Stream>) {
+        let mut state = self.lock();
+        state.open += 1;
+        state.idle.push(stream);
+        drop(state);
+        self.available.notify_one();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, PoolState> {
+        // A panic while holding the lock can't leave the state inconsistent,
+        // so keep using it.
+        match self.state.lock() {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
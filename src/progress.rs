@@ -0,0 +1,200 @@
+//! Decoding of the streams of JSON messages sent by the daemon.
+//!
+//! Endpoints like pull, push, build, load or events answer with one JSON
+//! message per line, for as long as the operation runs. `JsonStream` decodes
+//! them one at a time as they arrive, and `ProgressStream` decodes the
+//! progress messages of pull, push, build and load, surfacing the errors
+//! reported in the middle of the stream.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! extern crate rust_docker;
+//!
+//! use rust_docker::api::request::Request;
+//! use rust_docker::api::DockerApiClient;
+//! use rust_docker::client::DockerClient;
+//!
+//! let client = DockerClient::new("unix:///var/run/docker.sock").unwrap();
+//!
+//! let request = Request::post("/images/create")
+//!     .query("fromImage", "debian")
+//!     .query("tag", "jessie");
+//! let resp = client.send_request_streaming(request, 200).unwrap();
+//!
+//! for message in resp.progress() {
+//!     match message {
+//!         Ok(message) => println!("{}", message.status.unwrap_or_default()),
+//!         Err(err) => println!("Pull failed : {}", err),
+//!     }
+//! }
+//! ```
+#![allow(non_snake_case)]
+use std::io::{BufRead, BufReader, Read};
+use std::marker::PhantomData;
+
+use serde::de::DeserializeOwned;
+use serde_json;
+
+use crate::errors::DockerApiError;
+
+/// A progress message, as sent by pull, push, build and load.
+///
+/// * id: ID of the object the message is about, like an image layer.
+/// * status: What is being done, like `Downloading`.
+/// * stream: Output of the build.
+/// * progress: Progress bar of the operation, as drawn by the docker CLI.
+/// * progressDetail: Progress of the operation in bytes.
+/// * error: Error message, superseded by `errorDetail`.
+/// * errorDetail: Error which made the operation fail.
+/// * aux: Extra information, like the ID of a built image.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct ProgressMessage {
+    pub id: Option<String>,
+    pub status: Option<String>,
+    pub stream: Option<String>,
+    pub progress: Option<String>,
+    pub progressDetail: Option<ProgressDetail>,
+    pub error: Option<String>,
+    pub errorDetail: Option<ErrorDetail>,
+    pub aux: Option<serde_json::Value>,
+}
+
+impl ProgressMessage {
+    /// The error reported by the message, if any.
+    pub fn error_message(&self) -> Option<&str> {
+        match self.errorDetail {
+            Some(ref detail) if !detail.message.is_empty() => {
+                Some(&detail.message)
+            }
+            _ => self.error.as_deref(),
+        }
+    }
+
+    /// Deserializes the extra information of the message, like
+    /// `{"ID": "sha256:..."}` at the end of a build.
+    pub fn aux_as<T: DeserializeOwned>(
+        &self,
+    ) -> Result<Option<T>, DockerApiError> {
+        match self.aux {
+            Some(ref aux) => match serde_json::from_value(aux.clone()) {
+                Ok(aux) => Ok(Some(aux)),
+                Err(err) => Err(DockerApiError::JsonDeserializationError(err)),
+            },
+            None => Ok(None),
+        }
+    }
+}
+
+/// Progress of an operation, in bytes. Both are missing for operations
+/// which didn't start yet.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct ProgressDetail {
+    pub current: Option<u64>,
+    pub total: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct ErrorDetail {
+    pub code: Option<i64>,
+    #[serde(default)]
+    pub message: String,
+}
+
+/// Reads a stream of newline delimited JSON messages off any reader, like
+/// a `streaming::ResponseStream`, decoding them as `T` one at a time as
+/// they arrive. Blank lines are skipped.
+///
+/// It is also an iterator over the messages, ending with the stream.
+pub struct JsonStream<R, T> {
+    reader: BufReader<R>,
+    line: Vec<u8>,
+    message: PhantomData<fn() -> T>,
+}
+
+impl<R: Read, T: DeserializeOwned> JsonStream<R, T> {
+    /// Creates a new JsonStream reading messages off `reader`.
+    pub fn new(reader: R) -> JsonStream<R, T> {
+        JsonStream {
+            reader: BufReader::new(reader),
+            line: Vec::new(),
+            message: PhantomData,
+        }
+    }
+
+    /// Reads the next message, or `None` once the stream ended.
+    ///
+    /// A message which can't be decoded fails with
+    /// `DockerApiError::JsonDeserializationError`, the stream can still be
+    /// read past it.
+    pub fn next_message(&mut self) -> Result<Option<T>, DockerApiError> {
+        loop {
+            self.line.clear();
+            match self.reader.read_until(b'\n', &mut self.line) {
+                Ok(0) => return Ok(None),
+                Ok(_) => {}
+                Err(err) => return Err(DockerApiError::StreamReadError(err)),
+            }
+
+            if self.line.iter().all(|byte| byte.is_ascii_whitespace()) {
+                continue;
+            }
+
+            return match serde_json::from_slice(&self.line) {
+                Ok(message) => Ok(Some(message)),
+                Err(err) => Err(DockerApiError::JsonDeserializationError(err)),
+            };
+        }
+    }
+}
+
+impl<R: Read, T: DeserializeOwned> Iterator for JsonStream<R, T> {
+    type Item = Result<T, DockerApiError>;
+
+    fn next(&mut self) -> Option<Result<T, DockerApiError>> {
+        self.next_message().transpose()
+    }
+}
+
+/// A stream of progress messages, where messages reporting an error are
+/// turned into `DockerApiError::StreamError`. The daemon ends the stream
+/// once it reported an error.
+pub struct ProgressStream<R> {
+    messages: JsonStream<R, ProgressMessage>,
+}
+
+impl<R: Read> ProgressStream<R> {
+    /// Creates a new ProgressStream reading messages off `reader`.
+    pub fn new(reader: R) -> ProgressStream<R> {
+        ProgressStream {
+            messages: JsonStream::new(reader),
+        }
+    }
+
+    /// Reads every message until the stream ends, or fails with the first
+    /// error reported.
+    pub fn wait(self) -> Result<Vec<ProgressMessage>, DockerApiError> {
+        self.collect()
+    }
+}
+
+impl<R: Read> Iterator for ProgressStream<R> {
+    type Item = Result<ProgressMessage, DockerApiError>;
+
+    fn next(&mut self) -> Option<Result<ProgressMessage, DockerApiError>> {
+        match self.messages.next()? {
+            Ok(message) => match message.error_message() {
+                Some(error) => {
+                    let code =
+                        message.errorDetail.as_ref().and_then(|d| d.code);
+                    Some(Err(DockerApiError::StreamError(
+                        code,
+                        error.to_string(),
+                    )))
+                }
+                None => Some(Ok(message)),
+            },
+            Err(err) => Some(Err(err)),
+        }
+    }
+}
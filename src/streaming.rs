@@ -0,0 +1,245 @@
+//! Responses read as their body arrives.
+//!
+//! Endpoints like pull, build or events keep sending their response for as
+//! long as the operation runs. `DockerApiClient::send_request_streaming`
+//! hands such responses back as soon as their head was read, as a
+//! `ResponseStream` reading the body off the connection as the daemon sends
+//! it, chunked transfer encoding included.
+use std::collections::HashMap;
+use std::io;
+use std::io::{BufRead, BufReader, Cursor, Read};
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+
+use crate::progress::{JsonStream, ProgressStream};
+use crate::transport::Stream;
+use crate::upgrade::Upgraded;
+use crate::utils::api::{
+    body_framing, check_chunk_end, find_header, is_empty_line,
+    parse_chunk_size_line, read_http_head, unexpected_eof, BodyFraming,
+    Response, LF,
+};
+
+/// Where the reader is in the body of the response.
+enum BodyState {
+    /// Bytes left in a body with a Content-Length.
+    Length(u64),
+    /// Bytes left in the current chunk, the next chunk size line is read
+    /// once it is 0.
+    Chunked(u64),
+    UntilClose,
+    Done,
+}
+
+/// What the body of the response is read from.
+enum Source {
+    /// The connection the request was sent on.
+    Connection(BufReader<Box<dyn Stream>>),
+    /// A response which was read whole.
+    Memory(Cursor<Vec<u8>>),
+}
+
+impl Read for Source {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match *self {
+            Source::Connection(ref mut reader) => reader.read(buf),
+            Source::Memory(ref mut reader) => reader.read(buf),
+        }
+    }
+}
+
+impl BufRead for Source {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        match *self {
+            Source::Connection(ref mut reader) => reader.fill_buf(),
+            Source::Memory(ref mut reader) => reader.fill_buf(),
+        }
+    }
+
+    fn consume(&mut self, amt: usize) {
+        match *self {
+            Source::Connection(ref mut reader) => reader.consume(amt),
+            Source::Memory(ref mut reader) => reader.consume(amt),
+        }
+    }
+}
+
+/// A response of the daemon whose body is read as it arrives, see the
+/// module documentation.
+pub struct ResponseStream {
+    status_code: usize,
+    headers: HashMap<String, String>,
+    reader: Source,
+    body: BodyState,
+}
+
+impl ResponseStream {
+    /// Reads the body of the response to a request written to a connection
+    /// of its own.
+    pub(crate) fn from_upgraded(upgraded: Upgraded) -> io::Result<Self> {
+        let (status_code, headers, reader) = upgraded.into_parts();
+        ResponseStream::new(status_code, headers, Source::Connection(reader))
+    }
+
+    /// Reads the body of a response which was read whole, head included.
+    pub(crate) fn from_bytes(raw_resp: Vec<u8>) -> io::Result<Self> {
+        let mut reader = Cursor::new(raw_resp);
+        loop {
+            let mut head = Vec::new();
+            let (status_code, headers) =
+                read_http_head(&mut reader, &mut head)?;
+            if status_code / 100 == 1 && status_code != 101 {
+                continue;
+            }
+
+            return ResponseStream::new(
+                status_code,
+                headers,
+                Source::Memory(reader),
+            );
+        }
+    }
+
+    fn new(
+        status_code: usize,
+        headers: HashMap<String, String>,
+        reader: Source,
+    ) -> io::Result<Self> {
+        let body = match body_framing(status_code, &headers, false)? {
+            BodyFraming::Empty => BodyState::Done,
+            BodyFraming::Chunked => BodyState::Chunked(0),
+            BodyFraming::Length(len) => BodyState::Length(len),
+            BodyFraming::UntilClose => BodyState::UntilClose,
+        };
+
+        Ok(ResponseStream {
+            status_code,
+            headers,
+            reader,
+            body,
+        })
+    }
+
+    pub fn status_code(&self) -> usize {
+        self.status_code
+    }
+
+    /// Returns the value of the header `name`, header names are matched
+    /// case insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+
+    /// Sets the timeout of every read of the body, `None` blocking forever.
+    /// The read timeout of the client applies otherwise, which ends the
+    /// responses the daemon keeps quiet for longer, like followed logs.
+    ///
+    /// Responses which were read whole ignore it.
+    pub fn set_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        match self.reader {
+            Source::Connection(ref reader) => {
+                reader.get_ref().set_timeout(timeout)
+            }
+            Source::Memory(_) => Ok(()),
+        }
+    }
+
+    /// Decodes the body as a stream of JSON messages, see
+    /// `progress::JsonStream`.
+    pub fn json<T: DeserializeOwned>(self) -> JsonStream<ResponseStream, T> {
+        JsonStream::new(self)
+    }
+
+    /// Decodes the body as a stream of progress messages, like the ones of
+    /// pull, push, build or load, see `progress::ProgressStream`.
+    pub fn progress(self) -> ProgressStream<ResponseStream> {
+        ProgressStream::new(self)
+    }
+
+    /// Reads the rest of the body and returns the whole response, like
+    /// `send_request` would have.
+    pub fn into_response(mut self) -> io::Result<Response> {
+        let mut body = Vec::new();
+        self.read_to_end(&mut body)?;
+
+        Ok(Response {
+            status_code: self.status_code,
+            headers: self.headers,
+            body,
+        })
+    }
+
+    /// Reads the size line of the next chunk, skipping the trailer once the
+    /// last chunk was reached.
+    fn next_chunk(&mut self) -> io::Result<u64> {
+        let mut line = Vec::new();
+        if self.reader.read_until(LF, &mut line)? == 0 {
+            return Err(unexpected_eof(
+                "Connection closed before the last chunk",
+            ));
+        }
+
+        let size = parse_chunk_size_line(&line)? as u64;
+        if size == 0 {
+            loop {
+                line.clear();
+                if self.reader.read_until(LF, &mut line)? == 0
+                    || is_empty_line(&line)
+                {
+                    break;
+                }
+            }
+        }
+
+        Ok(size)
+    }
+}
+
+impl Read for ResponseStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            let remaining = match self.body {
+                BodyState::Done => return Ok(0),
+                BodyState::UntilClose => return self.reader.read(buf),
+                BodyState::Length(0) => {
+                    self.body = BodyState::Done;
+                    continue;
+                }
+                BodyState::Chunked(0) => {
+                    self.body = match self.next_chunk()? {
+                        0 => BodyState::Done,
+                        size => BodyState::Chunked(size),
+                    };
+                    continue;
+                }
+                BodyState::Length(remaining)
+                | BodyState::Chunked(remaining) => remaining,
+            };
+
+            let max = remaining.min(buf.len() as u64) as usize;
+            let read = self.reader.read(&mut buf[..max])?;
+            if read == 0 && max > 0 {
+                return Err(unexpected_eof(
+                    "Connection closed before the end of the response body",
+                ));
+            }
+
+            let remaining = remaining - read as u64;
+            self.body = match self.body {
+                BodyState::Length(_) => BodyState::Length(remaining),
+                _ => {
+                    if remaining == 0 {
+                        // Chunk data is followed by its CRLF.
+                        let mut crlf = [0; 2];
+                        self.reader.read_exact(&mut crlf)?;
+                        check_chunk_end(&crlf)?;
+                    }
+                    BodyState::Chunked(remaining)
+                }
+            };
+
+            return Ok(read);
+        }
+    }
+}
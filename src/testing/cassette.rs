@@ -0,0 +1,407 @@
+//! Record and replay of the exchanges with the docker daemon.
+//!
+//! `Recorder` wraps any `DockerApiClient` and keeps every request it sends
+//! along with the raw response of the daemon, which can then be saved to a
+//! cassette file. `Replayer` answers requests from a cassette, so the API
+//! helpers can be tested against the responses of real daemons, of many
+//! versions, without docker.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! extern crate rust_docker;
+//!
+//! use rust_docker::api::containers::Containers;
+//! use rust_docker::client::DockerClient;
+//! use rust_docker::testing::{Matching, Recorder, Replayer};
+//!
+//! // Once, against a real daemon.
+//! let client = DockerClient::new("unix:///var/run/docker.sock").unwrap();
+//! let recorder = Recorder::new(client);
+//! recorder.list_all_containers(None).unwrap();
+//! recorder.save("tests/fixtures/list_containers.json").unwrap();
+//!
+//! // Then in CI.
+//! let replayer =
+//!     Replayer::from_file("tests/fixtures/list_containers.json", Matching::Strict)
+//!         .unwrap();
+//! let containers = replayer.list_all_containers(None).unwrap();
+//! ```
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str;
+use std::sync::{Mutex, MutexGuard};
+
+#[cfg(feature = "tokio")]
+use async_trait::async_trait;
+use serde_json;
+
+use crate::api::containers::Containers;
+use crate::api::images::Images;
+use crate::api::version::Version;
+use crate::api::DockerApiClient;
+#[cfg(feature = "tokio")]
+use crate::asynchronous::containers::AsyncContainers;
+#[cfg(feature = "tokio")]
+use crate::asynchronous::images::AsyncImages;
+#[cfg(feature = "tokio")]
+use crate::asynchronous::version::AsyncVersion;
+#[cfg(feature = "tokio")]
+use crate::asynchronous::AsyncDockerApiClient;
+use crate::testing::{
+    parse_query, split_request, split_target, strip_api_version,
+};
+use crate::transport::Timeouts;
+
+/// Body of a recorded request or a raw recorded response. It is kept as
+/// text in the cassette when it is valid UTF-8, so cassettes can be read
+/// and edited, and as an array of bytes otherwise.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum Payload {
+    Text(String),
+    Binary(Vec<u8>),
+}
+
+impl Payload {
+    pub fn from_bytes(bytes: &[u8]) -> Payload {
+        match str::from_utf8(bytes) {
+            Ok(text) => Payload::Text(text.to_string()),
+            Err(_) => Payload::Binary(bytes.to_vec()),
+        }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        match *self {
+            Payload::Text(ref text) => text.as_bytes(),
+            Payload::Binary(ref bytes) => bytes,
+        }
+    }
+}
+
+impl Default for Payload {
+    fn default() -> Payload {
+        Payload::Text(String::new())
+    }
+}
+
+/// Request sent to the daemon.
+///
+/// * method: HTTP method of the request.
+/// * path: Request target, with the API version prefix and the query
+///   string, like `/v1.37/containers/json?all=true`.
+/// * body: Body of the request.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    #[serde(default)]
+    pub body: Payload,
+}
+
+/// A request along with the raw HTTP response of the daemon.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: Payload,
+}
+
+/// Exchanges with a daemon, in the order they happened.
+///
+/// * api_version: API version the recorded client was pinned to.
+/// * interactions: Requests along with the response of the daemon.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Cassette {
+    #[serde(default)]
+    pub api_version: Option<String>,
+    pub interactions: Vec<Interaction>,
+}
+
+impl Cassette {
+    /// Loads a cassette saved with `save`.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Cassette> {
+        let content = fs::read(path)?;
+        match serde_json::from_slice(&content) {
+            Ok(cassette) => Ok(cassette),
+            Err(err) => Err(io::Error::new(io::ErrorKind::InvalidData, err)),
+        }
+    }
+
+    /// Saves the cassette as JSON, creating the parent directories of
+    /// `path` if needed.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        match serde_json::to_vec_pretty(self) {
+            Ok(content) => fs::write(path, content),
+            Err(err) => Err(io::Error::new(io::ErrorKind::InvalidData, err)),
+        }
+    }
+}
+
+/// How `Replayer` matches requests with the interactions of its cassette.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Matching {
+    /// Interactions are replayed in the recorded order, every request must
+    /// have the same method, path, query string and body as the next
+    /// recorded one.
+    Strict,
+    /// Every request is answered by the first interaction not replayed yet
+    /// with the same method and endpoint, regardless of the API version, of
+    /// the order of the query parameters and of the body. Once they have
+    /// all been replayed, the last one is replayed again.
+    Lenient,
+}
+
+/// A `DockerApiClient` recording the exchanges of the wrapped client with
+/// the daemon.
+pub struct Recorder<C> {
+    client: C,
+    cassette: Mutex<Cassette>,
+}
+
+impl<C: DockerApiClient> Recorder<C> {
+    /// Creates a new Recorder sending requests with `client`.
+    pub fn new(client: C) -> Recorder<C> {
+        let cassette = Cassette {
+            api_version: client.api_version(),
+            interactions: Vec::new(),
+        };
+
+        Recorder {
+            client,
+            cassette: Mutex::new(cassette),
+        }
+    }
+
+    /// Exchanges recorded so far.
+    pub fn cassette(&self) -> Cassette {
+        lock(&self.cassette).clone()
+    }
+
+    /// Saves the exchanges recorded so far to a cassette file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        lock(&self.cassette).save(path)
+    }
+
+    /// Gives back the wrapped client.
+    pub fn into_inner(self) -> C {
+        self.client
+    }
+}
+
+impl<C: DockerApiClient> DockerApiClient for Recorder<C> {
+    fn api_version(&self) -> Option<String> {
+        self.client.api_version()
+    }
+
+    fn request(&self, request: &[u8]) -> Option<Vec<u8>> {
+        self.request_with_timeouts(request, None).ok()
+    }
+
+    fn request_with_timeouts(
+        &self,
+        request: &[u8],
+        timeouts: Option<Timeouts>,
+    ) -> io::Result<Vec<u8>> {
+        let response = self.client.request_with_timeouts(request, timeouts)?;
+
+        if let Some((method, path, body)) = split_request(request) {
+            let interaction = Interaction {
+                request: RecordedRequest {
+                    method: method.to_string(),
+                    path: path.to_string(),
+                    body: Payload::from_bytes(body),
+                },
+                response: Payload::from_bytes(&response),
+            };
+            lock(&self.cassette).interactions.push(interaction);
+        }
+
+        Ok(response)
+    }
+}
+
+impl<C: DockerApiClient> Version for Recorder<C> {}
+impl<C: DockerApiClient> Containers for Recorder<C> {}
+impl<C: DockerApiClient> Images for Recorder<C> {}
+
+/// A `DockerApiClient` answering requests with the responses recorded in a
+/// cassette.
+///
+/// Requests without a matching interaction fail with
+/// `DockerApiError::ConnectionError`, describing what was expected.
+pub struct Replayer {
+    cassette: Cassette,
+    matching: Matching,
+    replayed: Mutex<Vec<bool>>,
+}
+
+impl Replayer {
+    /// Creates a new Replayer answering requests from `cassette`.
+    pub fn new(cassette: Cassette, matching: Matching) -> Replayer {
+        let replayed = vec![false; cassette.interactions.len()];
+        Replayer {
+            cassette,
+            matching,
+            replayed: Mutex::new(replayed),
+        }
+    }
+
+    /// Creates a new Replayer answering requests from a cassette file.
+    pub fn from_file<P: AsRef<Path>>(
+        path: P,
+        matching: Matching,
+    ) -> io::Result<Replayer> {
+        Ok(Replayer::new(Cassette::load(path)?, matching))
+    }
+
+    /// Number of interactions of the cassette which were not replayed yet.
+    pub fn remaining(&self) -> usize {
+        lock(&self.replayed).iter().filter(|&&done| !done).count()
+    }
+
+    fn replay(&self, request: &[u8]) -> io::Result<Vec<u8>> {
+        let (method, path, body) = match split_request(request) {
+            Some(request) => request,
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "Not a valid HTTP request",
+                ))
+            }
+        };
+
+        let mut replayed = lock(&self.replayed);
+        let index = match self.matching {
+            Matching::Strict => {
+                let next = replayed.iter().filter(|&&done| done).count();
+                match self.cassette.interactions.get(next) {
+                    Some(interaction)
+                        if interaction.request.method == method
+                            && interaction.request.path == path
+                            && interaction.request.body.as_bytes() == body =>
+                    {
+                        next
+                    }
+                    Some(interaction) => {
+                        return Err(no_interaction(format!(
+                            "Expected {} {} as request {} of the cassette, got {} {}",
+                            interaction.request.method,
+                            interaction.request.path,
+                            next + 1,
+                            method,
+                            path
+                        )))
+                    }
+                    None => {
+                        return Err(no_interaction(format!(
+                            "All the interactions of the cassette were replayed, got {} {}",
+                            method, path
+                        )))
+                    }
+                }
+            }
+            Matching::Lenient => {
+                let endpoint = lenient_endpoint(path);
+                let matches: Vec<usize> = self
+                    .cassette
+                    .interactions
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, interaction)| {
+                        interaction.request.method == method
+                            && lenient_endpoint(&interaction.request.path)
+                                == endpoint
+                    })
+                    .map(|(index, _)| index)
+                    .collect();
+
+                match matches.iter().find(|&&index| !replayed[index]) {
+                    Some(&index) => index,
+                    None => match matches.last() {
+                        Some(&index) => index,
+                        None => {
+                            return Err(no_interaction(format!(
+                                "No interaction of the cassette matches {} {}",
+                                method, path
+                            )))
+                        }
+                    },
+                }
+            }
+        };
+
+        replayed[index] = true;
+        Ok(self.cassette.interactions[index]
+            .response
+            .as_bytes()
+            .to_vec())
+    }
+}
+
+impl DockerApiClient for Replayer {
+    fn api_version(&self) -> Option<String> {
+        self.cassette.api_version.clone()
+    }
+
+    fn request(&self, request: &[u8]) -> Option<Vec<u8>> {
+        self.replay(request).ok()
+    }
+
+    fn request_with_timeouts(
+        &self,
+        request: &[u8],
+        _timeouts: Option<Timeouts>,
+    ) -> io::Result<Vec<u8>> {
+        self.replay(request)
+    }
+}
+
+impl Version for Replayer {}
+impl Containers for Replayer {}
+impl Images for Replayer {}
+
+#[cfg(feature = "tokio")]
+#[async_trait]
+impl AsyncDockerApiClient for Replayer {
+    fn api_version(&self) -> Option<String> {
+        self.cassette.api_version.clone()
+    }
+
+    async fn request(&self, request: &[u8]) -> io::Result<Vec<u8>> {
+        self.replay(request)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl AsyncVersion for Replayer {}
+#[cfg(feature = "tokio")]
+impl AsyncContainers for Replayer {}
+#[cfg(feature = "tokio")]
+impl AsyncImages for Replayer {}
+
+/// Endpoint of a request target without the API version, along with its
+/// query parameters in a stable order.
+fn lenient_endpoint(target: &str) -> (String, Vec<(String, String)>) {
+    let (path, query) = split_target(target);
+    let mut query = parse_query(query);
+    query.sort();
+
+    (strip_api_version(path).to_string(), query)
+}
+
+fn no_interaction(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::NotFound, msg)
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    match mutex.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
@@ -0,0 +1,1096 @@
+//! In-memory stand-in for the docker daemon, to test code built on the API
+//! traits without a running docker.
+//!
+//! This module is only available with the `testing` cargo feature.
+//! `FakeDocker` implements `DockerApiClient`, so `Containers`, `Images` and
+//! `Version` can be used with it just like with a `DockerClient`. Requests
+//! are answered from in-memory containers and images, with the status codes
+//! and error messages of the real daemon.
+//!
+//! # Example
+//!
+//! ```rust
+//! extern crate rust_docker;
+//!
+//! use rust_docker::api::containers::Containers;
+//! use rust_docker::testing::FakeDocker;
+//!
+//! let docker = FakeDocker::new().with_image("debian:jessie");
+//!
+//! let container = docker
+//!     .create_container_minimal("web", "debian:jessie", vec!["ls".to_string()])
+//!     .unwrap();
+//! docker.start_container(&container.Id).unwrap();
+//! assert_eq!(docker.list_running_containers(None).unwrap().len(), 1);
+//!
+//! // Starting it again is a no-op for the daemon.
+//! assert!(docker.start_container("web").is_err());
+//! ```
+//!
+//! Exchanges with a real daemon can also be recorded to a cassette file and
+//! replayed later, see the `cassette` module.
+pub mod cassette;
+
+pub use self::cassette::{Cassette, Matching, Recorder, Replayer};
+
+use std::str;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "tokio")]
+use async_trait::async_trait;
+use serde_json::{self, json, Value};
+use uuid::Uuid;
+
+use crate::api::api_utils::{MAX_API_VERSION, MIN_API_VERSION};
+use crate::api::containers::Containers;
+use crate::api::images::Images;
+use crate::api::version::Version;
+use crate::api::DockerApiClient;
+#[cfg(feature = "tokio")]
+use crate::asynchronous::containers::AsyncContainers;
+#[cfg(feature = "tokio")]
+use crate::asynchronous::images::AsyncImages;
+#[cfg(feature = "tokio")]
+use crate::asynchronous::version::AsyncVersion;
+#[cfg(feature = "tokio")]
+use crate::asynchronous::AsyncDockerApiClient;
+use crate::multiplexed::{self, StreamType};
+use crate::utils::api::parse_api_version;
+
+/// Timestamp the daemon uses for events which didn't happen yet.
+const ZERO_TIME: &str = "0001-01-01T00:00:00Z";
+
+/// ID of the default bridge network.
+const FAKE_BRIDGE_ID: &str =
+    "f2de39df4171b0dc801e8002d1d999b77256983dfc63041c0f34030aa3977566";
+
+/// A fake docker daemon keeping its containers and images in memory.
+///
+/// Containers can only be created from images added with `with_image`,
+/// their logs are whatever was written with `write_stdout` and
+/// `write_stderr`. Every request received is recorded and can be looked up
+/// with `requests`.
+pub struct FakeDocker {
+    state: Mutex<State>,
+}
+
+#[derive(Default)]
+struct State {
+    images: Vec<FakeImage>,
+    containers: Vec<FakeContainer>,
+    requests: Vec<String>,
+}
+
+struct FakeImage {
+    id: String,
+    tags: Vec<String>,
+    created: u64,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Status {
+    Created,
+    Running,
+    Paused,
+    Exited,
+}
+
+impl Status {
+    fn as_str(self) -> &'static str {
+        match self {
+            Status::Created => "created",
+            Status::Running => "running",
+            Status::Paused => "paused",
+            Status::Exited => "exited",
+        }
+    }
+}
+
+struct FakeContainer {
+    id: String,
+    name: String,
+    image: String,
+    image_id: String,
+    config: Value,
+    host_config: Value,
+    created: u64,
+    status: Status,
+    pid: u64,
+    exit_code: u64,
+    started_at: Option<u64>,
+    finished_at: Option<u64>,
+    /// Stream, time and data of each write to the logs.
+    logs: Vec<(u8, u64, Vec<u8>)>,
+}
+
+impl FakeContainer {
+    fn is_running(&self) -> bool {
+        self.status == Status::Running || self.status == Status::Paused
+    }
+
+    fn tty(&self) -> bool {
+        self.config["Tty"].as_bool().unwrap_or(false)
+    }
+
+    fn cmd(&self) -> Vec<String> {
+        match self.config["Cmd"].as_array() {
+            Some(cmd) => cmd
+                .iter()
+                .filter_map(|arg| arg.as_str().map(|arg| arg.to_string()))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Mount points of the binds of the host config, like `/src:/dst:ro`.
+    fn mounts(&self) -> Vec<Value> {
+        let binds = match self.host_config["Binds"].as_array() {
+            Some(binds) => binds,
+            None => return Vec::new(),
+        };
+
+        binds
+            .iter()
+            .filter_map(|bind| bind.as_str())
+            .map(|bind| {
+                let parts: Vec<&str> = bind.split(':').collect();
+                let mode = parts.get(2).copied().unwrap_or("");
+                json!({
+                    "Type": "bind",
+                    "Source": parts[0],
+                    "Destination": parts.get(1).copied().unwrap_or(parts[0]),
+                    "Mode": mode,
+                    "RW": !mode.split(',').any(|option| option == "ro"),
+                    "Propagation": "rprivate",
+                })
+            })
+            .collect()
+    }
+
+    /// Settings on the default bridge, which only running containers have
+    /// an address on.
+    fn network_settings(&self) -> Value {
+        let mut ports = serde_json::Map::new();
+        let (address, gateway, prefix_len) = if self.is_running() {
+            if let Some(exposed) = self.config["ExposedPorts"].as_object() {
+                for port in exposed.keys() {
+                    ports.insert(port.clone(), Value::Null);
+                }
+            }
+            if let Some(bindings) = self.host_config["PortBindings"].as_object()
+            {
+                for (port, binding) in bindings {
+                    ports.insert(port.clone(), binding.clone());
+                }
+            }
+
+            let host = u8::from_str_radix(&self.id[..2], 16).unwrap_or(0);
+            let address = format!("172.17.0.{}", 2 + host % 250);
+            (address, "172.17.0.1", 16)
+        } else {
+            (String::new(), "", 0)
+        };
+
+        json!({
+            "Bridge": "",
+            "SandboxID": "",
+            "SandboxKey": "",
+            "Ports": ports,
+            "Gateway": gateway,
+            "IPAddress": address,
+            "IPPrefixLen": prefix_len,
+            "MacAddress": "",
+            "Networks": {
+                "bridge": {
+                    "Aliases": null,
+                    "NetworkID": FAKE_BRIDGE_ID,
+                    "EndpointID": "",
+                    "Gateway": gateway,
+                    "IPAddress": address,
+                    "IPPrefixLen": prefix_len,
+                    "MacAddress": "",
+                },
+            },
+        })
+    }
+
+    /// Human readable status, as shown by `docker ps`.
+    fn status_text(&self) -> String {
+        match self.status {
+            Status::Created => "Created".to_string(),
+            Status::Running => "Up Less than a second".to_string(),
+            Status::Paused => "Up Less than a second (Paused)".to_string(),
+            Status::Exited => {
+                format!("Exited ({}) Less than a second ago", self.exit_code)
+            }
+        }
+    }
+
+    fn to_list_json(&self) -> Value {
+        json!({
+            "Id": self.id,
+            "Names": [format!("/{}", self.name)],
+            "Image": self.image,
+            "ImageID": self.image_id,
+            "Command": self.cmd().join(" "),
+            "Created": self.created,
+            "State": self.status.as_str(),
+            "Status": self.status_text(),
+            "Ports": [],
+            "Labels": self.config["Labels"].clone(),
+            "SizeRw": 0,
+            "SizeRootFs": 0,
+            "HostConfig": { "NetworkMode": self.host_config["NetworkMode"] },
+            "Mounts": [],
+        })
+    }
+
+    fn to_inspect_json(&self) -> Value {
+        let cmd = self.cmd();
+        let (path, args) = match cmd.split_first() {
+            Some((path, args)) => (path.clone(), args.to_vec()),
+            None => (String::new(), Vec::new()),
+        };
+
+        json!({
+            "Id": self.id,
+            "Created": rfc3339(self.created),
+            "Path": path,
+            "Args": args,
+            "State": {
+                "Status": self.status.as_str(),
+                "Running": self.is_running(),
+                "Paused": self.status == Status::Paused,
+                "Restarting": false,
+                "OOMKilled": false,
+                "Dead": false,
+                "Pid": self.pid,
+                "ExitCode": self.exit_code,
+                "Error": "",
+                "StartedAt": self.started_at.map_or(ZERO_TIME.to_string(), rfc3339),
+                "FinishedAt": self.finished_at.map_or(ZERO_TIME.to_string(), rfc3339),
+            },
+            "Image": self.image_id,
+            "ResolvConfPath": "",
+            "Name": format!("/{}", self.name),
+            "HostnamePath": "",
+            "HostsPath": "",
+            "LogPath": "",
+            "RestartCount": 0,
+            "Driver": "overlay2",
+            "Platform": "linux",
+            "MountLabel": "",
+            "ProcessLabel": "",
+            "AppArmorProfile": "",
+            "ExecIDs": null,
+            "HostConfig": self.host_config,
+            "GraphDriver": { "Name": "overlay2", "Data": null },
+            "Mounts": self.mounts(),
+            "Config": self.config,
+            "NetworkSettings": self.network_settings(),
+        })
+    }
+}
+
+/// Request received by the fake daemon, without the API version prefix.
+struct FakeRequest {
+    method: String,
+    path: String,
+    query: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl FakeRequest {
+    fn parse(raw: &[u8]) -> Option<FakeRequest> {
+        let (method, target, body) = split_request(raw)?;
+        let (path, query) = split_target(target);
+
+        Some(FakeRequest {
+            method: method.to_string(),
+            path: strip_api_version(path).to_string(),
+            query: parse_query(query),
+            body: body.to_vec(),
+        })
+    }
+
+    fn param(&self, key: &str) -> Option<&str> {
+        self.query
+            .iter()
+            .find(|&(name, _)| name == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Boolean query parameter, the daemon accepts `1` and `true`.
+    fn flag(&self, key: &str) -> bool {
+        match self.param(key) {
+            Some(value) => value == "1" || value.eq_ignore_ascii_case("true"),
+            None => false,
+        }
+    }
+
+    fn summary(&self) -> String {
+        let mut summary = format!("{} {}", self.method, self.path);
+        for (i, (key, value)) in self.query.iter().enumerate() {
+            summary.push(if i == 0 { '?' } else { '&' });
+            summary.push_str(&format!("{}={}", key, value));
+        }
+
+        summary
+    }
+}
+
+impl FakeDocker {
+    /// Creates a new FakeDocker without any container or image.
+    pub fn new() -> FakeDocker {
+        FakeDocker {
+            state: Mutex::new(State::default()),
+        }
+    }
+
+    /// Adds an image which containers can be created from. Tags without a
+    /// version, like `debian`, get the `latest` version.
+    pub fn with_image(self, tag: &str) -> FakeDocker {
+        {
+            let mut state = self.lock();
+            let image = FakeImage {
+                id: format!("sha256:{}", random_id()),
+                tags: vec![normalize_tag(tag)],
+                created: now(),
+            };
+            state.images.push(image);
+        }
+
+        self
+    }
+
+    /// Appends `data` to the standard output of a container, as if the
+    /// container had written it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no such container.
+    pub fn write_stdout(&self, container: &str, data: &str) {
+        self.write_log(container, 1, data)
+    }
+
+    /// Appends `data` to the standard error of a container, as if the
+    /// container had written it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no such container.
+    pub fn write_stderr(&self, container: &str, data: &str) {
+        self.write_log(container, 2, data)
+    }
+
+    /// Requests received so far, as `METHOD /endpoint?query` with the API
+    /// version left out and the query decoded.
+    pub fn requests(&self) -> Vec<String> {
+        self.lock().requests.clone()
+    }
+
+    fn write_log(&self, container: &str, stream: u8, data: &str) {
+        let mut state = self.lock();
+        match find_container(&mut state, container) {
+            Some(container) => {
+                let data = data.as_bytes().to_vec();
+                container.logs.push((stream, now(), data))
+            }
+            None => panic!("No such container: {}", container),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        match self.state.lock() {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    /// Answers a raw request with a raw HTTP response.
+    fn handle(&self, raw: &[u8]) -> Vec<u8> {
+        let request = match FakeRequest::parse(raw) {
+            Some(request) => request,
+            None => return error(400, "malformed HTTP request"),
+        };
+
+        let mut state = self.lock();
+        state.requests.push(request.summary());
+
+        let segments: Vec<&str> =
+            request.path.trim_matches('/').split('/').collect();
+        match (request.method.as_str(), segments.as_slice()) {
+            ("GET", ["_ping"]) | ("HEAD", ["_ping"]) => ping(),
+            ("GET", ["version"]) => json_response(
+                200,
+                json!({
+                    "Platform": { "Name": "Fake Docker" },
+                    "Components": [{
+                        "Name": "Engine",
+                        "Version": "fake",
+                        "Details": { "ApiVersion": MAX_API_VERSION },
+                    }],
+                    "Version": "fake",
+                    "ApiVersion": MAX_API_VERSION,
+                    "MinAPIVersion": MIN_API_VERSION,
+                    "GoVersion": "none",
+                    "Os": "linux",
+                    "Arch": "amd64",
+                }),
+            ),
+            ("GET", ["info"]) => info(&state),
+            ("GET", ["images", "json"]) => list_images(&state),
+            ("GET", ["containers", "json"]) => {
+                list_containers(&state, &request)
+            }
+            ("POST", ["containers", "create"]) => {
+                create_container(&mut state, &request)
+            }
+            ("GET", ["containers", id, "json"]) => {
+                with_container(&mut state, id, |c| {
+                    json_response(200, c.to_inspect_json())
+                })
+            }
+            ("GET", ["containers", id, "changes"]) => {
+                with_container(&mut state, id, |_| {
+                    response(200, "application/json", b"null".to_vec())
+                })
+            }
+            ("GET", ["containers", id, "logs"]) => {
+                with_container(&mut state, id, |c| logs(c, &request))
+            }
+            ("POST", ["containers", id, "rename"]) => {
+                rename_container(&mut state, id, &request)
+            }
+            ("POST", ["containers", id, action]) => {
+                with_container(&mut state, id, |c| {
+                    change_status(c, action, &request)
+                })
+            }
+            ("DELETE", ["containers", id]) => {
+                remove_container(&mut state, id, &request)
+            }
+            _ => error(404, "page not found"),
+        }
+    }
+}
+
+impl Default for FakeDocker {
+    fn default() -> FakeDocker {
+        FakeDocker::new()
+    }
+}
+
+impl DockerApiClient for FakeDocker {
+    fn request(&self, request: &[u8]) -> Option<Vec<u8>> {
+        Some(self.handle(request))
+    }
+}
+
+impl Version for FakeDocker {}
+impl Containers for FakeDocker {}
+impl Images for FakeDocker {}
+
+#[cfg(feature = "tokio")]
+#[async_trait]
+impl AsyncDockerApiClient for FakeDocker {
+    async fn request(&self, request: &[u8]) -> std::io::Result<Vec<u8>> {
+        Ok(self.handle(request))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl AsyncVersion for FakeDocker {}
+#[cfg(feature = "tokio")]
+impl AsyncContainers for FakeDocker {}
+#[cfg(feature = "tokio")]
+impl AsyncImages for FakeDocker {}
+
+fn info(state: &State) -> Vec<u8> {
+    let count = |status: Status| {
+        state
+            .containers
+            .iter()
+            .filter(|c| c.status == status)
+            .count()
+    };
+
+    json_response(
+        200,
+        json!({
+            "ID": "FAKE",
+            "Name": "fake-docker",
+            "Containers": state.containers.len(),
+            "ContainersRunning": count(Status::Running),
+            "ContainersPaused": count(Status::Paused),
+            "ContainersStopped": count(Status::Created) + count(Status::Exited),
+            "Images": state.images.len(),
+            "ServerVersion": "fake",
+            "Driver": "memory",
+            "OperatingSystem": "in-memory",
+            "OSType": "linux",
+            "Architecture": "x86_64",
+            "Warnings": null,
+        }),
+    )
+}
+
+fn list_images(state: &State) -> Vec<u8> {
+    let images: Vec<Value> = state
+        .images
+        .iter()
+        .map(|image| {
+            let containers = state
+                .containers
+                .iter()
+                .filter(|c| c.image_id == image.id)
+                .count();
+            json!({
+                "Id": image.id,
+                "ParentId": "",
+                "RepoTags": image.tags,
+                "RepoDigests": [],
+                "Created": image.created,
+                "Size": 0,
+                "VirtualSize": 0,
+                "SharedSize": -1,
+                "Labels": null,
+                "Containers": containers,
+            })
+        })
+        .collect();
+
+    json_response(200, Value::Array(images))
+}
+
+fn list_containers(state: &State, request: &FakeRequest) -> Vec<u8> {
+    let all = request.flag("all");
+    let limit = request.param("limit").and_then(|l| l.parse::<usize>().ok());
+
+    // Most recently created containers come first.
+    let containers: Vec<Value> = state
+        .containers
+        .iter()
+        .rev()
+        .filter(|c| all || c.is_running())
+        .take(limit.unwrap_or(usize::MAX))
+        .map(FakeContainer::to_list_json)
+        .collect();
+
+    json_response(200, Value::Array(containers))
+}
+
+fn create_container(state: &mut State, request: &FakeRequest) -> Vec<u8> {
+    let mut config: Value = match serde_json::from_slice(&request.body) {
+        Ok(config @ Value::Object(_)) => config,
+        _ => return error(400, "invalid JSON in the container config"),
+    };
+
+    let image = match config["Image"].as_str() {
+        Some(image) if !image.is_empty() => image.to_string(),
+        _ => {
+            return error(
+                400,
+                "Config cannot be empty in order to create a container",
+            )
+        }
+    };
+
+    let image_id = match find_image(state, &image) {
+        Some(image) => image.id.clone(),
+        None => return error(404, &format!("No such image: {}", image)),
+    };
+
+    let id = random_id();
+    let name = match request.param("name") {
+        Some(name) if !name.is_empty() => name.trim_start_matches('/'),
+        _ => &id[..12],
+    }
+    .to_string();
+
+    if let Some(other) = state.containers.iter().find(|c| c.name == name) {
+        return error(
+            409,
+            &format!(
+                "Conflict. The container name \"/{}\" is already in use by \
+                 container \"{}\". You have to remove (or rename) that \
+                 container to be able to reuse that name.",
+                name, other.id
+            ),
+        );
+    }
+
+    // The host and networking configs are sent along with the config, but
+    // aren't part of it.
+    let fields = config.as_object_mut().unwrap();
+    let mut host_config = match fields.remove("HostConfig") {
+        Some(host_config @ Value::Object(_)) => host_config,
+        _ => json!({}),
+    };
+    fields.remove("NetworkingConfig");
+    if host_config["NetworkMode"].is_null() {
+        host_config["NetworkMode"] = json!("default");
+    }
+
+    config["Image"] = Value::String(image.clone());
+    state.containers.push(FakeContainer {
+        id: id.clone(),
+        name,
+        image,
+        image_id,
+        config,
+        host_config,
+        created: now(),
+        status: Status::Created,
+        pid: 0,
+        exit_code: 0,
+        started_at: None,
+        finished_at: None,
+        logs: Vec::new(),
+    });
+
+    json_response(201, json!({ "Id": id, "Warnings": [] }))
+}
+
+fn change_status(
+    container: &mut FakeContainer,
+    action: &str,
+    request: &FakeRequest,
+) -> Vec<u8> {
+    let id = container.id.clone();
+    match action {
+        "start" => match container.status {
+            Status::Running => no_content(304),
+            Status::Paused => error(
+                409,
+                "cannot start a paused container, try unpause instead",
+            ),
+            _ => {
+                start(container);
+                no_content(204)
+            }
+        },
+        "stop" => {
+            if !container.is_running() {
+                return no_content(304);
+            }
+            stop(container, 0);
+            no_content(204)
+        }
+        "restart" => {
+            if container.is_running() {
+                stop(container, 0);
+            }
+            start(container);
+            no_content(204)
+        }
+        "kill" => {
+            if !container.is_running() {
+                return error(
+                    409,
+                    &format!(
+                        "Cannot kill container: {}: Container {} is not running",
+                        id, id
+                    ),
+                );
+            }
+            let signal = request.param("signal").unwrap_or("SIGKILL");
+            stop(container, 128 + signal_number(signal));
+            no_content(204)
+        }
+        "pause" => match container.status {
+            Status::Running => {
+                container.status = Status::Paused;
+                no_content(204)
+            }
+            Status::Paused => {
+                error(409, &format!("Container {} is already paused", id))
+            }
+            _ => error(409, &format!("Container {} is not running", id)),
+        },
+        "unpause" => {
+            if container.status != Status::Paused {
+                return error(409, &format!("Container {} is not paused", id));
+            }
+            container.status = Status::Running;
+            no_content(204)
+        }
+        _ => error(404, "page not found"),
+    }
+}
+
+fn start(container: &mut FakeContainer) {
+    container.status = Status::Running;
+    container.pid = 1000 + container.id.len() as u64;
+    container.exit_code = 0;
+    container.started_at = Some(now());
+}
+
+fn stop(container: &mut FakeContainer, exit_code: u64) {
+    container.status = Status::Exited;
+    container.pid = 0;
+    container.exit_code = exit_code;
+    container.finished_at = Some(now());
+}
+
+fn rename_container(
+    state: &mut State,
+    id: &str,
+    request: &FakeRequest,
+) -> Vec<u8> {
+    let name = match request.param("name") {
+        Some(name) if !name.is_empty() => name.trim_start_matches('/'),
+        _ => return error(400, "Neither old nor new names may be empty"),
+    }
+    .to_string();
+
+    let index = match find_container_index(state, id) {
+        Some(index) => index,
+        None => return no_such_container(id),
+    };
+
+    if let Some(other) = state.containers.iter().find(|c| c.name == name) {
+        if other.id != state.containers[index].id {
+            return error(
+                409,
+                &format!(
+                    "Error when allocating new name: Conflict. The container \
+                     name \"/{}\" is already in use by container \"{}\".",
+                    name, other.id
+                ),
+            );
+        }
+    }
+
+    state.containers[index].name = name;
+    no_content(204)
+}
+
+fn remove_container(
+    state: &mut State,
+    id: &str,
+    request: &FakeRequest,
+) -> Vec<u8> {
+    let index = match find_container_index(state, id) {
+        Some(index) => index,
+        None => return no_such_container(id),
+    };
+
+    let container = &state.containers[index];
+    if container.is_running() && !request.flag("force") {
+        let message = format!(
+            "You cannot remove a {} container {}. Stop the container before \
+             attempting removal or force remove",
+            container.status.as_str(),
+            container.id
+        );
+        return error(409, &message);
+    }
+
+    state.containers.remove(index);
+    no_content(204)
+}
+
+/// Logs of a container, multiplexed in frames like the daemon does unless
+/// the container has a TTY.
+fn logs(container: &FakeContainer, request: &FakeRequest) -> Vec<u8> {
+    let stdout = request.flag("stdout");
+    let stderr = request.flag("stderr");
+    if !stdout && !stderr {
+        return error(
+            400,
+            "Bad parameters: you must choose at least one stream",
+        );
+    }
+
+    let since = request.param("since").and_then(|s| s.parse::<u64>().ok());
+    let until = request.param("until").and_then(|u| u.parse::<u64>().ok());
+    let entries: Vec<&(u8, u64, Vec<u8>)> = container
+        .logs
+        .iter()
+        .filter(|&&(stream, time, _)| {
+            ((stream == 1 && stdout) || (stream == 2 && stderr))
+                && since.is_none_or(|since| time >= since)
+                && until.is_none_or(|until| time < until)
+        })
+        .collect();
+    let skip = match request.param("tail").map(|tail| tail.parse::<usize>()) {
+        Some(Ok(tail)) => entries.len().saturating_sub(tail),
+        _ => 0,
+    };
+
+    let mut body = Vec::new();
+    for &&(stream, time, ref data) in &entries[skip..] {
+        let mut data = data.clone();
+        if request.flag("timestamps") {
+            data.splice(0..0, format!("{} ", rfc3339(time)).into_bytes());
+        }
+
+        match StreamType::from_byte(stream) {
+            Some(stream) if !container.tty() => {
+                // Writing to a Vec never fails.
+                let _ = multiplexed::write_frame(&mut body, stream, &data);
+            }
+            _ => body.extend_from_slice(&data),
+        }
+    }
+
+    let content_type = if container.tty() {
+        "application/vnd.docker.raw-stream"
+    } else {
+        "application/vnd.docker.multiplexed-stream"
+    };
+    response(200, content_type, body)
+}
+
+fn with_container<F>(state: &mut State, id: &str, f: F) -> Vec<u8>
+where
+    F: FnOnce(&mut FakeContainer) -> Vec<u8>,
+{
+    match find_container(state, id) {
+        Some(container) => f(container),
+        None => no_such_container(id),
+    }
+}
+
+/// Looks a container up by ID, unique ID prefix or name, like the daemon.
+fn find_container<'a>(
+    state: &'a mut State,
+    id: &str,
+) -> Option<&'a mut FakeContainer> {
+    let index = find_container_index(state, id)?;
+    state.containers.get_mut(index)
+}
+
+fn find_container_index(state: &State, id: &str) -> Option<usize> {
+    let name = id.trim_start_matches('/');
+    if let Some(index) = state
+        .containers
+        .iter()
+        .position(|c| c.id == id || c.name == name)
+    {
+        return Some(index);
+    }
+
+    let matches: Vec<usize> = state
+        .containers
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| !id.is_empty() && c.id.starts_with(id))
+        .map(|(index, _)| index)
+        .collect();
+    if matches.len() == 1 {
+        Some(matches[0])
+    } else {
+        None
+    }
+}
+
+fn find_image<'a>(state: &'a State, image: &str) -> Option<&'a FakeImage> {
+    let tag = normalize_tag(image);
+    state.images.iter().find(|i| {
+        i.tags.contains(&tag)
+            || i.id == image
+            || i.id.trim_start_matches("sha256:").starts_with(image)
+    })
+}
+
+fn normalize_tag(tag: &str) -> String {
+    let name = tag.rsplit('/').next().unwrap_or(tag);
+    if name.contains(':') {
+        tag.to_string()
+    } else {
+        format!("{}:latest", tag)
+    }
+}
+
+fn no_such_container(id: &str) -> Vec<u8> {
+    error(404, &format!("No such container: {}", id))
+}
+
+fn signal_number(signal: &str) -> u64 {
+    match signal.trim_start_matches("SIG") {
+        "HUP" | "1" => 1,
+        "INT" | "2" => 2,
+        "QUIT" | "3" => 3,
+        "TERM" | "15" => 15,
+        _ => 9,
+    }
+}
+
+fn error(status: usize, message: &str) -> Vec<u8> {
+    json_response(status, json!({ "message": message }))
+}
+
+fn json_response(status: usize, body: Value) -> Vec<u8> {
+    response(status, "application/json", body.to_string().into_bytes())
+}
+
+/// Response without a body, like 204 and 304 answers of the daemon.
+fn no_content(status: usize) -> Vec<u8> {
+    format!(
+        "HTTP/1.1 {} {}\r\nApi-Version: {}\r\n\r\n",
+        status,
+        reason(status),
+        MAX_API_VERSION
+    )
+    .into_bytes()
+}
+
+/// Answer to `/_ping`, along with the headers describing the daemon.
+fn ping() -> Vec<u8> {
+    format!(
+        "HTTP/1.1 200 OK\r\nApi-Version: {}\r\nOSType: linux\r\n\
+         Docker-Experimental: false\r\nBuilder-Version: 1\r\n\
+         Content-Type: text/plain\r\nContent-Length: 2\r\n\r\nOK",
+        MAX_API_VERSION
+    )
+    .into_bytes()
+}
+
+fn response(status: usize, content_type: &str, body: Vec<u8>) -> Vec<u8> {
+    let mut response = format!(
+        "HTTP/1.1 {} {}\r\nApi-Version: {}\r\nContent-Type: {}\r\nContent-Length: {}\r\n\r\n",
+        status,
+        reason(status),
+        MAX_API_VERSION,
+        content_type,
+        body.len()
+    )
+    .into_bytes();
+    response.extend_from_slice(&body);
+    response
+}
+
+fn reason(status: usize) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        204 => "No Content",
+        304 => "Not Modified",
+        400 => "Bad Request",
+        404 => "Not Found",
+        409 => "Conflict",
+        _ => "Internal Server Error",
+    }
+}
+
+/// Splits a raw request in its method, request target and body.
+fn split_request(raw: &[u8]) -> Option<(&str, &str, &[u8])> {
+    let head_end = raw.windows(4).position(|w| w == b"\r\n\r\n")?;
+    let head = str::from_utf8(&raw[..head_end]).ok()?;
+    let request_line = head.lines().next()?;
+
+    let mut parts = request_line.split(' ');
+    let method = parts.next()?;
+    let target = parts.next()?;
+
+    Some((method, target, &raw[head_end + 4..]))
+}
+
+/// Splits a request target in its path and query string.
+fn split_target(target: &str) -> (&str, &str) {
+    match target.find('?') {
+        Some(pos) => (&target[..pos], &target[pos + 1..]),
+        None => (target, ""),
+    }
+}
+
+/// Removes the `/v1.xx` prefix of versioned endpoints.
+fn strip_api_version(path: &str) -> &str {
+    let rest = match path.strip_prefix("/v") {
+        Some(rest) => rest,
+        None => return path,
+    };
+
+    match rest.find('/') {
+        Some(pos) if parse_api_version(&rest[..pos]).is_some() => &rest[pos..],
+        _ => path,
+    }
+}
+
+fn parse_query(query: &str) -> Vec<(String, String)> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let mut parts = pair.splitn(2, '=');
+            let key = percent_decode(parts.next().unwrap_or(""));
+            let value = percent_decode(parts.next().unwrap_or(""));
+            (key, value)
+        })
+        .collect()
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (b'+', _) => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// A 64 hexadecimal characters ID, like the ones of containers and images.
+fn random_id() -> String {
+    format!(
+        "{}{}",
+        Uuid::new_v4().to_simple(),
+        Uuid::new_v4().to_simple()
+    )
+}
+
+fn now() -> u64 {
+    match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(duration) => duration.as_secs(),
+        Err(_) => 0,
+    }
+}
+
+/// Formats a UNIX timestamp like the daemon, e.g. `2018-06-27T10:55:17Z`.
+fn rfc3339(secs: u64) -> String {
+    let days = (secs / 86400) as i64;
+    let time = secs % 86400;
+
+    // Civil date from the days since the epoch, see
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
@@ -0,0 +1,748 @@
+//! Transports used by the DockerClient to reach the docker daemon.
+use std::cell::Cell;
+use std::io;
+use std::io::Read;
+use std::io::Write;
+use std::net::{Shutdown, TcpStream, ToSocketAddrs};
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use openssl::error::ErrorStack;
+use openssl::ssl::{
+    HandshakeError, SslConnector, SslFiletype, SslMethod, SslStream,
+    SslVerifyMode,
+};
+use openssl::x509::X509VerifyResult;
+
+use crate::errors::DockerClientError;
+
+/// A bidirectional byte stream connected to the docker daemon.
+///
+/// The DockerClient only ever writes raw HTTP requests to the stream and
+/// reads the responses back, so anything which can do that can be used as
+/// a transport. Streams are handed between threads by the connection pool,
+/// so they must be `Send`.
+pub trait Stream: Read + Write + Send {
+    /// Sets the timeout of every read and write on the stream, `None`
+    /// blocking forever.
+    ///
+    /// Streams which can't time out, like the stdio of a process, ignore it.
+    fn set_timeout(&self, _timeout: Option<Duration>) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Shuts the writing side of the stream down, telling the daemon no
+    /// more data will be sent while still reading what it sends back. This
+    /// is how the end of the input of an attached process is signaled.
+    ///
+    /// Streams which can't be half closed fail with
+    /// `io::ErrorKind::Unsupported`.
+    fn shutdown_write(&mut self) -> io::Result<()> {
+        Err(unsupported("Half closing the stream is not supported"))
+    }
+
+    /// Splits the stream into a reading and a writing half, which can be
+    /// used from different threads.
+    ///
+    /// Streams which can't be split fail with `io::ErrorKind::Unsupported`.
+    fn split(
+        self: Box<Self>,
+    ) -> io::Result<(Box<dyn Read + Send>, Box<dyn WriteStream>)> {
+        Err(unsupported("Splitting the stream is not supported"))
+    }
+}
+
+/// The writing half of a stream split with `Stream::split`.
+pub trait WriteStream: Write + Send {
+    /// Shuts the writing side of the stream down, see
+    /// `Stream::shutdown_write`.
+    fn shutdown_write(&mut self) -> io::Result<()>;
+}
+
+impl Stream for TcpStream {
+    fn set_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.set_read_timeout(timeout)?;
+        self.set_write_timeout(timeout)
+    }
+
+    fn shutdown_write(&mut self) -> io::Result<()> {
+        self.shutdown(Shutdown::Write)
+    }
+
+    fn split(
+        self: Box<Self>,
+    ) -> io::Result<(Box<dyn Read + Send>, Box<dyn WriteStream>)> {
+        let write = self.try_clone()?;
+        Ok((self, Box::new(write)))
+    }
+}
+
+impl WriteStream for TcpStream {
+    fn shutdown_write(&mut self) -> io::Result<()> {
+        self.shutdown(Shutdown::Write)
+    }
+}
+
+impl Stream for UnixStream {
+    fn set_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.set_read_timeout(timeout)?;
+        self.set_write_timeout(timeout)
+    }
+
+    fn shutdown_write(&mut self) -> io::Result<()> {
+        self.shutdown(Shutdown::Write)
+    }
+
+    fn split(
+        self: Box<Self>,
+    ) -> io::Result<(Box<dyn Read + Send>, Box<dyn WriteStream>)> {
+        let write = self.try_clone()?;
+        Ok((self, Box::new(write)))
+    }
+}
+
+impl WriteStream for UnixStream {
+    fn shutdown_write(&mut self) -> io::Result<()> {
+        self.shutdown(Shutdown::Write)
+    }
+}
+
+/// Timeouts applied to the requests made by the DockerClient, `None` waits
+/// forever, which is the default for all of them.
+///
+/// * connect: Opening a new TCP connection to the daemon, TLS handshake
+///   included.
+/// * read: Waiting on the daemon, for each read or write on the connection.
+/// * request: The whole exchange, from writing the request to reading the
+///   last byte of the response.
+///
+/// Over SSH, the ssh client is killed once a timeout expires, see
+/// `SshStream`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Timeouts {
+    pub connect: Option<Duration>,
+    pub read: Option<Duration>,
+    pub request: Option<Duration>,
+}
+
+impl Timeouts {
+    /// Sets the timeout for opening a new connection to the daemon.
+    pub fn connect(mut self, timeout: Option<Duration>) -> Timeouts {
+        self.connect = timeout;
+        self
+    }
+
+    /// Sets the timeout for each read or write on the connection.
+    pub fn read(mut self, timeout: Option<Duration>) -> Timeouts {
+        self.read = timeout;
+        self
+    }
+
+    /// Sets the timeout for the whole request.
+    pub fn request(mut self, timeout: Option<Duration>) -> Timeouts {
+        self.request = timeout;
+        self
+    }
+}
+
+/// Connects to a `host:port` address, trying each address it resolves to in
+/// turn, and giving up on each after `timeout` if provided.
+pub fn connect_tcp(
+    addr: &str,
+    timeout: Option<Duration>,
+) -> Result<TcpStream, DockerClientError> {
+    let timeout = match timeout {
+        Some(timeout) => timeout,
+        None => match TcpStream::connect(addr) {
+            Ok(sock) => return Ok(sock),
+            Err(_err) => {
+                return Err(DockerClientError::SocketConnectionError(
+                    addr.to_string(),
+                ))
+            }
+        },
+    };
+
+    let socket_addrs = match addr.to_socket_addrs() {
+        Ok(socket_addrs) => socket_addrs,
+        Err(_err) => {
+            return Err(DockerClientError::SocketConnectionError(
+                addr.to_string(),
+            ))
+        }
+    };
+
+    let mut timed_out = false;
+    for socket_addr in socket_addrs {
+        match TcpStream::connect_timeout(&socket_addr, timeout) {
+            Ok(sock) => {
+                // Bound the TLS handshake by the same timeout.
+                if sock.set_timeout(Some(timeout)).is_err() {
+                    continue;
+                }
+                return Ok(sock);
+            }
+            Err(ref err) if err.kind() == io::ErrorKind::TimedOut => {
+                timed_out = true
+            }
+            Err(_) => {}
+        }
+    }
+
+    if timed_out {
+        Err(DockerClientError::ConnectionTimeout(addr.to_string()))
+    } else {
+        Err(DockerClientError::SocketConnectionError(addr.to_string()))
+    }
+}
+
+/// TLS material used to secure the connection to the docker daemon.
+///
+/// This follows the layout used by the docker CLI, where the CA certificate,
+/// the client certificate and its private key are stored as PEM files.
+///
+/// * ca: CA certificate used to verify the daemon (`ca.pem`)
+/// * cert: Client certificate presented to the daemon (`cert.pem`), if any.
+/// * key: Private key for the client certificate (`key.pem`), if any.
+/// * verify_peer: Verify the daemon certificate against the CA(true by
+///   default.)
+/// * verify_hostname: Check the daemon certificate against the host we
+///   are connecting to(true by default.)
+#[derive(Clone, Debug)]
+pub struct TlsConfig {
+    pub ca: PathBuf,
+    pub cert: Option<PathBuf>,
+    pub key: Option<PathBuf>,
+    pub verify_peer: bool,
+    pub verify_hostname: bool,
+}
+
+impl TlsConfig {
+    /// Creates a new TlsConfig from the paths to the CA certificate, the
+    /// client certificate and the client key.
+    pub fn new<P: AsRef<Path>>(ca: P, cert: P, key: P) -> TlsConfig {
+        TlsConfig {
+            ca: ca.as_ref().to_path_buf(),
+            cert: Some(cert.as_ref().to_path_buf()),
+            key: Some(key.as_ref().to_path_buf()),
+            verify_peer: true,
+            verify_hostname: true,
+        }
+    }
+
+    /// Creates a new TlsConfig verifying the daemon against the provided CA
+    /// certificate, without presenting any client certificate. This is for
+    /// daemons which don't authenticate their clients.
+    pub fn from_ca<P: AsRef<Path>>(ca: P) -> TlsConfig {
+        TlsConfig {
+            ca: ca.as_ref().to_path_buf(),
+            cert: None,
+            key: None,
+            verify_peer: true,
+            verify_hostname: true,
+        }
+    }
+
+    /// Creates a new TlsConfig from a directory containing `ca.pem`,
+    /// `cert.pem` and `key.pem`, like the one `DOCKER_CERT_PATH` points to.
+    pub fn from_cert_path<P: AsRef<Path>>(cert_path: P) -> TlsConfig {
+        let dir = cert_path.as_ref();
+        TlsConfig::new(
+            dir.join("ca.pem"),
+            dir.join("cert.pem"),
+            dir.join("key.pem"),
+        )
+    }
+
+    /// Enable or disable verification of the daemon hostname against its
+    /// certificate. The certificate chain is verified regardless.
+    pub fn verify_hostname(mut self, verify: bool) -> TlsConfig {
+        self.verify_hostname = verify;
+        self
+    }
+
+    /// Enable or disable verification of the daemon certificate altogether.
+    ///
+    /// This is what the docker CLI does when TLS is used without
+    /// `DOCKER_TLS_VERIFY`, missing CA or client certificate files are
+    /// skipped instead of being reported as errors in that case.
+    pub fn verify_peer(mut self, verify: bool) -> TlsConfig {
+        self.verify_peer = verify;
+        self
+    }
+
+    fn connector(&self) -> Result<SslConnector, ErrorStack> {
+        let mut builder = SslConnector::builder(SslMethod::tls_client())?;
+        if self.verify_peer {
+            builder.set_ca_file(&self.ca)?;
+        } else {
+            builder.set_verify(SslVerifyMode::NONE);
+            if self.ca.exists() {
+                builder.set_ca_file(&self.ca)?;
+            }
+        }
+
+        if let (Some(cert), Some(key)) = (&self.cert, &self.key) {
+            if self.verify_peer || (cert.exists() && key.exists()) {
+                builder.set_certificate_chain_file(cert)?;
+                builder.set_private_key_file(key, SslFiletype::PEM)?;
+                builder.check_private_key()?;
+            }
+        }
+
+        Ok(builder.build())
+    }
+}
+
+/// A TLS session with the docker daemon.
+pub struct TlsStream {
+    inner: SslStream<TcpStream>,
+}
+
+impl TlsStream {
+    /// Performs the TLS handshake with the daemon over an already connected
+    /// TCP stream. `addr` is the `host:port` address of the daemon, the host
+    /// part of which is used for SNI and hostname verification.
+    pub fn connect(
+        addr: &str,
+        stream: TcpStream,
+        config: &TlsConfig,
+    ) -> Result<TlsStream, DockerClientError> {
+        let connector = match config.connector() {
+            Ok(connector) => connector,
+            Err(err) => {
+                return Err(DockerClientError::TlsConfigError(err.to_string()))
+            }
+        };
+
+        let mut ssl_config = match connector.configure() {
+            Ok(ssl_config) => ssl_config,
+            Err(err) => {
+                return Err(DockerClientError::TlsConfigError(err.to_string()))
+            }
+        };
+        ssl_config
+            .set_verify_hostname(config.verify_peer && config.verify_hostname);
+
+        let host = match addr.rfind(':') {
+            Some(pos) => addr[..pos].trim_matches(|c| c == '[' || c == ']'),
+            None => addr,
+        };
+
+        match ssl_config.connect(host, stream) {
+            Ok(ssl_stream) => Ok(TlsStream { inner: ssl_stream }),
+            Err(HandshakeError::Failure(mid)) => {
+                let verify_result = mid.ssl().verify_result();
+                if verify_result != X509VerifyResult::OK {
+                    Err(DockerClientError::TlsVerificationError(
+                        addr.to_string(),
+                        verify_result.error_string().to_string(),
+                    ))
+                } else {
+                    Err(DockerClientError::TlsHandshakeError(
+                        addr.to_string(),
+                        mid.error().to_string(),
+                    ))
+                }
+            }
+            Err(HandshakeError::SetupFailure(err)) => {
+                Err(DockerClientError::TlsConfigError(err.to_string()))
+            }
+            // The stream is blocking, the handshake timed out.
+            Err(HandshakeError::WouldBlock(_)) => {
+                Err(DockerClientError::ConnectionTimeout(addr.to_string()))
+            }
+        }
+    }
+}
+
+/// TLS sessions can be half closed, by sending the `close_notify` alert,
+/// but not split since reads and writes share the state of the session.
+impl Stream for TlsStream {
+    fn set_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.inner.get_ref().set_timeout(timeout)
+    }
+
+    fn shutdown_write(&mut self) -> io::Result<()> {
+        match self.inner.shutdown() {
+            Ok(_) => Ok(()),
+            Err(err) => match err.into_io_error() {
+                Ok(err) => Err(err),
+                Err(err) => Err(io::Error::other(err.to_string())),
+            },
+        }
+    }
+}
+
+impl Read for TlsStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl Write for TlsStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Options for reaching a remote docker daemon over SSH.
+///
+/// The ssh client is spawned running `docker system dial-stdio` on the remote
+/// host, and the Engine API is spoken over its stdin/stdout, the same way the
+/// docker CLI handles `ssh://` hosts.
+///
+/// * binary: ssh client to spawn(`ssh` by default.)
+/// * options: Extra arguments passed to the ssh client before the host.
+#[derive(Clone, Debug)]
+pub struct SshConfig {
+    pub binary: PathBuf,
+    pub options: Vec<String>,
+}
+
+impl Default for SshConfig {
+    fn default() -> SshConfig {
+        SshConfig {
+            binary: PathBuf::from("ssh"),
+            options: Vec::new(),
+        }
+    }
+}
+
+impl SshConfig {
+    /// Use the provided ssh client instead of the `ssh` found in PATH.
+    pub fn binary<P: AsRef<Path>>(mut self, binary: P) -> SshConfig {
+        self.binary = binary.as_ref().to_path_buf();
+        self
+    }
+
+    /// Pass an extra argument to the ssh client, like `-i` or `-o`.
+    pub fn option<S: Into<String>>(mut self, option: S) -> SshConfig {
+        self.options.push(option.into());
+        self
+    }
+
+    /// Arguments for the ssh client to run `docker system dial-stdio` on
+    /// `host`, optionally as `user` and on a non default `port`.
+    pub fn args(
+        &self,
+        user: Option<&str>,
+        host: &str,
+        port: Option<u16>,
+    ) -> Vec<String> {
+        let mut args = self.options.clone();
+        // Never allocate a tty, it would mangle the binary stream.
+        args.push("-T".to_string());
+
+        if let Some(user) = user {
+            args.push("-l".to_string());
+            args.push(user.to_string());
+        }
+        if let Some(port) = port {
+            args.push("-p".to_string());
+            args.push(port.to_string());
+        }
+
+        args.push("--".to_string());
+        args.push(host.to_string());
+        for arg in &["docker", "system", "dial-stdio"] {
+            args.push(arg.to_string());
+        }
+
+        args
+    }
+}
+
+/// A connection to the docker daemon through the stdin/stdout of an ssh
+/// client running `docker system dial-stdio`.
+///
+/// Each connection runs its own ssh client, which is killed once the
+/// connection, or both of its halves once split, is dropped.
+///
+/// The stdio of a process can't time out, so a read or write outlasting the
+/// timeout set with `Stream::set_timeout` has the ssh client killed by a
+/// watchdog thread instead, and fails with `io::ErrorKind::TimedOut`. The
+/// connection can't be used anymore then.
+pub struct SshStream {
+    child: Arc<SshChild>,
+    stdin: Option<ChildStdin>,
+    stdout: ChildStdout,
+    timeout: Cell<Option<Duration>>,
+}
+
+/// The ssh client process, killed once dropped.
+struct SshChild {
+    watchdog: Arc<Watchdog>,
+}
+
+impl Drop for SshChild {
+    fn drop(&mut self) {
+        let mut state = self.watchdog.lock();
+        let _ = state.process.kill();
+        let _ = state.process.wait();
+        state.dropped = true;
+        self.watchdog.changed.notify_one();
+    }
+}
+
+impl SshChild {
+    fn new(process: Child) -> SshChild {
+        let state = WatchdogState {
+            process,
+            deadline: None,
+            watching: false,
+            timed_out: false,
+            dropped: false,
+        };
+
+        SshChild {
+            watchdog: Arc::new(Watchdog {
+                state: Mutex::new(state),
+                changed: Condvar::new(),
+            }),
+        }
+    }
+
+    /// Runs `io` on the stdio of the ssh client, which is killed if it
+    /// takes longer than `timeout`.
+    fn watch<T, F>(&self, timeout: Option<Duration>, io: F) -> io::Result<T>
+    where
+        F: FnOnce() -> io::Result<T>,
+    {
+        if let Some(timeout) = timeout {
+            let mut state = self.watchdog.lock();
+            // The thread is only started once a timeout is used.
+            if !state.watching {
+                let watchdog = self.watchdog.clone();
+                thread::Builder::new()
+                    .name("ssh-watchdog".to_string())
+                    .spawn(move || watchdog.run())?;
+                state.watching = true;
+            }
+            state.deadline = Some(Instant::now() + timeout);
+            self.watchdog.changed.notify_one();
+        }
+
+        let result = io();
+
+        let mut state = self.watchdog.lock();
+        state.deadline = None;
+        if state.timed_out {
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "The ssh client was killed after timing out",
+            ));
+        }
+        result
+    }
+}
+
+/// Kills the ssh client once the deadline of the read or write in progress
+/// has passed, see `SshStream`.
+struct Watchdog {
+    state: Mutex<WatchdogState>,
+    changed: Condvar,
+}
+
+struct WatchdogState {
+    process: Child,
+    deadline: Option<Instant>,
+    watching: bool,
+    timed_out: bool,
+    dropped: bool,
+}
+
+impl Watchdog {
+    fn lock(&self) -> MutexGuard<'_, WatchdogState> {
+        // A panic while holding the lock can't leave the state inconsistent,
+        // so keep using it.
+        match self.state.lock() {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    /// Waits on the deadlines until the ssh client is dropped.
+    fn run(&self) {
+        let mut state = self.lock();
+        while !state.dropped {
+            let deadline = state.deadline;
+            state = match deadline {
+                Some(deadline) if Instant::now() >= deadline => {
+                    let _ = state.process.kill();
+                    state.timed_out = true;
+                    state.deadline = None;
+                    state
+                }
+                Some(deadline) => {
+                    let timeout = deadline - Instant::now();
+                    match self.changed.wait_timeout(state, timeout) {
+                        Ok((state, _)) => state,
+                        Err(poisoned) => poisoned.into_inner().0,
+                    }
+                }
+                None => match self.changed.wait(state) {
+                    Ok(state) => state,
+                    Err(poisoned) => poisoned.into_inner(),
+                },
+            };
+        }
+    }
+}
+
+/// One half of a split SshStream, keeping the ssh client alive.
+struct SshHalf<T> {
+    _child: Arc<SshChild>,
+    io: T,
+}
+
+impl SshStream {
+    /// Spawns the ssh client for `host` with the provided configuration.
+    pub fn connect(
+        user: Option<&str>,
+        host: &str,
+        port: Option<u16>,
+        config: &SshConfig,
+    ) -> Result<SshStream, DockerClientError> {
+        let spawned = Command::new(&config.binary)
+            .args(config.args(user, host, port))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn();
+
+        let mut child = match spawned {
+            Ok(child) => child,
+            Err(err) => {
+                return Err(DockerClientError::SshCommandError(
+                    config.binary.display().to_string(),
+                    err.to_string(),
+                ))
+            }
+        };
+
+        let (stdin, stdout) = match (child.stdin.take(), child.stdout.take()) {
+            (Some(stdin), Some(stdout)) => (stdin, stdout),
+            _ => {
+                let _ = child.kill();
+                return Err(DockerClientError::SshCommandError(
+                    config.binary.display().to_string(),
+                    "could not open the ssh client stdio".to_string(),
+                ));
+            }
+        };
+
+        Ok(SshStream {
+            child: Arc::new(SshChild::new(child)),
+            stdin: Some(stdin),
+            stdout,
+            timeout: Cell::new(None),
+        })
+    }
+}
+
+/// The writing side is shut down by closing the stdin of the ssh client.
+impl Stream for SshStream {
+    fn set_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.timeout.set(timeout);
+        Ok(())
+    }
+
+    fn shutdown_write(&mut self) -> io::Result<()> {
+        self.stdin = None;
+        Ok(())
+    }
+
+    fn split(
+        self: Box<Self>,
+    ) -> io::Result<(Box<dyn Read + Send>, Box<dyn WriteStream>)> {
+        let read = SshHalf {
+            _child: self.child.clone(),
+            io: self.stdout,
+        };
+        let write = SshHalf {
+            _child: self.child,
+            io: self.stdin,
+        };
+        Ok((Box::new(read), Box::new(write)))
+    }
+}
+
+impl Read for SshStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let stdout = &mut self.stdout;
+        self.child.watch(self.timeout.get(), || stdout.read(buf))
+    }
+}
+
+impl Write for SshStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let stdin = &mut self.stdin;
+        self.child
+            .watch(self.timeout.get(), || write_stdin(stdin, buf))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let stdin = &mut self.stdin;
+        self.child.watch(self.timeout.get(), || flush_stdin(stdin))
+    }
+}
+
+impl Read for SshHalf<ChildStdout> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.io.read(buf)
+    }
+}
+
+impl Write for SshHalf<Option<ChildStdin>> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        write_stdin(&mut self.io, buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        flush_stdin(&mut self.io)
+    }
+}
+
+impl WriteStream for SshHalf<Option<ChildStdin>> {
+    fn shutdown_write(&mut self) -> io::Result<()> {
+        self.io = None;
+        Ok(())
+    }
+}
+
+fn write_stdin(
+    stdin: &mut Option<ChildStdin>,
+    buf: &[u8],
+) -> io::Result<usize> {
+    match *stdin {
+        Some(ref mut stdin) => stdin.write(buf),
+        None => Err(write_shut_down()),
+    }
+}
+
+fn flush_stdin(stdin: &mut Option<ChildStdin>) -> io::Result<()> {
+    match *stdin {
+        Some(ref mut stdin) => stdin.flush(),
+        None => Ok(()),
+    }
+}
+
+fn write_shut_down() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::BrokenPipe,
+        "The writing side of the stream was shut down",
+    )
+}
+
+fn unsupported(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::Unsupported, msg.to_string())
+}
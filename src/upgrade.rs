@@ -0,0 +1,203 @@
+//! Connections upgraded to raw bidirectional streams.
+//!
+//! Endpoints like attach or exec start answer a request with the
+//! `Upgrade: tcp` header by switching protocols, the connection then
+//! carries the input and output of the process as is, in both directions,
+//! instead of HTTP. `DockerApiClient::send_upgrade_request` performs the
+//! upgrade and hands the connection back as an `Upgraded` stream.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! extern crate rust_docker;
+//!
+//! use std::io::{self, Write};
+//! use std::thread;
+//!
+//! use rust_docker::api::request::Request;
+//! use rust_docker::api::DockerApiClient;
+//! use rust_docker::client::DockerClient;
+//!
+//! let client = DockerClient::new("unix:///var/run/docker.sock").unwrap();
+//!
+//! let request = Request::post("/containers/f808ca/attach")
+//!     .query("stream", true)
+//!     .query("stdin", true)
+//!     .query("stdout", true);
+//! let upgraded = client.send_upgrade_request(request).unwrap();
+//! let (mut output, mut input) = upgraded.split().unwrap();
+//!
+//! let printer = thread::spawn(move || io::copy(&mut output, &mut io::stdout()));
+//! input.write_all(b"echo hello\n").unwrap();
+//! // The process sees the end of its input, while its output is still read.
+//! input.close_write().unwrap();
+//! printer.join().unwrap().unwrap();
+//! ```
+use std::collections::HashMap;
+use std::io;
+use std::io::{BufRead, BufReader, Cursor, Read, Write};
+use std::time::Duration;
+
+use crate::transport::{Stream, WriteStream};
+use crate::utils::api::{
+    body_framing, find_header, read_http_body, read_http_head,
+};
+
+/// A connection to the daemon upgraded to a raw stream, reading the output
+/// sent by the daemon and writing the input sent to it.
+///
+/// The stream can be used as it is from a single thread, or split into a
+/// reading and a writing half with `split`.
+pub struct Upgraded {
+    status_code: usize,
+    headers: HashMap<String, String>,
+    head: Vec<u8>,
+    reader: BufReader<Box<dyn Stream>>,
+}
+
+impl Upgraded {
+    /// Reads the head of the response to an upgrade request off `stream`,
+    /// which the request was written to, and wraps the connection.
+    ///
+    /// Informational responses other than `101 Switching Protocols` are
+    /// skipped. Whether the daemon actually upgraded the connection is told
+    /// by `status_code`.
+    pub fn read_response(stream: Box<dyn Stream>) -> io::Result<Upgraded> {
+        let mut reader = BufReader::new(stream);
+        loop {
+            let mut head = Vec::new();
+            let (status_code, headers) =
+                read_http_head(&mut reader, &mut head)?;
+            if status_code / 100 == 1 && status_code != 101 {
+                continue;
+            }
+
+            return Ok(Upgraded {
+                status_code,
+                headers,
+                head,
+                reader,
+            });
+        }
+    }
+
+    /// Status code of the response to the upgrade request, `101` once the
+    /// connection was upgraded. Older daemons hijack the connection without
+    /// switching protocols, answering with `200` instead.
+    pub fn status_code(&self) -> usize {
+        self.status_code
+    }
+
+    /// Returns the value of the header `name` of the response to the
+    /// upgrade request, header names are matched case insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+
+    /// Sets the timeout of every read and write on the stream, `None`
+    /// blocking forever.
+    pub fn set_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.reader.get_ref().set_timeout(timeout)
+    }
+
+    /// Shuts the writing side of the stream down, telling the process its
+    /// input ended while still reading its output.
+    pub fn close_write(&mut self) -> io::Result<()> {
+        self.reader.get_mut().shutdown_write()
+    }
+
+    /// Splits the stream into a reading and a writing half, so that the
+    /// output of the process can be read while its input is written from
+    /// another thread.
+    ///
+    /// Fails with `io::ErrorKind::Unsupported` over TLS, where both
+    /// directions share the state of the session.
+    pub fn split(self) -> io::Result<(ReadHalf, WriteHalf)> {
+        // Output the daemon sent along with the response head.
+        let buffered = self.reader.buffer().to_vec();
+        let (read, write) = self.reader.into_inner().split()?;
+
+        let read_half = ReadHalf {
+            inner: Cursor::new(buffered).chain(read),
+        };
+        Ok((read_half, WriteHalf { inner: write }))
+    }
+
+    /// Status code, headers and connection, read up to the end of the
+    /// response head.
+    pub(crate) fn into_parts(
+        self,
+    ) -> (usize, HashMap<String, String>, BufReader<Box<dyn Stream>>) {
+        (self.status_code, self.headers, self.reader)
+    }
+
+    /// Reads the rest of a response which didn't upgrade the connection,
+    /// and returns it whole, head included.
+    pub(crate) fn into_response(mut self) -> io::Result<Vec<u8>> {
+        let mut raw_resp = self.head;
+        let framing = body_framing(self.status_code, &self.headers, false)?;
+        read_http_body(&mut self.reader, framing, &mut raw_resp)?;
+
+        Ok(raw_resp)
+    }
+}
+
+impl Read for Upgraded {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.reader.read(buf)
+    }
+}
+
+impl BufRead for Upgraded {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.reader.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.reader.consume(amt)
+    }
+}
+
+impl Write for Upgraded {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.reader.get_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.reader.get_mut().flush()
+    }
+}
+
+/// The reading half of an `Upgraded` stream, the output of the process.
+pub struct ReadHalf {
+    inner: io::Chain<Cursor<Vec<u8>>, Box<dyn Read + Send>>,
+}
+
+impl Read for ReadHalf {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+/// The writing half of an `Upgraded` stream, the input of the process.
+pub struct WriteHalf {
+    inner: Box<dyn WriteStream>,
+}
+
+impl WriteHalf {
+    /// Shuts the writing side of the stream down, telling the process its
+    /// input ended.
+    pub fn close_write(&mut self) -> io::Result<()> {
+        self.inner.shutdown_write()
+    }
+}
+
+impl Write for WriteHalf {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
@@ -1,23 +1,34 @@
 use std::collections::HashMap;
+use std::io;
+use std::io::{BufRead, Cursor, Read};
 use std::path::Path;
 
 use std::str;
-use std::usize;
 
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer};
 use serde_json;
 
-use errors::DockerApiError;
+use crate::api::api_utils;
+use crate::errors::DockerApiError;
 
 // This implementation of HTTP response parsing is mostly taken from
 // https://github.com/p00s/minihttpse
 // with minor changes.
 const CR: u8 = b'\r';
-const LF: u8 = b'\n';
+pub(crate) const LF: u8 = b'\n';
 
+/// * status_code: Status code of the response.
+/// * headers: Headers of the response.
+/// * body: Raw body of the response, with any chunked transfer encoding
+///   decoded. It is kept as bytes, the body of endpoints like container
+///   export or image save being binary, see `text` and `json` for the
+///   others.
 #[derive(Debug)]
 pub struct Response {
     pub status_code: usize,
-    pub body: String,
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
 }
 
 /// Response represent a minimal HTTP response that we are concerned with
@@ -30,27 +41,18 @@ impl Response {
     pub fn parse_http_response(
         res: Vec<u8>,
     ) -> Result<Response, DockerApiError> {
-        let mut pos: usize = 0;
-        for i in 0..(res.len() - 1) {
-            if res[i] == CR
-                && res[i + 1] == LF
-                && res[i + 2] == CR
-                && res[i + 3] == LF
-            {
-                pos = i + 3;
-                break;
+        let pos = match res.windows(4).position(|w| w == [CR, LF, CR, LF]) {
+            Some(pos) => pos + 4,
+            None => {
+                return Err(DockerApiError::HTTPResponseParseError(
+                    "Not a valid HTTP response",
+                ))
             }
-        }
-
-        if pos == 0 {
-            return Err(DockerApiError::HTTPResponseParseError(
-                "Not a valid HTTP response",
-            ));
-        }
+        };
 
         let (resp_header, resp_body): (&[u8], &[u8]) = res.split_at(pos);
 
-        let header_info = match String::from_utf8(resp_header.to_vec()) {
+        let header_info = match str::from_utf8(resp_header) {
             Ok(h) => h,
             Err(_) => {
                 return Err(DockerApiError::HTTPResponseParseError(
@@ -59,56 +61,59 @@ impl Response {
             }
         };
 
-        let body = resp_body[1..].to_owned();
-
-        let mut header_vec: Vec<&str> = header_info.split("\r\n").collect();
-        let status = header_vec[0].to_owned();
-        let status_vec: Vec<&str> = status.splitn(3, " ").collect();
-
-        let status_code: usize = match status_vec[1].parse() {
-            Ok(s) => s,
-            Err(_) => {
-                return Err(DockerApiError::HTTPResponseParseError(
-                    "Error while parsing HTTP status code",
-                ))
+        let (status_code, headers) = match parse_http_head(header_info) {
+            Ok(head) => head,
+            Err(err) => {
+                return Err(DockerApiError::HTTPResponseParseError(err))
             }
         };
 
-        header_vec.remove(0);
-        let len = header_vec.len();
-        header_vec.remove(len - 1);
+        let body = if is_chunked(&headers) {
+            Response::parse_chunk(resp_body.to_vec())?
+        } else {
+            resp_body.to_vec()
+        };
+
+        Ok(Response {
+            status_code,
+            headers,
+            body,
+        })
+    }
 
-        let mut headers: HashMap<String, String> = HashMap::new();
-        for header in header_vec {
-            let item = header.to_owned();
-            let item_vec: Vec<&str> = item.splitn(2, ": ").collect();
-            headers.insert(item_vec[0].to_owned(), item_vec[1].to_owned());
+    /// The body as text, without the whitespace around it, for JSON and
+    /// plain text endpoints. Fails if the body is not valid UTF-8.
+    pub fn text(&self) -> Result<&str, DockerApiError> {
+        match str::from_utf8(&self.body) {
+            Ok(text) => Ok(text.trim()),
+            Err(_) => Err(DockerApiError::HTTPResponseParseError(
+                "Error while parsing response body",
+            )),
         }
+    }
 
-        let body = match headers.get("Transfer-Encoding") {
-            Some(enc) => {
-                if enc == "chunked" {
-                    Response::parse_chunk(body)?
-                } else {
-                    body
-                }
-            }
-            None => body,
-        };
+    /// Deserializes the JSON body of the response.
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T, DockerApiError> {
+        match serde_json::from_slice(&self.body) {
+            Ok(value) => Ok(value),
+            Err(err) => Err(DockerApiError::JsonDeserializationError(err)),
+        }
+    }
 
-        let response = match String::from_utf8(body) {
-            Ok(s) => s.trim().to_owned(),
-            Err(_) => {
-                return Err(DockerApiError::HTTPResponseParseError(
-                    "Error while parsing response body",
-                ))
-            }
-        };
+    /// Gives the body back as a reader, to be handed to anything reading
+    /// a stream, like a `tar::Archive` or a `multiplexed::Demultiplexer`.
+    ///
+    /// The whole body is kept in memory, see
+    /// `DockerApiClient::send_request_streaming` to read large bodies as
+    /// they arrive instead.
+    pub fn into_reader(self) -> Cursor<Vec<u8>> {
+        Cursor::new(self.body)
+    }
 
-        Ok(Response {
-            status_code: status_code,
-            body: response,
-        })
+    /// Returns the value of the header `name`, header names are matched
+    /// case insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
     }
 
     /// A helper function to parse_http_reseponse, when the Header Transfer-Encoding
@@ -118,18 +123,15 @@ impl Response {
         let mut count: usize = 0;
 
         loop {
-            let mut pos: usize = 0;
-            for i in count..body.len() - 1 {
-                if body[i] == CR && body[i + 1] == LF {
-                    pos = i;
-                    break;
+            let pos = match body[count..].windows(2).position(|w| w == [CR, LF])
+            {
+                Some(pos) => count + pos,
+                None => {
+                    return Err(DockerApiError::HTTPResponseParseError(
+                        "Chuncked response without length marker",
+                    ))
                 }
-            }
-            if pos == 0 {
-                return Err(DockerApiError::HTTPResponseParseError(
-                    "Chuncked response without length marker",
-                ));
-            }
+            };
 
             let size_s = match str::from_utf8(&body[count..pos]) {
                 Ok(s) => s,
@@ -140,26 +142,326 @@ impl Response {
                 }
             };
 
-            count = pos + 2;
-            let size: usize = match usize::from_str_radix(size_s, 16) {
-                Ok(s) => s,
-                Err(_) => {
+            let size = match parse_chunk_size(size_s) {
+                Some(s) => s,
+                None => {
                     return Err(DockerApiError::HTTPResponseParseError(
                         "Invalid chunks",
                     ))
                 }
             };
 
-            if size == 0 && count + 2 == body.len() {
+            // The last chunk, anything after it is the optional trailer.
+            if size == 0 {
                 return Ok(buf);
             }
 
-            buf.extend_from_slice(&body[pos + 2..pos + 2 + size]);
-            count = count + size + 2;
+            count = pos + 2;
+            if count + size > body.len() {
+                return Err(DockerApiError::HTTPResponseParseError(
+                    "Truncated chunk in response",
+                ));
+            }
+
+            buf.extend_from_slice(&body[count..count + size]);
+            count += size;
+            if count + 2 > body.len() {
+                return Err(DockerApiError::HTTPResponseParseError(
+                    "Truncated chunk in response",
+                ));
+            }
+            // A size not matching the data is caught here.
+            if body[count..count + 2] != [CR, LF] {
+                return Err(DockerApiError::HTTPResponseParseError(
+                    "Chunk data not followed by CRLF",
+                ));
+            }
+            count += 2;
+        }
+    }
+}
+
+/// Parses the head of an HTTP response, the status line followed by the
+/// headers, and returns the status code along with the headers.
+pub fn parse_http_head(
+    head: &str,
+) -> Result<(usize, HashMap<String, String>), &'static str> {
+    let mut lines = head.lines();
+
+    let status_line = lines.next().unwrap_or("");
+    if !status_line.starts_with("HTTP/") {
+        return Err("Not a valid HTTP response");
+    }
+
+    let status_code: usize = match status_line.split_whitespace().nth(1) {
+        Some(code) => match code.parse() {
+            Ok(code) => code,
+            Err(_) => return Err("Error while parsing HTTP status code"),
+        },
+        None => return Err("Error while parsing HTTP status code"),
+    };
+
+    let mut headers: HashMap<String, String> = HashMap::new();
+    for line in lines {
+        if line.is_empty() {
+            break;
+        }
+
+        let mut item = line.splitn(2, ':');
+        let name = item.next().unwrap_or("").trim();
+        let value = match item.next() {
+            Some(value) => value.trim(),
+            None => return Err("Error while parsing HTTP header"),
+        };
+
+        headers.insert(name.to_owned(), value.to_owned());
+    }
+
+    Ok((status_code, headers))
+}
+
+/// Reads a single HTTP/1.1 response from the reader and returns its raw
+/// bytes, head and body included, ready for `Response::parse_http_response`.
+/// Along with it, returns whether the connection can be reused for another
+/// request.
+///
+/// The end of the response body is found from, in order:
+///
+/// * The status code and request method, responses to HEAD requests and
+///   204/304 responses never have a body.
+/// * Chunked transfer encoding, read up to the last chunk and its trailer.
+/// * The Content-Length header.
+/// * The connection being closed by the daemon.
+///
+/// Informational(1xx) responses preceding the final response are skipped.
+pub fn read_http_response<R: BufRead>(
+    reader: &mut R,
+    head_request: bool,
+) -> io::Result<(Vec<u8>, bool)> {
+    loop {
+        let mut raw_resp: Vec<u8> = Vec::new();
+        let (status_code, headers) = read_http_head(reader, &mut raw_resp)?;
+
+        if status_code / 100 == 1 && status_code != 101 {
+            continue;
+        }
+
+        let keep_alive = is_keep_alive(&raw_resp, status_code, &headers);
+        let framing = body_framing(status_code, &headers, head_request)?;
+        // A body ending with the connection means it can't be reused.
+        let until_close = matches!(framing, BodyFraming::UntilClose);
+        read_http_body(reader, framing, &mut raw_resp)?;
+
+        return Ok((raw_resp, keep_alive && !until_close));
+    }
+}
+
+/// Reads the body of a response, framed as provided, into `raw_resp`.
+pub(crate) fn read_http_body<R: BufRead>(
+    reader: &mut R,
+    framing: BodyFraming,
+    raw_resp: &mut Vec<u8>,
+) -> io::Result<()> {
+    match framing {
+        BodyFraming::Empty => {}
+        BodyFraming::Chunked => read_chunked_body(reader, raw_resp)?,
+        BodyFraming::Length(len) => {
+            let read = reader.take(len).read_to_end(raw_resp)?;
+            if (read as u64) < len {
+                return Err(unexpected_eof(
+                    "Connection closed before the end of the response body",
+                ));
+            }
+        }
+        BodyFraming::UntilClose => {
+            reader.read_to_end(raw_resp)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// How the end of a response body is found.
+pub(crate) enum BodyFraming {
+    Empty,
+    Chunked,
+    Length(u64),
+    UntilClose,
+}
+
+/// Finds how the body of a response is framed, from its status code, its
+/// headers and the request method.
+pub(crate) fn body_framing(
+    status_code: usize,
+    headers: &HashMap<String, String>,
+    head_request: bool,
+) -> io::Result<BodyFraming> {
+    if head_request
+        || status_code / 100 == 1
+        || status_code == 204
+        || status_code == 304
+    {
+        return Ok(BodyFraming::Empty);
+    }
+
+    if is_chunked(headers) {
+        return Ok(BodyFraming::Chunked);
+    }
+
+    match find_header(headers, "Content-Length") {
+        Some(len) => match len.parse() {
+            Ok(len) => Ok(BodyFraming::Length(len)),
+            Err(_) => Err(invalid_data("Invalid Content-Length header")),
+        },
+        None => Ok(BodyFraming::UntilClose),
+    }
+}
+
+/// Whether the connection can be reused once the response has been read,
+/// provided its body isn't delimited by the connection being closed.
+pub(crate) fn is_keep_alive(
+    raw_resp: &[u8],
+    status_code: usize,
+    headers: &HashMap<String, String>,
+) -> bool {
+    status_code != 101
+        && raw_resp.starts_with(b"HTTP/1.1")
+        && !find_header(headers, "Connection")
+            .map(|conn| conn.eq_ignore_ascii_case("close"))
+            .unwrap_or(false)
+}
+
+/// Reads the status line and headers of a response into `raw_resp`, and
+/// returns the parsed status code and headers.
+pub(crate) fn read_http_head<R: BufRead>(
+    reader: &mut R,
+    raw_resp: &mut Vec<u8>,
+) -> io::Result<(usize, HashMap<String, String>)> {
+    let start = raw_resp.len();
+    loop {
+        let line_start = raw_resp.len();
+        if reader.read_until(LF, raw_resp)? == 0 {
+            return Err(unexpected_eof(
+                "Connection closed before the end of the response head",
+            ));
+        }
+
+        if is_empty_line(&raw_resp[line_start..]) {
+            break;
+        }
+    }
+
+    parse_raw_http_head(&raw_resp[start..])
+}
+
+/// Parses the raw bytes of a response head read off the connection.
+pub(crate) fn parse_raw_http_head(
+    head: &[u8],
+) -> io::Result<(usize, HashMap<String, String>)> {
+    let head = match str::from_utf8(head) {
+        Ok(head) => head,
+        Err(_) => return Err(invalid_data("Error while parsing HTTP header")),
+    };
+
+    parse_http_head(head).map_err(invalid_data)
+}
+
+/// Whether the line ends the response head or the trailer.
+pub(crate) fn is_empty_line(line: &[u8]) -> bool {
+    line == b"\r\n" || line == b"\n"
+}
+
+/// Reads a chunked body, chunk framing included, into `raw_resp`.
+fn read_chunked_body<R: BufRead>(
+    reader: &mut R,
+    raw_resp: &mut Vec<u8>,
+) -> io::Result<()> {
+    loop {
+        let line_start = raw_resp.len();
+        if reader.read_until(LF, raw_resp)? == 0 {
+            return Err(unexpected_eof(
+                "Connection closed before the last chunk",
+            ));
+        }
+
+        let size = parse_chunk_size_line(&raw_resp[line_start..])?;
+        if size == 0 {
+            // Skip over the trailer up to the final empty line.
+            loop {
+                let line_start = raw_resp.len();
+                if reader.read_until(LF, raw_resp)? == 0
+                    || is_empty_line(&raw_resp[line_start..])
+                {
+                    return Ok(());
+                }
+            }
+        }
+
+        // Chunk data followed by its CRLF.
+        let len = size as u64 + 2;
+        if (reader.take(len).read_to_end(raw_resp)? as u64) < len {
+            return Err(unexpected_eof(
+                "Connection closed in the middle of a chunk",
+            ));
         }
+        check_chunk_end(&raw_resp[raw_resp.len() - 2..])?;
     }
 }
 
+/// Checks the two bytes read after the data of a chunk are its CRLF, they
+/// aren't when the size of the chunk doesn't match its data.
+pub(crate) fn check_chunk_end(end: &[u8]) -> io::Result<()> {
+    if end != [CR, LF] {
+        return Err(invalid_data("Chunk data not followed by CRLF"));
+    }
+
+    Ok(())
+}
+
+/// Parses the raw chunk size line read off the connection.
+pub(crate) fn parse_chunk_size_line(line: &[u8]) -> io::Result<usize> {
+    let size = match str::from_utf8(line) {
+        Ok(line) => parse_chunk_size(line.trim_end()),
+        Err(_) => None,
+    };
+
+    match size {
+        Some(size) => Ok(size),
+        None => Err(invalid_data("Invalid chunks")),
+    }
+}
+
+/// Parses the size of a chunk, ignoring any chunk extensions.
+fn parse_chunk_size(line: &str) -> Option<usize> {
+    let size = line.split(';').next().unwrap_or("").trim();
+    usize::from_str_radix(size, 16).ok()
+}
+
+fn is_chunked(headers: &HashMap<String, String>) -> bool {
+    match find_header(headers, "Transfer-Encoding") {
+        Some(enc) => enc.to_ascii_lowercase().contains("chunked"),
+        None => false,
+    }
+}
+
+pub(crate) fn find_header<'a>(
+    headers: &'a HashMap<String, String>,
+    name: &str,
+) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|&(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
+
+pub(crate) fn unexpected_eof(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::UnexpectedEof, msg.to_string())
+}
+
 /// This function validates a given unix domain socket address, it can be either
 /// of an absolute socket path or unix domain socket address.
 ///
@@ -187,7 +489,131 @@ pub fn validate_unix_socket_address(address: &str) -> Option<Vec<&str>> {
         return None;
     }
 
-    return Some(addr_comp);
+    Some(addr_comp)
+}
+
+/// This function validates a given TCP socket address for the docker daemon.
+///
+/// * tcp://127.0.0.1:2375
+/// * tcp://docker-host:2375
+///
+/// The address must contain both a host and a valid port. It returns a Vector
+/// of slices containing the protocol("tcp") and the `host:port` part of the
+/// address wrapped in option.
+pub fn validate_tcp_socket_address(address: &str) -> Option<Vec<&str>> {
+    let socket_protocol = "tcp";
+
+    let addr_comp: Vec<&str> = address.split("://").collect();
+    if addr_comp.len() != 2 || addr_comp[0] != socket_protocol {
+        return None;
+    }
+
+    // Ignore any trailing slash, `tcp://host:2375/` is a valid address.
+    let host_port = addr_comp[1].trim_end_matches('/');
+    let (host, port) = match host_port.rfind(':') {
+        Some(pos) => (&host_port[..pos], &host_port[pos + 1..]),
+        None => return None,
+    };
+
+    if host.is_empty() || port.parse::<u16>().is_err() {
+        return None;
+    }
+
+    Some(vec![socket_protocol, host_port])
+}
+
+/// This function validates a given SSH address for the docker daemon.
+///
+/// * ssh://docker-host
+/// * ssh://user@docker-host:2222
+///
+/// It returns the user, host and port of the address, where the user and
+/// port are optional, wrapped in option.
+pub fn validate_ssh_address(
+    address: &str,
+) -> Option<(Option<&str>, &str, Option<u16>)> {
+    let socket_protocol = "ssh";
+
+    let addr_comp: Vec<&str> = address.split("://").collect();
+    if addr_comp.len() != 2 || addr_comp[0] != socket_protocol {
+        return None;
+    }
+
+    let user_host = addr_comp[1].trim_end_matches('/');
+    let (user, host_port) = match user_host.rfind('@') {
+        Some(pos) => (Some(&user_host[..pos]), &user_host[pos + 1..]),
+        None => (None, user_host),
+    };
+
+    // IPv6 addresses are enclosed in brackets, so only look for a port
+    // after the closing bracket.
+    let port_start = host_port.rfind(']').unwrap_or(0);
+    let (host, port) = match host_port[port_start..].rfind(':') {
+        Some(pos) => {
+            let pos = port_start + pos;
+            match host_port[pos + 1..].parse::<u16>() {
+                Ok(port) => (&host_port[..pos], Some(port)),
+                Err(_) => return None,
+            }
+        }
+        None => (host_port, None),
+    };
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+
+    if host.is_empty() || user == Some("") {
+        return None;
+    }
+
+    Some((user, host, port))
+}
+
+/// Validates an API version like `1.37` or `v1.37`, and returns it without
+/// the `v` prefix.
+pub fn validate_api_version(version: &str) -> Option<&str> {
+    let version = version.trim_start_matches('v');
+
+    let parts: Vec<&str> = version.split('.').collect();
+    if parts.len() != 2 {
+        return None;
+    }
+
+    for part in parts {
+        if part.is_empty() || !part.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+    }
+
+    Some(version)
+}
+
+/// Parses an API version like `1.37` or `v1.37` into its major and minor
+/// numbers, so that versions can be compared.
+pub fn parse_api_version(version: &str) -> Option<(u32, u32)> {
+    let version = validate_api_version(version)?;
+    let mut parts = version.split('.');
+    match (parts.next()?.parse(), parts.next()?.parse()) {
+        (Ok(major), Ok(minor)) => Some((major, minor)),
+        _ => None,
+    }
+}
+
+/// Picks the API version to use with a daemon supporting versions up to
+/// `daemon_version`, the highest version supported by both the daemon and
+/// the client.
+///
+/// Returns None if the daemon only supports versions older than
+/// `api_utils::MIN_API_VERSION`, or if `daemon_version` is not valid.
+pub fn negotiate_api_version(daemon_version: &str) -> Option<String> {
+    let daemon = parse_api_version(daemon_version)?;
+    let max = parse_api_version(api_utils::MAX_API_VERSION)?;
+    let min = parse_api_version(api_utils::MIN_API_VERSION)?;
+
+    if daemon < min {
+        return None;
+    }
+
+    let (major, minor) = daemon.min(max);
+    Some(format!("{}.{}", major, minor))
 }
 
 /// Checks if the JSON string provided is valid or not and returns
@@ -200,3 +626,13 @@ pub fn validate_json_str(json_str: &str) -> bool {
 
     true
 }
+
+/// Deserializes `null` as the default value, for the lists the daemon sends
+/// as `null` when they are empty. To be used along with `#[serde(default)]`.
+pub(crate) fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::deserialize(deserializer)?.unwrap_or_default())
+}
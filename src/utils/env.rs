@@ -0,0 +1,56 @@
+//! Helpers to read the docker configuration from the environment, following
+//! the conventions of the docker CLI.
+use std::env;
+use std::path::PathBuf;
+
+/// Returns the value of the environment variable `key`, treating unset and
+/// empty variables the same way as the docker CLI does.
+pub fn get_non_empty(key: &str) -> Option<String> {
+    match env::var(key) {
+        Ok(ref val) if val.is_empty() => None,
+        Ok(val) => Some(val),
+        Err(_) => None,
+    }
+}
+
+/// Directory holding the docker CLI configuration, `DOCKER_CONFIG` if set
+/// and `~/.docker` otherwise.
+pub fn docker_config_dir() -> PathBuf {
+    if let Some(dir) = get_non_empty("DOCKER_CONFIG") {
+        return PathBuf::from(dir);
+    }
+
+    match get_non_empty("HOME") {
+        Some(home) => PathBuf::from(home).join(".docker"),
+        None => PathBuf::from(".docker"),
+    }
+}
+
+/// Directory holding the TLS material, `DOCKER_CERT_PATH` if set and the
+/// docker configuration directory otherwise.
+pub fn docker_cert_path() -> PathBuf {
+    match get_non_empty("DOCKER_CERT_PATH") {
+        Some(dir) => PathBuf::from(dir),
+        None => docker_config_dir(),
+    }
+}
+
+/// The docker CLI accepts `tcp://host` without a port and uses the default
+/// daemon port, 2376 with TLS and 2375 without. Any other address is
+/// returned unchanged.
+pub fn with_default_tcp_port(host: &str, tls: bool) -> String {
+    if !host.starts_with("tcp://") {
+        return host.to_string();
+    }
+
+    let host_port = host["tcp://".len()..].trim_end_matches('/');
+    // IPv6 addresses are enclosed in brackets, so only look for a port
+    // after the closing bracket.
+    let port_start = host_port.rfind(']').unwrap_or(0);
+    if host_port[port_start..].contains(':') {
+        return host.to_string();
+    }
+
+    let port = if tls { 2376 } else { 2375 };
+    format!("tcp://{}:{}", host_port, port)
+}
@@ -1,9 +1,10 @@
 use std::env;
-use std::path::Path;
+use std::path::PathBuf;
 
 use flate2::write::GzEncoder;
 use flate2::Compression;
 use std::fs::File;
+use tar;
 use uuid::Uuid;
 
 /// This function validates the provided directory path as a string and return the
@@ -15,18 +16,18 @@ use uuid::Uuid;
 /// ```rust
 /// use rust_docker::file::get_validated_dir_path;
 /// match get_validated_dir_path("/tmp") {
-///     Ok(dir_path) => dir_path,
+///     Ok(dir_path) => println!("{:?}", dir_path),
 ///     Err(e) => println!("Error occured {}", e)
 /// }
 /// ```
-pub fn get_validated_dir_path(dir: &str) -> Result<Path, String> {
-    let mut dir_path = Path::new(dir);
+pub fn get_validated_dir_path(dir: &str) -> Result<PathBuf, String> {
+    let mut dir_path = PathBuf::from(dir);
 
     if !dir_path.is_absolute() {
         match env::current_dir() {
-            Ok(cur_dir) => {
+            Ok(mut cur_dir) => {
                 cur_dir.push(dir);
-                dir_path = cur_dir.as_path();
+                dir_path = cur_dir;
             }
             Err(e) => return Err(format!("Current directory not valid {}", e)),
         };
@@ -48,34 +49,35 @@ pub fn get_validated_dir_path(dir: &str) -> Result<Path, String> {
 ///
 /// It appends all the files inside the directory recursively to the tar file
 /// and write those files to it.
-pub fn create_gzipped_tarball(dir: &str) -> Result<Path, String> {
+pub fn create_gzipped_tarball(dir: &str) -> Result<PathBuf, String> {
     // This assumes that the base_dir_path is a valid path
     // which is not none when converted to string.
     let base_dir_path = get_validated_dir_path(dir)?;
 
-    let tar_name = format!("{}.tar.gz", Uuid::new_v4().to_simple());
+    let tar_path =
+        env::temp_dir().join(format!("{}.tar.gz", Uuid::new_v4().to_simple()));
 
-    let tar_file = match File::create(&tar_name) {
+    let tar_file = match File::create(&tar_path) {
         Ok(tar_file) => tar_file,
         Err(err) => {
-            return Err(format!(
-                "Error while creating tar file : {}",
-                err.cause()
-            ))
+            return Err(format!("Error while creating tar file : {}", err))
         }
     };
 
     // Create a new gzip enocder
-    let encoder = GzEncoder::new(tar_gz, Compression::default());
+    let encoder = GzEncoder::new(tar_file, Compression::default());
     // Create a new tar Builder.
     let mut tar = tar::Builder::new(encoder);
 
     // Append all files in the directory we want to Tar.
-    if let Err(err) = tar.append_dir_all(base_dir_path.to_str().unwrap(), ".") {
-        return Err(format!("Error while writing to tar : {}", err.to_string()));
+    if let Err(err) = tar.append_dir_all(".", &base_dir_path) {
+        return Err(format!("Error while writing to tar : {}", err));
     }
 
     // Finish building the Tar file
-    tar.finish();
-    Ok(base_dir_path)
+    if let Err(err) = tar.finish() {
+        return Err(format!("Error while writing to tar : {}", err));
+    }
+
+    Ok(tar_path)
 }
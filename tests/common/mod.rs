@@ -0,0 +1,85 @@
+//! Loopback stand-ins for the docker daemon shared by the integration tests.
+#![allow(dead_code)]
+
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+
+/// Reads a single HTTP request head from the stream. The head is read byte
+/// by byte so that none of the body following it is consumed.
+pub fn read_request<S: Read>(stream: &mut S) -> String {
+    let mut request = Vec::new();
+    let mut buf = [0; 1];
+    while !request.ends_with(b"\r\n\r\n") {
+        let len = stream.read(&mut buf).unwrap();
+        if len == 0 {
+            break;
+        }
+        request.push(buf[0]);
+    }
+
+    String::from_utf8(request).unwrap()
+}
+
+/// Formats a `200 OK` JSON response with the provided body.
+pub fn json_response(body: &str) -> String {
+    format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+        body.len(),
+        body
+    )
+}
+
+/// Starts a stand-in for the docker daemon on a loopback TCP port which
+/// answers a single request with the provided body. Returns the `tcp://`
+/// address for it along with a handle yielding the raw request it received.
+pub fn serve_once(body: &'static str) -> (String, thread::JoinHandle<String>) {
+    serve_with(move |mut stream: TcpStream| {
+        let request = read_request(&mut stream);
+        stream.write_all(json_response(body).as_bytes()).unwrap();
+        request
+    })
+}
+
+/// Starts a stand-in for the docker daemon on a loopback TCP port which
+/// hands the first accepted connection to `handler`.
+pub fn serve_with<F, T>(handler: F) -> (String, thread::JoinHandle<T>)
+where
+    F: FnOnce(TcpStream) -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = format!("tcp://{}", listener.local_addr().unwrap());
+
+    let handle = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        handler(stream)
+    });
+
+    (addr, handle)
+}
+
+/// Starts a stand-in for the docker daemon on a loopback TCP port which
+/// accepts any number of connections, each handled on its own thread by
+/// `handler`. Returns the `tcp://` address for it.
+pub fn serve_many<F>(handler: F) -> String
+where
+    F: Fn(TcpStream) + Send + Sync + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = format!("tcp://{}", listener.local_addr().unwrap());
+
+    let handler = ::std::sync::Arc::new(handler);
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(_) => return,
+            };
+            let handler = handler.clone();
+            thread::spawn(move || handler(stream));
+        }
+    });
+
+    addr
+}
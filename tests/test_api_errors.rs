@@ -0,0 +1,250 @@
+extern crate rust_docker;
+
+mod common;
+
+use rust_docker::api::containers::{Containers, RemoveContainerOptions};
+use rust_docker::api::request::Method;
+use rust_docker::api::version::Version;
+use rust_docker::client::DockerClient;
+use rust_docker::errors::DockerApiError;
+
+use std::error::Error;
+use std::io::{self, Write};
+use std::net::TcpStream;
+
+/// Stand-in answering a single request with the provided status line and
+/// body.
+fn daemon_answering(
+    status: &'static str,
+    body: &'static str,
+) -> (String, std::thread::JoinHandle<String>) {
+    common::serve_with(move |mut stream: TcpStream| {
+        let request = common::read_request(&mut stream);
+        let response = format!(
+            "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        );
+        stream.write_all(response.as_bytes()).unwrap();
+        request
+    })
+}
+
+#[test]
+fn test_not_found() {
+    let (addr, _server) = daemon_answering(
+        "404 Not Found",
+        "{\"message\":\"No such container: f808ca\"}",
+    );
+
+    let client = DockerClient::new(addr).unwrap();
+    match client.inspect_container("f808ca") {
+        Err(DockerApiError::NotFound(method, endpoint, message)) => {
+            assert_eq!(method, Method::GET);
+            assert_eq!(endpoint, "/containers/f808ca/json");
+            assert_eq!(message, "No such container: f808ca");
+        }
+        other => panic!("Unexpected result : {:?}", other),
+    }
+}
+
+#[test]
+fn test_conflict() {
+    let (addr, _server) = daemon_answering(
+        "409 Conflict",
+        "{\"message\":\"Conflict. The container name \\\"/web\\\" is already in use\"}",
+    );
+
+    let client = DockerClient::new(addr).unwrap();
+    let err = client
+        .create_container_minimal("web", "debian:jessie", Vec::new())
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Conflict (POST /containers/create) : Conflict. The container name \"/web\" is already in use"
+    );
+}
+
+#[test]
+fn test_not_modified_without_body() {
+    let (addr, _server) = daemon_answering("304 Not Modified", "");
+
+    let client = DockerClient::new(addr).unwrap();
+    match client.start_container("f808ca") {
+        Err(DockerApiError::NotModified(method, endpoint, message)) => {
+            assert_eq!(method, Method::POST);
+            assert_eq!(endpoint, "/containers/f808ca/start");
+            assert_eq!(message, "status code 304");
+        }
+        other => panic!("Unexpected result : {:?}", other),
+    }
+}
+
+#[test]
+fn test_bad_parameter_and_server_error() {
+    let (addr, _server) = daemon_answering(
+        "400 Bad Request",
+        "{\"message\":\"invalid filter 'foo'\"}",
+    );
+    let client = DockerClient::new(addr).unwrap();
+    match client.get_container_details_with_filter("{\"foo\":[\"1\"]}", None) {
+        Err(DockerApiError::BadParameter(_, _, message)) => {
+            assert_eq!(message, "invalid filter 'foo'")
+        }
+        other => panic!("Unexpected result : {:?}", other),
+    }
+
+    // Messages which aren't JSON are kept as they are.
+    let (addr, _server) =
+        daemon_answering("502 Bad Gateway", "upstream unavailable\n");
+    let client = DockerClient::new(addr).unwrap();
+    match client.list_all_containers(None) {
+        Err(DockerApiError::ServerError(method, endpoint, message)) => {
+            assert_eq!(method, Method::GET);
+            assert_eq!(endpoint, "/containers/json");
+            assert_eq!(message, "upstream unavailable");
+        }
+        other => panic!("Unexpected result : {:?}", other),
+    }
+}
+
+#[test]
+fn test_unmapped_status() {
+    let (addr, _server) =
+        daemon_answering("401 Unauthorized", "{\"message\":\"denied\"}");
+
+    let client = DockerClient::new(addr).unwrap();
+    match client.inspect_container("f808ca") {
+        Err(DockerApiError::InvalidApiResponseError(status, body)) => {
+            assert_eq!(status, 401);
+            assert_eq!(body, "{\"message\":\"denied\"}");
+        }
+        other => panic!("Unexpected result : {:?}", other),
+    }
+}
+
+#[test]
+fn test_error_sources() {
+    let (addr, _server) = daemon_answering("200 OK", "{\"Id\":");
+
+    let client = DockerClient::new(addr).unwrap();
+    let err = client.inspect_container("f808ca").unwrap_err();
+    match err {
+        DockerApiError::JsonDeserializationError(_) => {}
+        ref other => panic!("Unexpected error : {:?}", other),
+    }
+    assert!(err
+        .source()
+        .unwrap()
+        .downcast_ref::<serde_json::Error>()
+        .is_some());
+
+    // The stand-in is gone, the IO error is chained as the source.
+    let err = client.get_api_version().unwrap_err();
+    match err {
+        DockerApiError::ConnectionError(ref endpoint, _) => {
+            assert_eq!(endpoint, "/_ping")
+        }
+        ref other => panic!("Unexpected error : {:?}", other),
+    }
+    assert!(err.source().unwrap().downcast_ref::<io::Error>().is_some());
+}
+
+#[test]
+fn test_remove_running_container() {
+    let (addr, server) = daemon_answering(
+        "409 Conflict",
+        "{\"message\":\"cannot remove container \\\"/web\\\": container is running: \
+         stop the container before removing or force remove\"}",
+    );
+
+    let client = DockerClient::new(addr).unwrap();
+    let options = RemoveContainerOptions::default().volumes(true);
+    match client.remove_container("web", options) {
+        Err(DockerApiError::ContainerRunning(id, message)) => {
+            assert_eq!(id, "web");
+            assert!(message.ends_with("or force remove"));
+        }
+        other => panic!("Unexpected result : {:?}", other),
+    }
+
+    let request = server.join().unwrap();
+    assert!(request.starts_with(
+        "DELETE /containers/web?force=false&v=true&link=false HTTP/1.1\r\n"
+    ));
+}
+
+#[test]
+fn test_remove_missing_container() {
+    let (addr, _server) = daemon_answering(
+        "404 Not Found",
+        "{\"message\":\"No such container: web\"}",
+    );
+
+    let client = DockerClient::new(addr).unwrap();
+    match client.remove_container("web", RemoveContainerOptions::default()) {
+        Err(DockerApiError::NoSuchContainer(id, message)) => {
+            assert_eq!(id, "web");
+            assert_eq!(message, "No such container: web");
+        }
+        other => panic!("Unexpected result : {:?}", other),
+    }
+}
+
+#[test]
+fn test_remove_running_container_other_wording() {
+    // Daemons before 20.10 word it differently.
+    let (addr, _server) = daemon_answering(
+        "409 Conflict",
+        "{\"message\":\"You cannot remove a running container f808ca. Stop \
+         the container before attempting removal or force remove\"}",
+    );
+
+    let client = DockerClient::new(addr).unwrap();
+    match client.remove_container("web", RemoveContainerOptions::default()) {
+        Err(DockerApiError::ContainerRunning(id, message)) => {
+            assert_eq!(id, "web");
+            assert!(message.starts_with("You cannot remove a running"));
+        }
+        other => panic!("Unexpected result : {:?}", other),
+    }
+}
+
+#[test]
+fn test_remove_conflicts_of_unforced_removal() {
+    // Only conflicts saying the container is running are told apart.
+    let (addr, _server) = daemon_answering(
+        "409 Conflict",
+        "{\"message\":\"removal of container web is already in progress\"}",
+    );
+
+    let client = DockerClient::new(addr).unwrap();
+    match client.remove_container("web", RemoveContainerOptions::default()) {
+        Err(DockerApiError::Conflict(method, _, message)) => {
+            assert_eq!(method, Method::DELETE);
+            assert_eq!(
+                message,
+                "removal of container web is already in progress"
+            );
+        }
+        other => panic!("Unexpected result : {:?}", other),
+    }
+}
+
+#[test]
+fn test_remove_other_conflicts() {
+    let (addr, _server) = daemon_answering(
+        "409 Conflict",
+        "{\"message\":\"removal of container web is already in progress\"}",
+    );
+
+    let client = DockerClient::new(addr).unwrap();
+    let options = RemoveContainerOptions::default().force(true);
+    match client.remove_container("web", options) {
+        Err(DockerApiError::Conflict(method, _, _)) => {
+            assert_eq!(method, Method::DELETE)
+        }
+        other => panic!("Unexpected result : {:?}", other),
+    }
+}
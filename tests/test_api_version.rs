@@ -0,0 +1,221 @@
+extern crate rust_docker;
+
+mod common;
+
+use rust_docker::api::api_utils::{MAX_API_VERSION, MIN_API_VERSION};
+use rust_docker::api::version::Version;
+use rust_docker::api::DockerApiClient;
+use rust_docker::client::DockerClient;
+use rust_docker::errors::DockerClientError;
+use rust_docker::utils::api::{negotiate_api_version, parse_api_version};
+
+use std::io::Write;
+use std::net::TcpStream;
+
+/// Stand-in answering `/_ping` with the provided `API-Version` header, and
+/// then the request made with the negotiated version.
+fn daemon_with_version(
+    version: &'static str,
+) -> (String, std::thread::JoinHandle<Vec<String>>) {
+    common::serve_with(move |mut stream: TcpStream| {
+        let ping = common::read_request(&mut stream);
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nApi-Version: {}\r\nContent-Length: 2\r\n\r\nOK",
+            version
+        );
+        stream.write_all(response.as_bytes()).unwrap();
+
+        let request = common::read_request(&mut stream);
+        if !request.is_empty() {
+            let response = common::json_response("{\"ID\":\"stand-in\"}");
+            stream.write_all(response.as_bytes()).unwrap();
+        }
+        vec![ping, request]
+    })
+}
+
+#[test]
+fn test_negotiate_api_version() {
+    assert_eq!(parse_api_version("v1.37"), Some((1, 37)));
+    assert_eq!(parse_api_version("1.x"), None);
+
+    // Older daemons are spoken to with their own version.
+    assert_eq!(negotiate_api_version("1.26"), Some("1.26".to_string()));
+    assert_eq!(negotiate_api_version("1.9"), None);
+    assert_eq!(
+        negotiate_api_version(MIN_API_VERSION),
+        Some(MIN_API_VERSION.to_string())
+    );
+
+    // Newer daemons still support the versions the client knows about.
+    assert_eq!(
+        negotiate_api_version("1.99"),
+        Some(MAX_API_VERSION.to_string())
+    );
+    assert_eq!(
+        negotiate_api_version("2.0"),
+        Some(MAX_API_VERSION.to_string())
+    );
+}
+
+#[test]
+fn test_requests_use_negotiated_version() {
+    let (addr, server) = daemon_with_version("1.26");
+
+    let client = DockerClient::new(addr)
+        .unwrap()
+        .negotiate_api_version()
+        .unwrap();
+    assert_eq!(client.api_version(), Some("1.26".to_string()));
+    assert!(client.get_version_info().is_ok());
+
+    let requests = server.join().unwrap();
+    assert!(requests[0].starts_with("GET /_ping HTTP/1.1\r\n"));
+    assert!(requests[1].starts_with("GET /v1.26/info HTTP/1.1\r\n"));
+}
+
+#[test]
+fn test_negotiation_ignores_pinned_version() {
+    let (addr, server) = daemon_with_version("1.43");
+
+    let client = DockerClient::new(addr)
+        .unwrap()
+        .with_api_version("1.30")
+        .unwrap()
+        .negotiate_api_version()
+        .unwrap();
+    assert_eq!(client.api_version(), Some("1.43".to_string()));
+    drop(client);
+
+    let requests = server.join().unwrap();
+    assert!(requests[0].starts_with("GET /_ping HTTP/1.1\r\n"));
+}
+
+#[test]
+fn test_version_endpoint_fallback() {
+    // Daemons older than 1.25 don't send the API-Version header.
+    let (addr, server) = common::serve_with(|mut stream: TcpStream| {
+        let ping = common::read_request(&mut stream);
+        let response = "HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nOK";
+        stream.write_all(response.as_bytes()).unwrap();
+
+        let version = common::read_request(&mut stream);
+        let response = common::json_response(
+            "{\"Version\":\"1.13.1\",\"ApiVersion\":\"1.26\",\"MinAPIVersion\":\"1.12\"}",
+        );
+        stream.write_all(response.as_bytes()).unwrap();
+        vec![ping, version]
+    });
+
+    let client = DockerClient::new(addr)
+        .unwrap()
+        .negotiate_api_version()
+        .unwrap();
+    assert_eq!(client.api_version(), Some("1.26".to_string()));
+
+    let requests = server.join().unwrap();
+    assert!(requests[1].starts_with("GET /version HTTP/1.1\r\n"));
+}
+
+#[test]
+fn test_unsupported_daemon_version() {
+    let (addr, _server) = daemon_with_version("1.12");
+
+    match DockerClient::new(addr).unwrap().negotiate_api_version() {
+        Err(DockerClientError::ApiVersionNegotiationError(_)) => {}
+        Err(err) => panic!("Unexpected error : {}", err),
+        Ok(_) => panic!("Negotiated with a daemon which is too old"),
+    }
+}
+
+#[test]
+fn test_ping_version_and_info() {
+    let addr = common::serve_many(|mut stream: TcpStream| loop {
+        let request = common::read_request(&mut stream);
+        let response = if request.starts_with("GET /_ping ") {
+            "HTTP/1.1 200 OK\r\nApi-Version: 1.41\r\nOSType: linux\r\n\
+             Docker-Experimental: true\r\nBuilder-Version: 2\r\n\
+             Content-Length: 2\r\n\r\nOK"
+                .to_string()
+        } else if request.starts_with("GET /version ") {
+            common::json_response(
+                "{\"Platform\":{\"Name\":\"Docker Engine - Community\"},\
+                 \"Components\":[{\"Name\":\"Engine\",\"Version\":\"20.10.7\",\
+                 \"Details\":{\"ApiVersion\":\"1.41\",\"MinAPIVersion\":\"1.12\"}},\
+                 {\"Name\":\"containerd\",\"Version\":\"1.4.6\",\
+                 \"Details\":{\"GitCommit\":\"d71fcd7d8303\"}}],\
+                 \"Version\":\"20.10.7\",\"ApiVersion\":\"1.41\",\
+                 \"MinAPIVersion\":\"1.12\",\"GitCommit\":\"b0f5bc3\",\
+                 \"GoVersion\":\"go1.13.15\",\"Os\":\"linux\",\"Arch\":\"amd64\",\
+                 \"KernelVersion\":\"5.10.0\",\"BuildTime\":\"2021-06-02T11:54:50.000000000+00:00\"}",
+            )
+        } else if request.starts_with("GET /info ") {
+            common::json_response(
+                "{\"ID\":\"7TRN:IPZB\",\"Containers\":3,\"ContainersRunning\":1,\
+                 \"ContainersPaused\":0,\"ContainersStopped\":2,\"Images\":12,\
+                 \"Driver\":\"overlay2\",\"DriverStatus\":[[\"Backing Filesystem\",\"extfs\"]],\
+                 \"Plugins\":{\"Volume\":[\"local\"]},\"NCPU\":8,\
+                 \"MemTotal\":16651862016,\"Name\":\"builder\",\"Labels\":[],\
+                 \"ServerVersion\":\"20.10.7\",\"OSType\":\"linux\",\
+                 \"Warnings\":null}",
+            )
+        } else {
+            return;
+        };
+        stream.write_all(response.as_bytes()).unwrap();
+    });
+
+    let client = DockerClient::new(addr).unwrap();
+
+    let ping = client.ping().unwrap();
+    assert_eq!(ping.api_version.unwrap(), "1.41");
+    assert_eq!(ping.os_type.unwrap(), "linux");
+    assert!(ping.experimental);
+    assert_eq!(ping.builder_version.unwrap(), "2");
+
+    let version = client.version().unwrap();
+    assert_eq!(version.Version, "20.10.7");
+    assert_eq!(version.MinAPIVersion, "1.12");
+    assert_eq!(version.GoVersion, "go1.13.15");
+    assert_eq!(version.Platform.unwrap().Name, "Docker Engine - Community");
+    assert_eq!(version.Components.len(), 2);
+    assert_eq!(version.Components[1].Name, "containerd");
+    let details = version.Components[0].Details.as_ref().unwrap();
+    assert_eq!(details["MinAPIVersion"], "1.12");
+
+    // Fields this client doesn't know about are ignored.
+    let info = client.info().unwrap();
+    assert_eq!(info.ContainersStopped, 2);
+    assert_eq!(info.Images, 12);
+    assert_eq!(info.NCPU, 8);
+    assert_eq!(info.MemTotal, 16651862016);
+    assert_eq!(info.DriverStatus.unwrap()[0][1], "extfs");
+    assert_eq!(info.Labels, Some(Vec::new()));
+    assert_eq!(info.Warnings, None);
+}
+
+#[test]
+fn test_ping_older_daemons() {
+    let addr = common::serve_many(|mut stream: TcpStream| loop {
+        let request = common::read_request(&mut stream);
+        let response = if request.starts_with("GET /_ping ") {
+            "HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nOK".to_string()
+        } else if request.starts_with("GET /version ") {
+            common::json_response(
+                "{\"Version\":\"1.13.1\",\"ApiVersion\":\"1.26\",\
+                 \"GoVersion\":\"go1.7.5\",\"Os\":\"linux\",\"Arch\":\"amd64\"}",
+            )
+        } else {
+            return;
+        };
+        stream.write_all(response.as_bytes()).unwrap();
+    });
+
+    let client = DockerClient::new(addr).unwrap();
+    assert_eq!(client.ping().unwrap(), Default::default());
+
+    let version = client.version().unwrap();
+    assert_eq!(version.ApiVersion, "1.26");
+    assert_eq!(version.MinAPIVersion, "");
+    assert!(version.Components.is_empty());
+}
@@ -0,0 +1,241 @@
+#![cfg(feature = "tokio")]
+extern crate rust_docker;
+extern crate tokio;
+
+mod common;
+
+use rust_docker::api::request::Request;
+use rust_docker::asynchronous::containers::AsyncContainers;
+use rust_docker::asynchronous::images::AsyncImages;
+use rust_docker::asynchronous::version::AsyncVersion;
+use rust_docker::asynchronous::{AsyncDockerApiClient, AsyncDockerClient};
+use rust_docker::errors::DockerApiError;
+use rust_docker::transport::Timeouts;
+
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::io::Write;
+use std::net::TcpStream;
+use std::os::unix::net::UnixListener;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Answers every request on the connection with its own path as the body,
+/// until the client closes the connection.
+fn echo_path<S: std::io::Read + Write>(mut stream: S) {
+    loop {
+        let request = common::read_request(&mut stream);
+        if request.is_empty() {
+            return;
+        }
+
+        let path = request.split_whitespace().nth(1).unwrap().to_string();
+        let response = common::json_response(&path);
+        if stream.write_all(response.as_bytes()).is_err() {
+            return;
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_version_info_over_tcp() {
+    let (addr, server) = common::serve_once("{\"ID\":\"stand-in\"}");
+
+    let client = AsyncDockerClient::new(addr).await.unwrap();
+    let info = client.get_version_info().await.unwrap();
+    assert_eq!(info, "{\"ID\":\"stand-in\"}");
+
+    let request = server.join().unwrap();
+    assert!(request.starts_with("GET /info HTTP/1.1\r\n"));
+}
+
+#[tokio::test]
+async fn test_list_images_and_containers() {
+    let (addr, server) = common::serve_with(|mut stream: TcpStream| {
+        common::read_request(&mut stream);
+        let images = "[{\"Id\":\"sha256:e216a057b1cb\",\"ParentId\":\"\",\
+                      \"RepoTags\":[\"debian:jessie\"],\"RepoDigests\":null,\
+                      \"Created\":1527624862,\"Size\":127166010,\
+                      \"VirtualSize\":127166010,\"SharedSize\":-1,\
+                      \"Labels\":null,\"Containers\":-1}]";
+        stream
+            .write_all(common::json_response(images).as_bytes())
+            .unwrap();
+
+        let request = common::read_request(&mut stream);
+        let response = "HTTP/1.1 204 No Content\r\n\r\n";
+        stream.write_all(response.as_bytes()).unwrap();
+        request
+    });
+
+    let client = AsyncDockerClient::new(addr).await.unwrap();
+    let images = client.list_images(None).await.unwrap();
+    assert_eq!(images[0].RepoTags, vec!["debian:jessie".to_string()]);
+
+    let status = client.start_container("f808ca").await.unwrap();
+    assert_eq!(status, "Container start successful");
+
+    let request = server.join().unwrap();
+    assert!(request.starts_with("POST /containers/f808ca/start HTTP/1.1\r\n"));
+}
+
+#[tokio::test]
+async fn test_unix_socket_transport() {
+    let path = env::temp_dir()
+        .join(format!("rust-docker-async-{}.sock", std::process::id()));
+    let _ = fs::remove_file(&path);
+    let listener = UnixListener::bind(&path).unwrap();
+    thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        echo_path(stream);
+    });
+
+    let addr = format!("unix://{}", path.display());
+    let client = AsyncDockerClient::new(addr).await.unwrap();
+    for path in &["/_ping", "/info", "/version"] {
+        let resp = client.get_response_from_api(path, "GET", "").await.unwrap();
+        assert_eq!(resp.text().unwrap(), *path);
+    }
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_shared_client_across_tasks() {
+    let open = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+    let (open_count, peak_count) = (open.clone(), peak.clone());
+    let addr = common::serve_many(move |stream| {
+        let now = open_count.fetch_add(1, Ordering::SeqCst) + 1;
+        peak_count.fetch_max(now, Ordering::SeqCst);
+        stream.set_nodelay(true).unwrap();
+        thread::sleep(Duration::from_millis(5));
+        echo_path(stream);
+        open_count.fetch_sub(1, Ordering::SeqCst);
+    });
+
+    let client = AsyncDockerClient::new(addr)
+        .await
+        .unwrap()
+        .with_pool_size(3);
+    let tasks: Vec<_> = (0..16)
+        .map(|task| {
+            let client = client.clone();
+            tokio::spawn(async move {
+                for i in 0..20 {
+                    let path = format!("/containers/{}-{}/json", task, i);
+                    let resp = client
+                        .get_response_from_api(&path, "GET", "")
+                        .await
+                        .unwrap();
+                    assert_eq!(resp.text().unwrap(), path);
+                }
+            })
+        })
+        .collect();
+
+    for task in tasks {
+        task.await.unwrap();
+    }
+
+    assert!(peak.load(Ordering::SeqCst) <= 3);
+}
+
+#[tokio::test]
+async fn test_unreachable_daemon() {
+    assert!(AsyncDockerClient::new("tcp://127.0.0.1").await.is_err());
+    assert!(AsyncDockerClient::new("/does/not/exist.sock")
+        .await
+        .is_err());
+    assert!(AsyncDockerClient::new("ssh://docker-host").await.is_err());
+}
+
+#[tokio::test]
+async fn test_post_is_not_sent_again() {
+    let posts = Arc::new(AtomicUsize::new(0));
+    let counter = posts.clone();
+    let addr = common::serve_many(move |mut stream: TcpStream| loop {
+        let request = common::read_request(&mut stream);
+        if request.is_empty() {
+            return;
+        }
+        // The daemon acts on the request, then the connection is reset
+        // before it answers.
+        if request.starts_with("POST ") {
+            counter.fetch_add(1, Ordering::SeqCst);
+            return;
+        }
+        stream
+            .write_all(common::json_response("OK").as_bytes())
+            .unwrap();
+    });
+
+    let client = AsyncDockerClient::new(addr).await.unwrap();
+    assert_eq!(client.get_version_info().await.unwrap(), "OK");
+    assert!(client.start_container("web").await.is_err());
+
+    thread::sleep(Duration::from_millis(50));
+    assert_eq!(posts.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_connection_errors_keep_their_source() {
+    let addr = common::serve_many(|mut stream: TcpStream| {
+        // The connection is closed without any response.
+        common::read_request(&mut stream);
+    });
+
+    let client = AsyncDockerClient::new(addr).await.unwrap();
+    let err = client.start_container("web").await.unwrap_err();
+    match err {
+        DockerApiError::ConnectionError(ref endpoint, _) => {
+            assert_eq!(endpoint, "/containers/web/start")
+        }
+        ref other => panic!("Unexpected error : {:?}", other),
+    }
+    let source = err.source().unwrap().downcast_ref::<io::Error>().unwrap();
+    assert_eq!(source.kind(), io::ErrorKind::UnexpectedEof);
+}
+
+#[tokio::test]
+async fn test_timeouts() {
+    // The daemon reads the request but never answers it.
+    let addr = common::serve_many(|mut stream: TcpStream| {
+        common::read_request(&mut stream);
+        thread::sleep(Duration::from_secs(5));
+    });
+
+    let timeouts = Timeouts::default().read(Some(Duration::from_millis(100)));
+    let client = AsyncDockerClient::new(addr)
+        .await
+        .unwrap()
+        .with_timeouts(timeouts);
+    assert_eq!(client.timeouts(), timeouts);
+
+    let start = Instant::now();
+    match client.send_request(Request::get("/info")).await {
+        Err(DockerApiError::Timeout(endpoint)) => assert_eq!(endpoint, "/info"),
+        other => panic!("Unexpected result : {:?}", other.map(|_| ())),
+    }
+    assert!(start.elapsed() < Duration::from_secs(2));
+
+    // The timeouts of the client are overridden for a single request.
+    let timeouts =
+        Timeouts::default().request(Some(Duration::from_millis(100)));
+    let start = Instant::now();
+    let request = Request::post("/containers/web/wait");
+    match client
+        .send_request_with_timeouts(request, Some(timeouts))
+        .await
+    {
+        Err(DockerApiError::Timeout(endpoint)) => {
+            assert_eq!(endpoint, "/containers/web/wait")
+        }
+        other => panic!("Unexpected result : {:?}", other.map(|_| ())),
+    }
+    assert!(start.elapsed() < Duration::from_secs(2));
+}
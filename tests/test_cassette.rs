@@ -0,0 +1,153 @@
+#![cfg(feature = "testing")]
+extern crate rust_docker;
+
+use rust_docker::api::containers::Containers;
+use rust_docker::api::images::Images;
+use rust_docker::api::request::Request;
+use rust_docker::api::DockerApiClient;
+use rust_docker::errors::DockerApiError;
+use rust_docker::testing::cassette::{Interaction, Payload, RecordedRequest};
+use rust_docker::testing::{
+    Cassette, FakeDocker, Matching, Recorder, Replayer,
+};
+
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+
+fn cassette_path(name: &str) -> PathBuf {
+    env::temp_dir()
+        .join(format!("rust-docker-cassettes-{}", process::id()))
+        .join(format!("{}.json", name))
+}
+
+/// Records a short session against a fake daemon.
+fn record_session() -> Cassette {
+    let docker = FakeDocker::new().with_image("debian:jessie");
+    let recorder = Recorder::new(docker);
+
+    let created = recorder
+        .create_container_minimal("web", "debian:jessie", Vec::new())
+        .unwrap();
+    recorder.start_container(&created.Id).unwrap();
+    recorder.list_running_containers(Some(5)).unwrap();
+    recorder.list_images(None).unwrap();
+
+    recorder.cassette()
+}
+
+#[test]
+fn test_record_and_replay_strict() {
+    let path = cassette_path("strict");
+    let docker = FakeDocker::new().with_image("debian:jessie");
+    let recorder = Recorder::new(docker);
+    let created = recorder
+        .create_container_minimal("web", "debian:jessie", Vec::new())
+        .unwrap();
+    recorder.start_container("web").unwrap();
+    recorder.save(&path).unwrap();
+
+    let cassette = Cassette::load(&path).unwrap();
+    assert_eq!(cassette.interactions.len(), 2);
+    assert_eq!(cassette.interactions[0].request.method, "POST");
+    assert_eq!(
+        cassette.interactions[0].request.path,
+        "/containers/create?name=web"
+    );
+
+    let replayer = Replayer::from_file(&path, Matching::Strict).unwrap();
+    let replayed = replayer
+        .create_container_minimal("web", "debian:jessie", Vec::new())
+        .unwrap();
+    assert_eq!(replayed.Id, created.Id);
+    replayer.start_container("web").unwrap();
+    assert_eq!(replayer.remaining(), 0);
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_strict_mismatch() {
+    let replayer = Replayer::new(record_session(), Matching::Strict);
+
+    // The container must be created first.
+    let err = replayer.start_container("web").unwrap_err();
+    match err {
+        DockerApiError::ConnectionError(ref endpoint, _) => {
+            assert_eq!(endpoint, "/containers/web/start")
+        }
+        ref other => panic!("Unexpected error : {:?}", other),
+    }
+    let cause = err.source().unwrap().to_string();
+    assert!(cause.starts_with(
+        "Expected POST /containers/create?name=web as request 1 of the cassette"
+    ));
+
+    // Bodies must match as well.
+    let err = replayer
+        .create_container_minimal("web", "debian:stretch", Vec::new())
+        .unwrap_err();
+    assert!(err.source().is_some());
+    assert_eq!(replayer.remaining(), 4);
+}
+
+#[test]
+fn test_replay_lenient() {
+    let replayer = Replayer::new(record_session(), Matching::Lenient);
+
+    // Order, query parameters order and bodies don't matter.
+    let images = replayer.list_images(None).unwrap();
+    assert_eq!(images.len(), 1);
+    let request = Request::get("/v1.30/containers/json")
+        .query("limit", 5)
+        .query("size", true);
+    let resp = replayer.send_request(request).unwrap();
+    assert_eq!(resp.status_code, 200);
+    replayer
+        .create_container_minimal("other", "alpine", Vec::new())
+        .ok();
+    assert_eq!(replayer.remaining(), 2);
+
+    // Interactions are replayed again once they were all used.
+    assert_eq!(replayer.list_images(None).unwrap().len(), 1);
+
+    match replayer.inspect_container("web") {
+        Err(DockerApiError::ConnectionError(..)) => {}
+        other => panic!("Unexpected result : {:?}", other),
+    }
+}
+
+#[test]
+fn test_binary_payloads() {
+    let response =
+        b"HTTP/1.1 200 OK\r\nContent-Length: 4\r\n\r\n\xff\x00\x01\x02";
+    let cassette = Cassette {
+        api_version: Some("1.40".to_string()),
+        interactions: vec![Interaction {
+            request: RecordedRequest {
+                method: "GET".to_string(),
+                path: "/v1.40/containers/web/export".to_string(),
+                body: Payload::default(),
+            },
+            response: Payload::from_bytes(response),
+        }],
+    };
+    assert_eq!(
+        cassette.interactions[0].response,
+        Payload::Binary(response.to_vec())
+    );
+
+    let path = cassette_path("binary");
+    cassette.save(&path).unwrap();
+    let replayer = Replayer::from_file(&path, Matching::Strict).unwrap();
+    assert_eq!(replayer.api_version(), Some("1.40".to_string()));
+
+    let raw = Request::get("/containers/web/export")
+        .to_bytes(Some("1.40"))
+        .unwrap();
+    assert_eq!(replayer.request(&raw).unwrap(), response.to_vec());
+
+    fs::remove_file(&path).unwrap();
+}
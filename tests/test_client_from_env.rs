@@ -0,0 +1,107 @@
+extern crate rust_docker;
+
+mod common;
+
+use rust_docker::api::version::Version;
+use rust_docker::client::DockerClient;
+use rust_docker::errors::DockerClientError;
+use rust_docker::utils::env::with_default_tcp_port;
+
+use std::env;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+
+fn clear_docker_env() {
+    for key in &[
+        "DOCKER_HOST",
+        "DOCKER_API_VERSION",
+        "DOCKER_CERT_PATH",
+        "DOCKER_TLS_VERIFY",
+        "DOCKER_TLS",
+    ] {
+        env::remove_var(key);
+    }
+}
+
+// The environment is shared by the whole test binary, so every scenario
+// runs sequentially from a single test.
+#[test]
+fn test_client_from_env() {
+    clear_docker_env();
+
+    // DOCKER_HOST and DOCKER_API_VERSION
+    let (addr, server) = common::serve_once("{}");
+    env::set_var("DOCKER_HOST", &addr);
+    env::set_var("DOCKER_API_VERSION", "1.30");
+    let client = DockerClient::from_env().unwrap();
+    client.get_version_info().unwrap();
+    let request = server.join().unwrap();
+    assert!(request.starts_with("GET /v1.30/info HTTP/1.1\r\n"));
+
+    // An invalid DOCKER_API_VERSION is reported.
+    let (addr, _server) = common::serve_once("{}");
+    env::set_var("DOCKER_HOST", &addr);
+    env::set_var("DOCKER_API_VERSION", "latest");
+    match DockerClient::from_env() {
+        Err(DockerClientError::InvalidApiVersion(version)) => {
+            assert_eq!(version, "latest")
+        }
+        Err(err) => panic!("Unexpected error : {}", err),
+        Ok(_) => panic!("Invalid API version was accepted"),
+    }
+    env::remove_var("DOCKER_API_VERSION");
+
+    // DOCKER_TLS_VERIFY with DOCKER_CERT_PATH, the daemon is plain HTTP so
+    // the TLS handshake must fail.
+    let (addr, _server) = common::serve_with(|mut stream: TcpStream| {
+        let mut buf = [0; 512];
+        let _ = stream.read(&mut buf);
+        let _ = stream.write_all(b"HTTP/1.1 400 Bad Request\r\n\r\n");
+    });
+    env::set_var("DOCKER_HOST", &addr);
+    env::set_var("DOCKER_TLS_VERIFY", "1");
+    env::set_var(
+        "DOCKER_CERT_PATH",
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/tls/client"),
+    );
+    match DockerClient::from_env() {
+        Err(DockerClientError::TlsHandshakeError(..)) => {}
+        Err(err) => panic!("Unexpected error : {}", err),
+        Ok(_) => panic!("TLS was not used"),
+    }
+
+    // An empty DOCKER_TLS_VERIFY is the same as an unset one.
+    let (addr, server) = common::serve_once("{}");
+    env::set_var("DOCKER_HOST", &addr);
+    env::set_var("DOCKER_TLS_VERIFY", "");
+    let client = DockerClient::from_env().unwrap();
+    client.get_version_info().unwrap();
+    let request = server.join().unwrap();
+    assert!(request.starts_with("GET /info HTTP/1.1\r\n"));
+
+    clear_docker_env();
+}
+
+#[test]
+fn test_default_tcp_port() {
+    assert_eq!(
+        with_default_tcp_port("tcp://docker-host", false),
+        "tcp://docker-host:2375"
+    );
+    assert_eq!(
+        with_default_tcp_port("tcp://docker-host", true),
+        "tcp://docker-host:2376"
+    );
+    assert_eq!(
+        with_default_tcp_port("tcp://docker-host:4243", true),
+        "tcp://docker-host:4243"
+    );
+    assert_eq!(
+        with_default_tcp_port("tcp://[::1]", false),
+        "tcp://[::1]:2375"
+    );
+    assert_eq!(
+        with_default_tcp_port("unix:///var/run/docker.sock", true),
+        "unix:///var/run/docker.sock"
+    );
+}
@@ -0,0 +1,204 @@
+extern crate rust_docker;
+#[macro_use]
+extern crate serde_json;
+
+mod common;
+
+use rust_docker::api::request::Request;
+use rust_docker::api::DockerApiClient;
+use rust_docker::client::DockerClient;
+
+use std::io::Write;
+use std::net::TcpStream;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// Answers every request on the connection with its own path as the body,
+/// until the client closes the connection.
+fn echo_path(mut stream: TcpStream) {
+    loop {
+        let request = common::read_request(&mut stream);
+        if request.is_empty() {
+            return;
+        }
+
+        let path = request.split_whitespace().nth(1).unwrap().to_string();
+        let response = common::json_response(&path);
+        if stream.write_all(response.as_bytes()).is_err() {
+            return;
+        }
+    }
+}
+
+fn get(client: &DockerClient, path: &str) -> String {
+    let resp = client.get_response_from_api(path, "GET", "").unwrap();
+    assert_eq!(resp.status_code, 200);
+    resp.text().unwrap().to_string()
+}
+
+#[test]
+fn test_client_is_send_and_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<DockerClient>();
+}
+
+#[test]
+fn test_shared_client_across_threads() {
+    let addr = common::serve_many(echo_path);
+    let client = Arc::new(DockerClient::new(addr).unwrap());
+
+    let workers: Vec<_> = (0..8)
+        .map(|worker| {
+            let client = client.clone();
+            thread::spawn(move || {
+                for i in 0..50 {
+                    let path = format!("/containers/{}-{}/json", worker, i);
+                    assert_eq!(get(&client, &path), path);
+                }
+            })
+        })
+        .collect();
+
+    for worker in workers {
+        worker.join().unwrap();
+    }
+}
+
+#[test]
+fn test_clones_share_the_pool() {
+    let connections = Arc::new(AtomicUsize::new(0));
+    let counter = connections.clone();
+    let addr = common::serve_many(move |stream| {
+        counter.fetch_add(1, Ordering::SeqCst);
+        echo_path(stream)
+    });
+
+    let client = DockerClient::new(addr).unwrap();
+    for i in 0..10 {
+        let path = format!("/images/{}/json", i);
+        assert_eq!(get(&client.clone(), &path), path);
+    }
+
+    // Sequential requests keep reusing the connection opened by `new`.
+    assert_eq!(connections.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_pool_size_is_bounded() {
+    let open = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+    let (open_count, peak_count) = (open.clone(), peak.clone());
+    let addr = common::serve_many(move |mut stream| {
+        let now = open_count.fetch_add(1, Ordering::SeqCst) + 1;
+        peak_count.fetch_max(now, Ordering::SeqCst);
+
+        loop {
+            let request = common::read_request(&mut stream);
+            if request.is_empty() {
+                break;
+            }
+            // Keep the connection busy so that other requests need their
+            // own connection.
+            thread::sleep(Duration::from_millis(20));
+            let response = common::json_response("{}");
+            if stream.write_all(response.as_bytes()).is_err() {
+                break;
+            }
+        }
+
+        open_count.fetch_sub(1, Ordering::SeqCst);
+    });
+
+    let client = DockerClient::new(addr).unwrap().with_pool_size(2);
+    let workers: Vec<_> = (0..6)
+        .map(|_| {
+            let client = client.clone();
+            thread::spawn(move || {
+                for _ in 0..3 {
+                    assert_eq!(get(&client, "/_ping"), "{}");
+                }
+            })
+        })
+        .collect();
+
+    for worker in workers {
+        worker.join().unwrap();
+    }
+
+    assert!(peak.load(Ordering::SeqCst) <= 2);
+}
+
+#[test]
+fn test_stale_connection_is_replaced() {
+    let connections = Arc::new(AtomicUsize::new(0));
+    let counter = connections.clone();
+    let addr = common::serve_many(move |mut stream| {
+        // The first connection answers a single request and is then closed
+        // by the daemon while idle in the pool.
+        if counter.fetch_add(1, Ordering::SeqCst) == 0 {
+            common::read_request(&mut stream);
+            let response = common::json_response("/first");
+            stream.write_all(response.as_bytes()).unwrap();
+            return;
+        }
+        echo_path(stream)
+    });
+
+    let client = DockerClient::new(addr).unwrap();
+    assert_eq!(get(&client, "/first"), "/first");
+
+    thread::sleep(Duration::from_millis(50));
+    assert_eq!(get(&client, "/second"), "/second");
+    assert_eq!(connections.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn test_connection_close_is_not_reused() {
+    let connections = Arc::new(AtomicUsize::new(0));
+    let counter = connections.clone();
+    let addr = common::serve_many(move |mut stream| {
+        counter.fetch_add(1, Ordering::SeqCst);
+        common::read_request(&mut stream);
+        let response =
+            "HTTP/1.1 200 OK\r\nConnection: close\r\nContent-Length: 2\r\n\r\n{}";
+        stream.write_all(response.as_bytes()).unwrap();
+    });
+
+    let client = DockerClient::new(addr).unwrap();
+    for _ in 0..3 {
+        assert_eq!(get(&client, "/_ping"), "{}");
+    }
+    assert_eq!(connections.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn test_post_is_not_sent_again() {
+    let requests = Arc::new(AtomicUsize::new(0));
+    let counter = requests.clone();
+    let addr = common::serve_many(move |mut stream| {
+        if common::read_request(&mut stream).is_empty() {
+            return;
+        }
+        // The daemon acts on the request, then the connection is reset
+        // before it answers, the body being left unread.
+        counter.fetch_add(1, Ordering::SeqCst);
+        drop(stream);
+    });
+
+    let client = DockerClient::new(addr.clone()).unwrap();
+    let request = Request::post("/containers/create")
+        .json(&json!({ "Image": "debian" }))
+        .unwrap();
+    assert!(client.send_request(request).is_err());
+
+    thread::sleep(Duration::from_millis(50));
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+    // Idempotent requests are sent again on another connection.
+    let client = DockerClient::new(addr).unwrap();
+    assert!(client.send_request(Request::get("/_ping")).is_err());
+    thread::sleep(Duration::from_millis(50));
+    assert_eq!(requests.load(Ordering::SeqCst), 3);
+}
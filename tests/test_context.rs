@@ -0,0 +1,185 @@
+extern crate openssl;
+extern crate rust_docker;
+extern crate uuid;
+
+mod common;
+
+use rust_docker::api::version::Version;
+use rust_docker::context::{context_dir_name, ContextStore, DEFAULT_CONTEXT};
+use rust_docker::errors::DockerClientError;
+
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::thread;
+
+use openssl::ssl::{SslAcceptor, SslFiletype, SslMethod};
+use uuid::Uuid;
+
+/// Creates an empty docker configuration directory.
+fn config_dir() -> PathBuf {
+    let dir = env::temp_dir()
+        .join(format!("docker-rs-context-{}", Uuid::new_v4().to_simple()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Adds a context to the store the same way `docker context create` does.
+fn add_context(config_dir: &Path, name: &str, host: &str, tls: bool) {
+    let meta_dir = config_dir
+        .join("contexts")
+        .join("meta")
+        .join(context_dir_name(name));
+    fs::create_dir_all(&meta_dir).unwrap();
+    fs::write(
+        meta_dir.join("meta.json"),
+        format!(
+            "{{\"Name\":\"{}\",\"Metadata\":{{\"Description\":\"{} daemon\"}},\
+             \"Endpoints\":{{\"docker\":{{\"Host\":\"{}\",\"SkipTLSVerify\":false}}}}}}",
+            name, name, host
+        ),
+    )
+    .unwrap();
+
+    if tls {
+        let tls_dir = config_dir
+            .join("contexts")
+            .join("tls")
+            .join(context_dir_name(name))
+            .join("docker");
+        fs::create_dir_all(&tls_dir).unwrap();
+    }
+}
+
+#[test]
+fn test_context_dir_name() {
+    // sha256("default")
+    assert_eq!(
+        context_dir_name("default"),
+        "37a8eec1ce19687d132fe29051dca629d164e2c4958ba141d5f4133a33f0688f"
+    );
+}
+
+#[test]
+fn test_list_and_get_contexts() {
+    let dir = config_dir();
+    add_context(&dir, "remote", "tcp://10.0.0.2:2376", true);
+    add_context(&dir, "local", "unix:///var/run/docker.sock", false);
+
+    let store = ContextStore::new(&dir);
+    let names: Vec<String> =
+        store.list().unwrap().into_iter().map(|c| c.name).collect();
+    assert_eq!(names, vec![DEFAULT_CONTEXT, "local", "remote"]);
+
+    let remote = store.get("remote").unwrap();
+    assert_eq!(remote.host, "tcp://10.0.0.2:2376");
+    assert_eq!(remote.description, Some("remote daemon".to_string()));
+    let tls = remote.tls.unwrap();
+    assert!(tls.verify_peer);
+    assert_eq!(
+        tls.ca,
+        dir.join("contexts/tls")
+            .join(context_dir_name("remote"))
+            .join("docker/ca.pem")
+    );
+
+    assert!(store.get("local").unwrap().tls.is_none());
+
+    match store.get("missing") {
+        Err(DockerClientError::ContextNotFound(name)) => {
+            assert_eq!(name, "missing")
+        }
+        Err(err) => panic!("Unexpected error : {}", err),
+        Ok(_) => panic!("Missing context was found"),
+    }
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_context_with_ca_only() {
+    // Like `docker context create --docker "host=tcp://..,ca=ca.pem"`, for
+    // daemons which don't authenticate their clients.
+    let tls_fixtures =
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/tls");
+    let server = tls_fixtures.join("server");
+    let mut acceptor =
+        SslAcceptor::mozilla_intermediate_v5(SslMethod::tls()).unwrap();
+    acceptor
+        .set_certificate_chain_file(server.join("cert.pem"))
+        .unwrap();
+    acceptor
+        .set_private_key_file(server.join("key.pem"), SslFiletype::PEM)
+        .unwrap();
+    let acceptor = acceptor.build();
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut stream = acceptor.accept(stream).unwrap();
+        common::read_request(&mut stream);
+        let response = common::json_response("{\"ID\":\"ca-only\"}");
+        stream.write_all(response.as_bytes()).unwrap();
+    });
+
+    let dir = config_dir();
+    let host = format!("tcp://localhost:{}", port);
+    add_context(&dir, "ca-only", &host, true);
+    let tls_dir = dir
+        .join("contexts/tls")
+        .join(context_dir_name("ca-only"))
+        .join("docker");
+    fs::copy(tls_fixtures.join("client/ca.pem"), tls_dir.join("ca.pem"))
+        .unwrap();
+
+    let context = ContextStore::new(&dir).get("ca-only").unwrap();
+    let tls = context.tls.clone().unwrap();
+    assert_eq!(tls.ca, tls_dir.join("ca.pem"));
+    assert_eq!(tls.cert, None);
+    assert_eq!(tls.key, None);
+
+    let client = context.client().unwrap();
+    assert_eq!(client.get_version_info().unwrap(), "{\"ID\":\"ca-only\"}");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+// The environment is shared by the whole test binary, so every scenario
+// depending on it runs sequentially from a single test.
+#[test]
+fn test_current_context() {
+    env::remove_var("DOCKER_CONTEXT");
+    env::remove_var("DOCKER_HOST");
+
+    let dir = config_dir();
+    let store = ContextStore::new(&dir);
+
+    // Without config.json the default context is used.
+    assert_eq!(store.current_context_name().unwrap(), DEFAULT_CONTEXT);
+
+    let (addr, server) = common::serve_once("{\"ID\":\"context\"}");
+    add_context(&dir, "ci", &addr, false);
+    fs::write(dir.join("config.json"), "{\"currentContext\":\"ci\"}").unwrap();
+    assert_eq!(store.current_context_name().unwrap(), "ci");
+
+    let client = store.current().unwrap().client().unwrap();
+    assert_eq!(client.get_version_info().unwrap(), "{\"ID\":\"context\"}");
+    server.join().unwrap();
+
+    // DOCKER_HOST selects the default context.
+    env::set_var("DOCKER_HOST", "tcp://127.0.0.1:2375");
+    assert_eq!(store.current_context_name().unwrap(), DEFAULT_CONTEXT);
+    assert_eq!(store.current().unwrap().host, "tcp://127.0.0.1:2375");
+
+    // DOCKER_CONTEXT overrides everything else.
+    env::set_var("DOCKER_CONTEXT", "other");
+    assert_eq!(store.current_context_name().unwrap(), "other");
+    assert!(store.current().is_err());
+
+    env::remove_var("DOCKER_CONTEXT");
+    env::remove_var("DOCKER_HOST");
+    fs::remove_dir_all(&dir).unwrap();
+}
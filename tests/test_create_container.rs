@@ -0,0 +1,215 @@
+extern crate rust_docker;
+#[macro_use]
+extern crate serde_json;
+
+mod common;
+
+use rust_docker::api::containers::{
+    ContainerConfig, Containers, DeviceMapping, EmptyObject, EndpointSettings,
+    HealthConfig, HostConfig, LogConfig, NetworkingConfig, PortBinding,
+    Resources, RestartPolicy, Ulimit,
+};
+
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::sync::mpsc;
+use std::sync::Mutex;
+
+fn strings(values: &[&str]) -> Vec<String> {
+    values.iter().map(|value| value.to_string()).collect()
+}
+
+#[test]
+fn test_unset_fields_are_omitted() {
+    let config = ContainerConfig {
+        Image: "debian:jessie".to_string(),
+        Tty: Some(false),
+        HostConfig: Some(HostConfig::default()),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        serde_json::to_value(&config).unwrap(),
+        json!({ "Image": "debian:jessie", "Tty": false, "HostConfig": {} })
+    );
+}
+
+#[test]
+fn test_full_create_body() {
+    let (tx, rx) = mpsc::channel();
+    let tx = Mutex::new(tx);
+    let addr = common::serve_many(move |mut stream: TcpStream| {
+        let request = common::read_request(&mut stream);
+        if request.is_empty() {
+            return;
+        }
+
+        let len: usize = request
+            .lines()
+            .find(|line| line.starts_with("Content-Length: "))
+            .map(|line| line["Content-Length: ".len()..].parse().unwrap())
+            .unwrap();
+        let mut body = vec![0; len];
+        stream.read_exact(&mut body).unwrap();
+        tx.lock().unwrap().send((request, body)).unwrap();
+
+        let body = "{\"Id\":\"e90e34656806\",\"Warnings\":[]}";
+        let response = format!(
+            "HTTP/1.1 201 Created\r\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        stream.write_all(response.as_bytes()).unwrap();
+    });
+
+    let mut exposed_ports = HashMap::new();
+    exposed_ports.insert("80/tcp".to_string(), EmptyObject {});
+    let mut port_bindings = HashMap::new();
+    port_bindings.insert(
+        "80/tcp".to_string(),
+        vec![PortBinding {
+            HostIp: None,
+            HostPort: Some("8080".to_string()),
+        }],
+    );
+    let mut endpoints = HashMap::new();
+    endpoints.insert(
+        "backend".to_string(),
+        EndpointSettings {
+            Aliases: Some(strings(&["web"])),
+            ..Default::default()
+        },
+    );
+
+    let config = ContainerConfig {
+        Image: "nginx:1.21".to_string(),
+        Entrypoint: Some(strings(&["/docker-entrypoint.sh"])),
+        Cmd: Some(strings(&["nginx", "-g", "daemon off;"])),
+        Env: Some(strings(&["NGINX_PORT=80"])),
+        ExposedPorts: Some(exposed_ports),
+        Healthcheck: Some(HealthConfig {
+            Test: Some(strings(&["CMD", "curl", "-f", "http://localhost"])),
+            Interval: Some(30_000_000_000),
+            Retries: Some(3),
+            ..Default::default()
+        }),
+        StopSignal: Some("SIGQUIT".to_string()),
+        StopTimeout: Some(20),
+        HostConfig: Some(HostConfig {
+            Binds: Some(strings(&["/srv/www:/usr/share/nginx/html:ro"])),
+            PortBindings: Some(port_bindings),
+            RestartPolicy: Some(RestartPolicy {
+                Name: "on-failure".to_string(),
+                MaximumRetryCount: Some(5),
+            }),
+            CapAdd: Some(strings(&["NET_ADMIN"])),
+            Privileged: Some(false),
+            LogConfig: Some(LogConfig {
+                Type: "json-file".to_string(),
+                Config: None,
+            }),
+            Dns: Some(strings(&["10.0.0.2"])),
+            Resources: Resources {
+                Memory: Some(268_435_456),
+                NanoCpus: Some(500_000_000),
+                Devices: Some(vec![DeviceMapping {
+                    PathOnHost: "/dev/fuse".to_string(),
+                    PathInContainer: "/dev/fuse".to_string(),
+                    CgroupPermissions: "rwm".to_string(),
+                }]),
+                Ulimits: Some(vec![Ulimit {
+                    Name: "nofile".to_string(),
+                    Soft: 1024,
+                    Hard: 2048,
+                }]),
+                ..Default::default()
+            },
+            ..Default::default()
+        }),
+        NetworkingConfig: Some(NetworkingConfig {
+            EndpointsConfig: Some(endpoints),
+        }),
+        ..Default::default()
+    };
+
+    let client = rust_docker::DockerClient::new(addr).unwrap();
+    let created = client.create_container("web", config).unwrap();
+    assert_eq!(created.Id, "e90e34656806");
+
+    let (request, body) = rx.recv().unwrap();
+    assert!(request.starts_with("POST /containers/create?name=web HTTP/1.1"));
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(
+        body,
+        json!({
+            "Image": "nginx:1.21",
+            "Entrypoint": ["/docker-entrypoint.sh"],
+            "Cmd": ["nginx", "-g", "daemon off;"],
+            "Env": ["NGINX_PORT=80"],
+            "ExposedPorts": { "80/tcp": {} },
+            "Healthcheck": {
+                "Test": ["CMD", "curl", "-f", "http://localhost"],
+                "Interval": 30_000_000_000i64,
+                "Retries": 3,
+            },
+            "StopSignal": "SIGQUIT",
+            "StopTimeout": 20,
+            "HostConfig": {
+                "Binds": ["/srv/www:/usr/share/nginx/html:ro"],
+                "PortBindings": { "80/tcp": [{ "HostPort": "8080" }] },
+                "RestartPolicy": {
+                    "Name": "on-failure",
+                    "MaximumRetryCount": 5,
+                },
+                "CapAdd": ["NET_ADMIN"],
+                "Privileged": false,
+                "LogConfig": { "Type": "json-file" },
+                "Dns": ["10.0.0.2"],
+                // Resources are part of the host config itself.
+                "Memory": 268_435_456,
+                "NanoCpus": 500_000_000,
+                "Devices": [{
+                    "PathOnHost": "/dev/fuse",
+                    "PathInContainer": "/dev/fuse",
+                    "CgroupPermissions": "rwm",
+                }],
+                "Ulimits": [{ "Name": "nofile", "Soft": 1024, "Hard": 2048 }],
+            },
+            "NetworkingConfig": {
+                "EndpointsConfig": { "backend": { "Aliases": ["web"] } },
+            },
+        })
+    );
+}
+
+#[test]
+fn test_host_config_from_daemon() {
+    // As found in the answer to inspect, resources included.
+    let host_config: HostConfig = serde_json::from_value(json!({
+        "Binds": null,
+        "NetworkMode": "default",
+        "PortBindings": {},
+        "RestartPolicy": { "Name": "no", "MaximumRetryCount": 0 },
+        "AutoRemove": false,
+        "CapAdd": null,
+        "Privileged": false,
+        "ShmSize": 67108864,
+        "Memory": 0,
+        "CpuShares": 512,
+        "BlkioWeightDevice": [],
+        "OomKillDisable": false,
+        "PidsLimit": null,
+        "Ulimits": null,
+        "MaskedPaths": ["/proc/kcore"],
+    }))
+    .unwrap();
+
+    assert_eq!(host_config.Binds, None);
+    assert_eq!(host_config.NetworkMode.unwrap(), "default");
+    assert_eq!(host_config.RestartPolicy.unwrap().Name, "no");
+    assert_eq!(host_config.ShmSize, Some(67108864));
+    assert_eq!(host_config.Resources.CpuShares, Some(512));
+    assert_eq!(host_config.Resources.PidsLimit, None);
+    assert_eq!(host_config.MaskedPaths.unwrap(), strings(&["/proc/kcore"]));
+}
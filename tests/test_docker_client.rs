@@ -1,8 +1,10 @@
 extern crate rust_docker;
 
 use rust_docker::api::containers::Containers;
+use rust_docker::api::request::Method;
 use rust_docker::api::version::Version;
 use rust_docker::client::DockerClient;
+use rust_docker::errors::DockerApiError;
 
 use std::process::exit;
 
@@ -26,8 +28,7 @@ fn test() {
     let running_cont = client.list_running_containers(None).unwrap();
     println!("{:?}", running_cont);
 
-    let mut cmd: Vec<String> = Vec::new();
-    cmd.push("ls".to_string());
+    let cmd: Vec<String> = vec!["ls".to_string()];
     let res = client
         .create_container_minimal("kk", "debian:jessie", cmd)
         .unwrap();
@@ -44,15 +45,15 @@ fn test_error_when_image_does_not_exist_locally() {
             cmd,
         );
 
-        assert!(res.is_err());
-
-        if let Err(e) = res {
-            assert_eq!(
-                format!("{}", e),
-                "Invalid API response, status_code : 404, body: {\"message\":\"No such image: this-image:doesnt-exist\"}"
-            );
+        match res {
+            Err(DockerApiError::NotFound(method, endpoint, message)) => {
+                assert_eq!(method, Method::POST);
+                assert_eq!(endpoint, "/containers/create");
+                assert_eq!(message, "No such image: this-image:doesnt-exist");
+            }
+            other => panic!("Unexpected result : {:?}", other),
         }
     } else {
-        assert!(false, "Could not create a new DockerClient object");
+        panic!("Could not create a new DockerClient object");
     }
 }
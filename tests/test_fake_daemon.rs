@@ -0,0 +1,250 @@
+#![cfg(feature = "testing")]
+extern crate rust_docker;
+
+use rust_docker::api::api_utils::MAX_API_VERSION;
+use rust_docker::api::containers::{
+    ContainerConfig, Containers, HostConfig, RemoveContainerOptions,
+};
+use rust_docker::api::images::Images;
+use rust_docker::api::request::Request;
+use rust_docker::api::version::Version;
+use rust_docker::api::DockerApiClient;
+use rust_docker::errors::DockerApiError;
+use rust_docker::testing::FakeDocker;
+
+fn cmd(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+}
+
+#[test]
+fn test_container_lifecycle() {
+    let docker = FakeDocker::new().with_image("debian:jessie");
+
+    let created = docker
+        .create_container_minimal("web", "debian:jessie", cmd(&["ls", "-l"]))
+        .unwrap();
+    assert_eq!(created.Id.len(), 64);
+    assert!(docker.list_running_containers(None).unwrap().is_empty());
+
+    docker.start_container("web").unwrap();
+    let running = docker.list_running_containers(None).unwrap();
+    assert_eq!(running.len(), 1);
+    assert_eq!(running[0].Names, vec!["/web".to_string()]);
+    assert_eq!(running[0].Command, "ls -l");
+    assert_eq!(running[0].State, "running");
+
+    let details = docker.inspect_container(&created.Id[..12]).unwrap();
+    assert_eq!(details.Name, "/web");
+    assert_eq!(details.Path, "ls");
+    assert_eq!(details.Args, vec!["-l".to_string()]);
+    assert!(details.State.Running);
+    assert_eq!(details.Config.Image, "debian:jessie");
+    let bridge = &details.NetworkSettings.Networks.unwrap()["bridge"];
+    assert!(bridge.IPAddress.as_ref().unwrap().starts_with("172.17.0."));
+
+    docker.pause_container("web").unwrap();
+    assert!(docker.inspect_container("web").unwrap().State.Paused);
+    docker.unpause_container("web").unwrap();
+
+    docker.kill_container("web", Some("SIGTERM")).unwrap();
+    let details = docker.inspect_container("web").unwrap();
+    assert_eq!(details.State.Status, "exited");
+    assert_eq!(details.State.ExitCode, 143);
+
+    docker.rename_container("web", "api").unwrap();
+    let all = docker.list_all_containers(None).unwrap();
+    assert_eq!(all[0].Names, vec!["/api".to_string()]);
+}
+
+#[test]
+fn test_host_config_is_kept_apart() {
+    let docker = FakeDocker::new().with_image("nginx");
+
+    let config = ContainerConfig {
+        Image: "nginx".to_string(),
+        HostConfig: Some(HostConfig {
+            Binds: Some(vec!["/srv/www:/usr/share/nginx/html".to_string()]),
+            NetworkMode: Some("host".to_string()),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    docker.create_container("web", config).unwrap();
+
+    let details = docker.inspect_container("web").unwrap();
+    assert!(details.Config.HostConfig.is_none());
+    assert_eq!(details.Config.Cmd, None);
+    assert_eq!(details.HostConfig.NetworkMode.unwrap(), "host");
+    assert_eq!(details.Mounts[0].Source, "/srv/www");
+    assert_eq!(details.Mounts[0].Destination, "/usr/share/nginx/html");
+    assert!(details.Mounts[0].RW);
+
+    let all = docker.list_all_containers(None).unwrap();
+    assert_eq!(all[0].HostConfig.NetworkMode.as_ref().unwrap(), "host");
+}
+
+#[test]
+fn test_realistic_errors() {
+    let docker = FakeDocker::new().with_image("debian");
+
+    match docker.create_container_minimal("web", "alpine", Vec::new()) {
+        Err(DockerApiError::NotFound(_, _, message)) => {
+            assert_eq!(message, "No such image: alpine")
+        }
+        other => panic!("Unexpected result : {:?}", other),
+    }
+
+    // Images without a tag are the latest one.
+    docker
+        .create_container_minimal("web", "debian:latest", Vec::new())
+        .unwrap();
+    match docker.create_container_minimal("web", "debian", Vec::new()) {
+        Err(DockerApiError::Conflict(_, _, message)) => {
+            assert!(message.contains("\"/web\" is already in use"))
+        }
+        other => panic!("Unexpected result : {:?}", other),
+    }
+
+    match docker.stop_container("web", None) {
+        Err(DockerApiError::NotModified(_, endpoint, _)) => {
+            assert_eq!(endpoint, "/containers/web/stop")
+        }
+        other => panic!("Unexpected result : {:?}", other),
+    }
+
+    match docker.kill_container("web", None) {
+        Err(DockerApiError::Conflict(_, _, message)) => {
+            assert!(message.ends_with("is not running"))
+        }
+        other => panic!("Unexpected result : {:?}", other),
+    }
+
+    match docker.inspect_container("missing") {
+        Err(DockerApiError::NotFound(_, _, message)) => {
+            assert_eq!(message, "No such container: missing")
+        }
+        other => panic!("Unexpected result : {:?}", other),
+    }
+}
+
+#[test]
+fn test_remove_container() {
+    let docker = FakeDocker::new().with_image("debian:jessie");
+    docker
+        .create_container_minimal("web", "debian:jessie", Vec::new())
+        .unwrap();
+    docker.start_container("web").unwrap();
+
+    let resp = docker
+        .send_request(Request::delete("/containers/web"))
+        .unwrap();
+    assert_eq!(resp.status_code, 409);
+
+    match docker.remove_container("web", RemoveContainerOptions::default()) {
+        Err(DockerApiError::ContainerRunning(id, message)) => {
+            assert_eq!(id, "web");
+            assert!(message.starts_with("You cannot remove a running"));
+        }
+        other => panic!("Unexpected result : {:?}", other),
+    }
+    match docker.remove_container("api", RemoveContainerOptions::default()) {
+        Err(DockerApiError::NoSuchContainer(id, _)) => assert_eq!(id, "api"),
+        other => panic!("Unexpected result : {:?}", other),
+    }
+
+    let resp = docker
+        .send_request(Request::delete("/containers/web").query("force", true))
+        .unwrap();
+    assert_eq!(resp.status_code, 204);
+    assert!(docker.list_all_containers(None).unwrap().is_empty());
+}
+
+#[test]
+fn test_list_limit_and_images() {
+    let docker = FakeDocker::new().with_image("debian:jessie");
+    for name in &["first", "second", "third"] {
+        docker
+            .create_container_minimal(name, "debian:jessie", Vec::new())
+            .unwrap();
+    }
+
+    let latest = docker.list_all_containers(Some(2)).unwrap();
+    assert_eq!(latest.len(), 2);
+    assert_eq!(latest[0].Names, vec!["/third".to_string()]);
+
+    let images = docker.list_images(None).unwrap();
+    assert_eq!(images.len(), 1);
+    assert_eq!(images[0].RepoTags, vec!["debian:jessie".to_string()]);
+    assert_eq!(images[0].Containers, 3);
+}
+
+#[test]
+fn test_logs() {
+    let docker = FakeDocker::new().with_image("debian:jessie");
+    docker
+        .create_container_minimal("web", "debian:jessie", Vec::new())
+        .unwrap();
+    docker.write_stdout("web", "listening\n");
+    docker.write_stderr("web", "warning\n");
+    docker.write_stdout("web", "ready\n");
+
+    let request = Request::get("/containers/web/logs")
+        .query("stdout", true)
+        .query("tail", 1);
+    let resp = docker.send_request(request).unwrap();
+    assert_eq!(resp.status_code, 200);
+    assert_eq!(resp.body, b"\x01\0\0\0\0\0\0\x06ready\n");
+
+    let resp = docker
+        .send_request(Request::get("/containers/web/logs"))
+        .unwrap();
+    assert_eq!(resp.status_code, 400);
+}
+
+#[test]
+fn test_version_and_requests() {
+    let docker = FakeDocker::new();
+    assert!(docker.get_version_info().unwrap().contains("fake-docker"));
+    assert!(docker.get_api_version().is_ok());
+
+    let ping = docker.ping().unwrap();
+    assert_eq!(ping.api_version.unwrap(), MAX_API_VERSION);
+    assert_eq!(ping.os_type.unwrap(), "linux");
+    assert_eq!(docker.version().unwrap().Components[0].Name, "Engine");
+    let info = docker.info().unwrap();
+    assert_eq!(info.Name, "fake-docker");
+    assert_eq!(info.Containers, 0);
+
+    docker.get_container_details_with_filter("{}", Some(1)).ok();
+    let requests = docker.requests();
+    assert_eq!(requests[0], "GET /info");
+    assert_eq!(requests[2], "GET /_ping");
+    assert_eq!(
+        requests.last().unwrap(),
+        "GET /containers/json?all=true&size=true&limit=1&filters={}"
+    );
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn test_async_client() {
+    use rust_docker::asynchronous::containers::AsyncContainers;
+
+    let docker = FakeDocker::new().with_image("debian:jessie");
+    let created = AsyncContainers::create_container_minimal(
+        &docker,
+        "web",
+        "debian:jessie",
+        Vec::new(),
+    )
+    .await
+    .unwrap();
+    AsyncContainers::start_container(&docker, &created.Id)
+        .await
+        .unwrap();
+
+    let running = AsyncContainers::list_running_containers(&docker, None)
+        .await
+        .unwrap();
+    assert_eq!(running.len(), 1);
+}
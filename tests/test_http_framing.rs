@@ -0,0 +1,223 @@
+extern crate rust_docker;
+extern crate serde_json;
+
+mod common;
+
+use rust_docker::api::request::Request;
+use rust_docker::api::version::Version;
+use rust_docker::api::DockerApiClient;
+use rust_docker::client::DockerClient;
+use rust_docker::utils::api::Response;
+
+use std::error::Error;
+use std::io;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::thread;
+use std::time::Duration;
+
+/// Writes the response in several segments, pausing in between so each of
+/// them reaches the client as a separate read.
+fn write_segments(stream: &mut TcpStream, segments: &[&[u8]]) {
+    for segment in segments {
+        stream.write_all(segment).unwrap();
+        stream.flush().unwrap();
+        thread::sleep(Duration::from_millis(20));
+    }
+}
+
+fn large_body() -> String {
+    format!("{{\"Names\":\"{}\"}}", "x".repeat(5000))
+}
+
+#[test]
+fn test_content_length_across_segments() {
+    let body = large_body();
+    let expected = body.clone();
+    let (addr, server) = common::serve_with(move |mut stream: TcpStream| {
+        common::read_request(&mut stream);
+        let head = format!(
+            "HTTP/1.1 200 OK\r\nApi-Version: 1.37\r\nContent-Length: {}\r\n\r\n",
+            body.len()
+        );
+        let (first, second) = body.as_bytes().split_at(1024);
+        write_segments(&mut stream, &[head.as_bytes(), first, second]);
+    });
+
+    let client = DockerClient::new(addr).unwrap();
+    let resp = client.get_response_from_api("/info", "GET", "").unwrap();
+    assert_eq!(resp.status_code, 200);
+    assert_eq!(resp.text().unwrap(), expected);
+    assert_eq!(resp.header("API-Version"), Some("1.37"));
+    assert_eq!(resp.header("content-length"), Some("5012"));
+
+    server.join().unwrap();
+}
+
+#[test]
+fn test_chunked_across_segments() {
+    let (addr, server) = common::serve_with(|mut stream: TcpStream| {
+        common::read_request(&mut stream);
+        write_segments(
+            &mut stream,
+            &[
+                b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n",
+                b"5\r\n{\"ID\"",
+                b"\r\nb;ext=1\r\n:\"chunked",
+                b"\"}\r\n",
+                b"0\r\nX-Trailer: done\r\n\r\n",
+            ],
+        );
+    });
+
+    let client = DockerClient::new(addr).unwrap();
+    let info = client.get_version_info().unwrap();
+    assert_eq!(info, "{\"ID\":\"chunked\"}");
+
+    server.join().unwrap();
+}
+
+#[test]
+fn test_body_until_connection_close() {
+    let body = large_body();
+    let expected = body.clone();
+    let (addr, server) = common::serve_with(move |mut stream: TcpStream| {
+        common::read_request(&mut stream);
+        let (first, second) = body.as_bytes().split_at(3000);
+        write_segments(
+            &mut stream,
+            &[
+                b"HTTP/1.1 200 OK\r\nConnection: close\r\n\r\n",
+                first,
+                second,
+            ],
+        );
+    });
+
+    let client = DockerClient::new(addr).unwrap();
+    assert_eq!(client.get_version_info().unwrap(), expected);
+
+    server.join().unwrap();
+}
+
+#[test]
+fn test_keep_alive_connection() {
+    let (addr, server) = common::serve_with(|mut stream: TcpStream| {
+        for id in &["first", "second"] {
+            common::read_request(&mut stream);
+            let body = format!("{{\"ID\":\"{}\"}}", id);
+            stream
+                .write_all(common::json_response(&body).as_bytes())
+                .unwrap();
+        }
+
+        // A response without a body must not wait for one.
+        common::read_request(&mut stream);
+        stream
+            .write_all(b"HTTP/1.1 204 No Content\r\nApi-Version: 1.37\r\n\r\n")
+            .unwrap();
+    });
+
+    let client = DockerClient::new(addr).unwrap();
+    assert_eq!(client.get_version_info().unwrap(), "{\"ID\":\"first\"}");
+    assert_eq!(client.get_version_info().unwrap(), "{\"ID\":\"second\"}");
+
+    let resp = client
+        .get_response_from_api("/containers/kk/start", "POST", "")
+        .unwrap();
+    assert_eq!(resp.status_code, 204);
+    assert!(resp.body.is_empty());
+
+    server.join().unwrap();
+}
+
+#[test]
+fn test_parse_http_response() {
+    let raw = b"HTTP/1.1 404 Not Found\r\nContent-Type: application/json\r\n\
+                Transfer-Encoding: chunked\r\n\r\n\
+                b\r\n{\"message\":\r\n\
+                7\r\n\"nope\"}\r\n0\r\n\r\n";
+    let resp = Response::parse_http_response(raw.to_vec()).unwrap();
+    assert_eq!(resp.status_code, 404);
+    assert_eq!(resp.header("content-type"), Some("application/json"));
+    assert_eq!(resp.text().unwrap(), "{\"message\":\"nope\"}");
+
+    assert!(
+        Response::parse_http_response(b"HTTP/1.1 200 OK\r\n".to_vec()).is_err()
+    );
+    assert!(Response::parse_http_response(
+        b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\nff\r\nabc"
+            .to_vec()
+    )
+    .is_err());
+}
+
+#[test]
+fn test_chunk_size_not_matching_its_data() {
+    // The size says 3 bytes but 4 are sent before the CRLF.
+    let raw = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n\
+                3\r\nabcd\r\n0\r\n\r\n";
+    assert!(Response::parse_http_response(raw.to_vec()).is_err());
+
+    let addr = common::serve_many(move |mut stream: TcpStream| loop {
+        if common::read_request(&mut stream).is_empty() {
+            return;
+        }
+        stream.write_all(raw).unwrap();
+    });
+    let client = DockerClient::new(addr).unwrap();
+
+    // Read whole, or as it arrives.
+    let err = client.send_request(Request::get("/info")).unwrap_err();
+    let source = err.source().unwrap().downcast_ref::<io::Error>().unwrap();
+    assert_eq!(source.kind(), io::ErrorKind::InvalidData);
+
+    let mut stream = client
+        .send_request_streaming(Request::get("/info"), 200)
+        .unwrap();
+    let mut body = Vec::new();
+    let err = stream.read_to_end(&mut body).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn test_binary_bodies() {
+    let archive: Vec<u8> =
+        (0..=255).chain(b"\r\n \n".iter().cloned()).collect();
+    let mut raw = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/x-tar\r\n\
+         Content-Length: {}\r\n\r\n",
+        archive.len()
+    )
+    .into_bytes();
+    raw.extend_from_slice(&archive);
+
+    // Bodies are kept as they are, trailing whitespace included.
+    let resp = Response::parse_http_response(raw.clone()).unwrap();
+    assert_eq!(resp.body, archive);
+    assert!(resp.text().is_err());
+    assert!(resp.json::<serde_json::Value>().is_err());
+
+    let mut read = Vec::new();
+    resp.into_reader().read_to_end(&mut read).unwrap();
+    assert_eq!(read, archive);
+
+    // Over the connection, read whole or as it arrives.
+    let addr = common::serve_many(move |mut stream: TcpStream| {
+        if common::read_request(&mut stream).is_empty() {
+            return;
+        }
+        let (head, body) = raw.split_at(raw.len() - archive.len());
+        write_segments(&mut stream, &[head, &body[..100], &body[100..]]);
+    });
+
+    let client = DockerClient::new(addr).unwrap();
+    let request = Request::get("/containers/f808ca/export");
+    let resp = client.send_request(request.clone()).unwrap();
+    assert_eq!(resp.body.len(), 260);
+
+    let mut stream = client.send_request_streaming(request, 200).unwrap();
+    let mut read = Vec::new();
+    stream.read_to_end(&mut read).unwrap();
+    assert_eq!(read, resp.body);
+}
@@ -0,0 +1,100 @@
+extern crate rust_docker;
+
+mod common;
+
+use rust_docker::api::containers::Containers;
+use rust_docker::client::DockerClient;
+
+use std::io::Write;
+use std::net::TcpStream;
+
+/// Stand-in answering every request with the provided inspect body.
+fn serve_inspect(body: &'static str) -> DockerClient {
+    let addr = common::serve_many(move |mut stream: TcpStream| loop {
+        if common::read_request(&mut stream).is_empty() {
+            return;
+        }
+        let response = common::json_response(body);
+        stream.write_all(response.as_bytes()).unwrap();
+    });
+
+    DockerClient::new(addr).unwrap()
+}
+
+#[test]
+fn test_inspect_running_container() {
+    let client = serve_inspect(include_str!(
+        "fixtures/api/container_inspect_v1.41.json"
+    ));
+    let details = client.inspect_container("web").unwrap();
+
+    assert_eq!(details.Name, "/web");
+    assert_eq!(details.ExecIDs.unwrap().len(), 1);
+    assert_eq!(details.HostConfig.ShmSize, Some(67108864));
+    assert_eq!(details.HostConfig.Resources.MemorySwappiness, None);
+    assert_eq!(details.GraphDriver.Name, "overlay2");
+    assert!(details.GraphDriver.Data.unwrap().contains_key("MergedDir"));
+
+    let health = details.State.Health.unwrap();
+    assert_eq!(health.Status, "unhealthy");
+    assert_eq!(health.FailingStreak, 3);
+    let log = health.Log.unwrap();
+    assert_eq!(log[0].ExitCode, 7);
+    assert!(log[0].Output.starts_with("curl: (7)"));
+
+    assert_eq!(details.Mounts.len(), 2);
+    assert_eq!(details.Mounts[0].Type, "bind");
+    assert!(!details.Mounts[0].RW);
+    // Bind mounts have no name.
+    assert_eq!(details.Mounts[0].Name, "");
+    assert_eq!(details.Mounts[1].Driver, "local");
+
+    let config = details.Config;
+    assert_eq!(config.Entrypoint.unwrap(), vec!["/docker-entrypoint.sh"]);
+    assert!(config.ExposedPorts.unwrap().contains_key("80/tcp"));
+    assert_eq!(config.StopSignal.unwrap(), "SIGQUIT");
+
+    let settings = details.NetworkSettings;
+    assert_eq!(settings.IPAddress, "172.17.0.2");
+    let ports = settings.Ports.unwrap();
+    // Exposed but not published.
+    assert!(ports["443/tcp"].is_none());
+    let bindings = ports["80/tcp"].as_ref().unwrap();
+    assert_eq!(bindings[1].HostIp.as_ref().unwrap(), "::");
+    assert_eq!(bindings[1].HostPort.as_ref().unwrap(), "8080");
+
+    let networks = settings.Networks.unwrap();
+    let backend = &networks["backend"];
+    assert_eq!(backend.IPAddress.as_ref().unwrap(), "10.1.0.10");
+    assert_eq!(backend.Gateway.as_ref().unwrap(), "10.1.0.1");
+    assert_eq!(backend.Aliases.as_ref().unwrap()[0], "web");
+    let ipam = backend.IPAMConfig.as_ref().unwrap();
+    assert_eq!(ipam.IPv4Address.as_ref().unwrap(), "10.1.0.10");
+    assert!(networks["bridge"].Aliases.is_none());
+}
+
+#[test]
+fn test_inspect_older_daemons() {
+    // Daemons before 1.25 send neither Platform, Mounts types nor health.
+    let client = serve_inspect(
+        "{\"Id\":\"4fa6e0f0c678\",\"Created\":\"2016-11-02T17:58:49Z\",\
+         \"Path\":\"sh\",\"Args\":[],\"State\":{\"Status\":\"exited\",\
+         \"Running\":false,\"ExitCode\":-1,\"Error\":\"oci runtime error\"},\
+         \"Image\":\"sha256:baa5d63\",\"Name\":\"/old\",\"ExecIDs\":null,\
+         \"HostConfig\":{\"NetworkMode\":\"default\"},\
+         \"Mounts\":[{\"Source\":\"/data\",\"Destination\":\"/data\",\
+         \"Mode\":\"\",\"RW\":true}],\
+         \"Config\":{\"Image\":\"busybox\",\"Cmd\":null,\"Entrypoint\":null},\
+         \"NetworkSettings\":{\"Ports\":null,\"Networks\":null}}",
+    );
+    let details = client.inspect_container("old").unwrap();
+
+    assert_eq!(details.Platform, None);
+    assert_eq!(details.ExecIDs, None);
+    assert_eq!(details.State.ExitCode, -1);
+    assert!(details.State.Health.is_none());
+    assert_eq!(details.Mounts[0].Type, "");
+    assert_eq!(details.Config.Cmd, None);
+    assert!(details.NetworkSettings.Networks.is_none());
+    assert_eq!(details.GraphDriver.Name, "");
+}
@@ -0,0 +1,125 @@
+extern crate rust_docker;
+extern crate serde_json;
+
+mod common;
+
+use rust_docker::api::containers::{Container, Containers, Port};
+use rust_docker::api::images::ImageCompactInfo;
+use rust_docker::client::DockerClient;
+
+use std::io::Write;
+use std::net::TcpStream;
+
+fn containers(fixture: &str) -> Vec<Container> {
+    serde_json::from_str(fixture).unwrap()
+}
+
+fn images(fixture: &str) -> Vec<ImageCompactInfo> {
+    serde_json::from_str(fixture).unwrap()
+}
+
+#[test]
+fn test_containers_v1_24() {
+    let containers =
+        containers(include_str!("fixtures/api/containers_v1.24.json"));
+    assert_eq!(containers.len(), 2);
+
+    let exited = &containers[0];
+    assert_eq!(exited.Created, 1367854155);
+    assert_eq!(exited.SizeRw, Some(12288));
+    assert_eq!(exited.Ports[0].PublicPort, Some(3333));
+    assert_eq!(exited.Ports[0].IP, None);
+    // Mounts had neither a type nor a propagation.
+    assert_eq!(exited.Mounts[0].Type, "");
+    assert_eq!(exited.Mounts[0].Propagation, "");
+    assert!(!exited.Mounts[0].RW);
+
+    // Not published, and sizes weren't asked for.
+    let running = &containers[1];
+    assert_eq!(
+        running.Ports,
+        vec![Port {
+            IP: None,
+            PrivatePort: 8080,
+            PublicPort: None,
+            Type: "tcp".to_string(),
+        }]
+    );
+    assert_eq!(running.SizeRootFs, None);
+    assert!(running.extra.is_empty());
+}
+
+#[test]
+fn test_containers_v1_41() {
+    let containers =
+        containers(include_str!("fixtures/api/containers_v1.41.json"));
+    let web = &containers[0];
+
+    assert_eq!(web.Ports.len(), 3);
+    assert_eq!(web.Ports[1].IP.as_ref().unwrap(), "::");
+    assert_eq!(web.Ports[2].PublicPort, None);
+    assert_eq!(web.HostConfig.NetworkMode.as_ref().unwrap(), "default");
+    assert_eq!(web.Mounts[1].Type, "volume");
+
+    let networks = web.NetworkSettings.as_ref().unwrap();
+    let bridge = &networks.Networks.as_ref().unwrap()["bridge"];
+    assert_eq!(bridge.IPAddress.as_ref().unwrap(), "172.17.0.2");
+    assert!(web.extra.is_empty());
+}
+
+#[test]
+fn test_containers_v1_48() {
+    let containers =
+        containers(include_str!("fixtures/api/containers_v1.48.json"));
+    let container = &containers[0];
+
+    assert!(container.Ports.is_empty());
+    assert!(container.Mounts.is_empty());
+    assert_eq!(container.Labels, None);
+
+    // Fields this client doesn't know about are kept.
+    let descriptor = &container.extra["ImageManifestDescriptor"];
+    assert_eq!(descriptor["platform"]["architecture"], "arm64");
+
+    // And sent back along with the others.
+    let value = serde_json::to_value(container).unwrap();
+    assert_eq!(value["ImageManifestDescriptor"]["size"], 424);
+    assert_eq!(value["Id"], container.Id.as_str());
+}
+
+#[test]
+fn test_images() {
+    // SharedSize and Containers are only sent from API 1.25.
+    let old = images(include_str!("fixtures/api/images_v1.24.json"));
+    assert_eq!(old[0].RepoTags, vec!["ubuntu:12.04", "ubuntu:precise"]);
+    assert_eq!(old[0].VirtualSize, Some(103579269));
+    assert_eq!(old[0].Containers, 0);
+
+    let dangling = &images(include_str!("fixtures/api/images_v1.41.json"))[1];
+    assert!(dangling.RepoTags.is_empty());
+    assert_eq!(dangling.RepoDigests, None);
+    assert_eq!(dangling.SharedSize, -1);
+
+    // VirtualSize is no longer sent from API 1.44.
+    let new = images(include_str!("fixtures/api/images_v1.48.json"));
+    assert_eq!(new[0].VirtualSize, None);
+    assert_eq!(new[0].Size, 28861712);
+    assert_eq!(new[0].extra["Descriptor"]["size"], 6688);
+}
+
+#[test]
+fn test_list_containers_with_unpublished_ports() {
+    let addr = common::serve_many(|mut stream: TcpStream| loop {
+        if common::read_request(&mut stream).is_empty() {
+            return;
+        }
+        let body = include_str!("fixtures/api/containers_v1.41.json");
+        let response = common::json_response(body);
+        stream.write_all(response.as_bytes()).unwrap();
+    });
+
+    let client = DockerClient::new(addr).unwrap();
+    let containers = client.list_all_containers(None).unwrap();
+    assert_eq!(containers[0].Names, vec!["/web"]);
+    assert_eq!(containers[0].Ports[2].PrivatePort, 443);
+}
@@ -0,0 +1,260 @@
+extern crate rust_docker;
+
+mod common;
+
+use rust_docker::api::containers::{Containers, LogsOptions};
+use rust_docker::client::DockerClient;
+use rust_docker::errors::DockerApiError;
+use rust_docker::logs::{LogLine, Logs};
+use rust_docker::transport::Timeouts;
+
+use std::io::Write;
+use std::net::TcpStream;
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+fn chunk(data: &[u8]) -> Vec<u8> {
+    let mut chunk = format!("{:x}\r\n", data.len()).into_bytes();
+    chunk.extend_from_slice(data);
+    chunk.extend_from_slice(b"\r\n");
+    chunk
+}
+
+fn frame(stream: u8, data: &str) -> Vec<u8> {
+    let mut frame = vec![stream, 0, 0, 0];
+    frame.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    frame.extend_from_slice(data.as_bytes());
+    frame
+}
+
+fn stdout(line: &str) -> LogLine {
+    LogLine::StdOut(line.as_bytes().to_vec())
+}
+
+fn stderr(line: &str) -> LogLine {
+    LogLine::StdErr(line.as_bytes().to_vec())
+}
+
+#[test]
+fn test_follow_and_stop_early() {
+    let (requests, rx_request) = mpsc::channel();
+    let (tx_read, rx_read) = mpsc::channel::<()>();
+    let (tx_closed, rx_closed) = mpsc::channel();
+    let state = Mutex::new((requests, rx_read, tx_closed));
+    let addr = common::serve_many(move |mut stream: TcpStream| {
+        let request = common::read_request(&mut stream);
+        if request.is_empty() {
+            return;
+        }
+        let state = state.lock().unwrap();
+        state.0.send(request).unwrap();
+
+        let mut sent = b"HTTP/1.1 200 OK\r\n\
+                         Content-Type: application/vnd.docker.multiplexed-stream\r\n\
+                         Transfer-Encoding: chunked\r\n\r\n"
+            .to_vec();
+        // A line split across frames, and frames split across chunks.
+        let mut frames = frame(1, "starting\nlisten");
+        frames.extend(frame(2, "warning: no config\n"));
+        frames.extend(frame(1, "ing on :80\n"));
+        let (first, second) = frames.split_at(20);
+        sent.extend(chunk(first));
+        sent.extend(chunk(second));
+        stream.write_all(&sent).unwrap();
+
+        // Following until the client goes away.
+        state.1.recv().unwrap();
+        let mut closed = false;
+        for i in 0..100 {
+            let line = format!("request {}\n", i);
+            if stream.write_all(&chunk(&frame(1, &line))).is_err() {
+                closed = true;
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        state.2.send(closed).unwrap();
+    });
+
+    let client = DockerClient::new(addr).unwrap();
+    let options = LogsOptions::default()
+        .tail(Some(10))
+        .since(Some(1626082064))
+        .follow(true);
+    let mut logs = client.get_container_logs("web", options).unwrap();
+
+    let request = rx_request.recv().unwrap();
+    assert!(request.starts_with(
+        "GET /containers/web/logs?stdout=true&stderr=true&since=1626082064\
+         &tail=10&timestamps=false&follow=true HTTP/1.1\r\n"
+    ));
+
+    assert_eq!(logs.next().unwrap().unwrap(), stdout("starting"));
+    assert_eq!(logs.next().unwrap().unwrap(), stderr("warning: no config"));
+    assert_eq!(logs.next().unwrap().unwrap(), stdout("listening on :80"));
+
+    tx_read.send(()).unwrap();
+    assert_eq!(logs.next().unwrap().unwrap(), stdout("request 0"));
+    drop(logs);
+    assert!(rx_closed.recv().unwrap());
+}
+
+#[test]
+fn test_tty_output() {
+    // Daemons before 1.42 don't tell the output of a TTY apart.
+    let addr = common::serve_many(|mut stream: TcpStream| {
+        if common::read_request(&mut stream).is_empty() {
+            return;
+        }
+
+        let body = "\x1b[32mroot@web:/# \x1b[0mls\r\nbin  etc\r\n";
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        stream.write_all(response.as_bytes()).unwrap();
+    });
+
+    let client = DockerClient::new(addr).unwrap();
+    let logs = client
+        .get_container_logs("web", LogsOptions::default())
+        .unwrap();
+    let mut output = Vec::new();
+    for line in logs {
+        let line = line.unwrap();
+        assert_eq!(line.stream(), None);
+        output.extend(line.into_data());
+    }
+    assert_eq!(output, b"\x1b[32mroot@web:/# \x1b[0mls\r\nbin  etc\r\n");
+}
+
+#[test]
+fn test_logs_of_missing_container() {
+    let addr = common::serve_many(|mut stream: TcpStream| {
+        if common::read_request(&mut stream).is_empty() {
+            return;
+        }
+
+        let body = "{\"message\":\"No such container: web\"}";
+        let response = format!(
+            "HTTP/1.1 404 Not Found\r\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        stream.write_all(response.as_bytes()).unwrap();
+    });
+
+    let client = DockerClient::new(addr).unwrap();
+    match client.get_container_logs("web", LogsOptions::default()) {
+        Err(DockerApiError::NoSuchContainer(id, message)) => {
+            assert_eq!(id, "web");
+            assert_eq!(message, "No such container: web");
+        }
+        Err(err) => panic!("Unexpected error : {}", err),
+        Ok(_) => panic!("Got the logs of a missing container"),
+    }
+}
+
+#[test]
+fn test_last_lines_without_newline() {
+    let mut output = frame(2, "fatal: ");
+    output.extend(frame(1, "partial"));
+    output.extend(frame(2, "disk full"));
+
+    let lines: Vec<LogLine> = Logs::new(&output[..], None)
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(lines, vec![stdout("partial"), stderr("fatal: disk full")]);
+
+    // A truncated frame.
+    let mut logs = Logs::new(&output[..10], Some(false));
+    match logs.next() {
+        Some(Err(DockerApiError::StreamReadError(_))) => {}
+        other => panic!("Unexpected line : {:?}", other),
+    }
+    assert!(logs.next().is_none());
+}
+
+#[cfg(feature = "testing")]
+#[test]
+fn test_fake_daemon_logs() {
+    use rust_docker::testing::FakeDocker;
+
+    let docker = FakeDocker::new().with_image("debian");
+    docker
+        .create_container_minimal("web", "debian", Vec::new())
+        .unwrap();
+    docker.write_stdout("web", "one\n");
+    docker.write_stderr("web", "two\n");
+    docker.write_stdout("web", "three\n");
+
+    let options = LogsOptions::default().tail(Some(2));
+    let lines: Vec<LogLine> = docker
+        .get_container_logs("web", options)
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(lines, vec![stderr("two"), stdout("three")]);
+
+    let options = LogsOptions::default().stdout(false).timestamps(true);
+    let lines: Vec<LogLine> = docker
+        .get_container_logs("web", options)
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    let line = String::from_utf8(lines[0].data().to_vec()).unwrap();
+    let (timestamp, text) = line.split_at(line.find(' ').unwrap());
+    assert!(timestamp.ends_with('Z'));
+    assert_eq!(text, " two");
+
+    // Logs written before `since` are left out.
+    let options = LogsOptions::default().since(Some(i64::MAX));
+    let mut logs = docker.get_container_logs("web", options).unwrap();
+    assert!(logs.next().is_none());
+}
+
+#[test]
+fn test_follow_quiet_container() {
+    let addr = common::serve_many(|mut stream: TcpStream| {
+        if common::read_request(&mut stream).is_empty() {
+            return;
+        }
+
+        let mut sent = b"HTTP/1.1 200 OK\r\n\
+                         Content-Type: application/vnd.docker.multiplexed-stream\r\n\
+                         Transfer-Encoding: chunked\r\n\r\n"
+            .to_vec();
+        sent.extend(chunk(&frame(1, "waiting\n")));
+        stream.write_all(&sent).unwrap();
+
+        // Quiet for longer than the read timeout of the client.
+        thread::sleep(Duration::from_millis(300));
+        let mut sent = chunk(&frame(1, "done\n"));
+        sent.extend(chunk(b""));
+        stream.write_all(&sent).unwrap();
+    });
+
+    let timeouts = Timeouts::default().read(Some(Duration::from_millis(100)));
+    let client = DockerClient::new(addr).unwrap().with_timeouts(timeouts);
+
+    let options = LogsOptions::default().follow(true);
+    let lines: Vec<LogLine> = client
+        .get_container_logs("web", options)
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(lines, vec![stdout("waiting"), stdout("done")]);
+
+    // Logs which aren't followed still time out.
+    let mut logs = client
+        .get_container_logs("web", LogsOptions::default())
+        .unwrap();
+    assert_eq!(logs.next().unwrap().unwrap(), stdout("waiting"));
+    match logs.next() {
+        Some(Err(DockerApiError::StreamReadError(_))) => {}
+        other => panic!("Unexpected line : {:?}", other),
+    }
+}
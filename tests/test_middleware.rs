@@ -0,0 +1,194 @@
+extern crate log;
+extern crate rust_docker;
+
+mod common;
+
+use rust_docker::api::containers::Containers;
+use rust_docker::api::request::Request;
+use rust_docker::api::version::Version;
+use rust_docker::api::DockerApiClient;
+use rust_docker::client::DockerClient;
+use rust_docker::errors::DockerApiError;
+use rust_docker::middleware::{Logger, MiddlewareClient, Next, SetHeader};
+use rust_docker::utils::api::Response;
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::net::TcpStream;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+/// Keeps the messages logged by the tests.
+struct Captured(Mutex<Vec<String>>);
+
+impl Log for Captured {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        let message = format!("{} {}", record.level(), record.args());
+        self.0.lock().unwrap().push(message);
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: Captured = Captured(Mutex::new(Vec::new()));
+
+#[test]
+fn test_set_header() {
+    let (addr, server) = common::serve_once("{\"ID\":\"stand-in\"}");
+
+    let client = MiddlewareClient::new(DockerClient::new(addr).unwrap())
+        .with(SetHeader::new("X-Registry-Auth", "c2VjcmV0"));
+    assert!(client.get_version_info().is_ok());
+
+    let request = server.join().unwrap();
+    assert!(request.starts_with("GET /info HTTP/1.1\r\n"));
+    assert!(request.contains("\r\nX-Registry-Auth: c2VjcmV0\r\n"));
+}
+
+#[test]
+fn test_middlewares_order() {
+    let (addr, _server) = common::serve_once("{}");
+
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let (first, second) = (calls.clone(), calls.clone());
+    let client = MiddlewareClient::new(DockerClient::new(addr).unwrap())
+        .with(move |request: Request, next: Next<'_>| {
+            first.lock().unwrap().push("first");
+            let resp = next.run(request.header("X-Trace", "1"));
+            first.lock().unwrap().push("first done");
+            resp
+        })
+        .with(move |request: Request, next: Next<'_>| {
+            // Headers set by the middlewares before are visible.
+            assert_eq!(
+                request.headers(),
+                &[("X-Trace".to_string(), "1".to_string())]
+            );
+            second.lock().unwrap().push("second");
+            next.run(request)
+        });
+
+    client.send_request(Request::get("/info")).unwrap();
+    assert_eq!(
+        *calls.lock().unwrap(),
+        vec!["first", "second", "first done"]
+    );
+}
+
+#[test]
+fn test_transform_and_short_circuit() {
+    let (addr, _server) =
+        common::serve_once("[{\"Id\":\"f808ca\",\"Names\":[\"/web\"]}]");
+
+    fn redact(
+        request: Request,
+        next: Next<'_>,
+    ) -> Result<Response, DockerApiError> {
+        let mut resp = next.run(request)?;
+        resp.body = resp.text()?.replace("f808ca", "<redacted>").into_bytes();
+        Ok(resp)
+    }
+
+    fn answer_pings(
+        request: Request,
+        next: Next<'_>,
+    ) -> Result<Response, DockerApiError> {
+        if request.endpoint() != "/_ping" {
+            return next.run(request);
+        }
+
+        let mut headers = HashMap::new();
+        headers.insert("Api-Version".to_string(), "1.40".to_string());
+        Ok(Response {
+            status_code: 200,
+            headers,
+            body: b"OK".to_vec(),
+        })
+    }
+
+    let client = MiddlewareClient::new(DockerClient::new(addr).unwrap())
+        .with(redact)
+        .with(answer_pings);
+
+    // The stand-in only answers a single request, pings never reach it.
+    assert_eq!(client.get_api_version().unwrap(), "1.40");
+    assert_eq!(client.get_api_version().unwrap(), "1.40");
+    let resp = client
+        .send_request(Request::get("/containers/json"))
+        .unwrap();
+    assert_eq!(
+        resp.text().unwrap(),
+        "[{\"Id\":\"<redacted>\",\"Names\":[\"/web\"]}]"
+    );
+}
+
+#[test]
+fn test_logger() {
+    log::set_logger(&LOGGER).unwrap();
+    log::set_max_level(LevelFilter::Trace);
+
+    let (addr, _server) = common::serve_with(|mut stream: TcpStream| {
+        common::read_request(&mut stream);
+        let body = "{\"message\":\"No such container: f808ca\"}";
+        let response = format!(
+            "HTTP/1.1 404 Not Found\r\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        stream.write_all(response.as_bytes()).unwrap();
+    });
+
+    let client = MiddlewareClient::new(DockerClient::new(addr).unwrap())
+        .with(Logger::new(Level::Warn));
+    assert!(client.inspect_container("f808ca").is_err());
+
+    let logged: Vec<String> = LOGGER
+        .0
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|message| message.starts_with("WARN"))
+        .cloned()
+        .collect();
+    assert_eq!(logged.len(), 1);
+    assert!(
+        logged[0].starts_with("WARN GET /containers/f808ca/json -> 404 in ")
+    );
+}
+
+#[test]
+fn test_set_header_on_streaming_requests() {
+    let (tx, rx) = mpsc::channel();
+    let tx = Mutex::new(tx);
+    let addr = common::serve_many(move |mut stream: TcpStream| {
+        let request = common::read_request(&mut stream);
+        if request.is_empty() {
+            return;
+        }
+        tx.lock().unwrap().send(request).unwrap();
+        let response = "HTTP/1.1 101 UPGRADED\r\nConnection: Upgrade\r\n\
+                        Upgrade: tcp\r\n\r\n";
+        stream.write_all(response.as_bytes()).unwrap();
+    });
+
+    let client = MiddlewareClient::new(DockerClient::new(addr).unwrap())
+        .with(SetHeader::new("X-Registry-Auth", "c2VjcmV0"));
+
+    let request = Request::post("/images/create").query("fromImage", "debian");
+    assert!(client.send_request_streaming(request, 101).is_ok());
+    let request = rx.recv().unwrap();
+    assert!(request.starts_with("POST /images/create?fromImage=debian "));
+    assert!(request.contains("\r\nX-Registry-Auth: c2VjcmV0\r\n"));
+
+    let request = Request::post("/containers/f808ca/attach");
+    assert!(client.send_upgrade_request(request).is_ok());
+    let request = rx.recv().unwrap();
+    assert!(request.contains("\r\nUpgrade: tcp\r\n"));
+    assert!(request.contains("\r\nX-Registry-Auth: c2VjcmV0\r\n"));
+}
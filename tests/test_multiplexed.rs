@@ -0,0 +1,128 @@
+extern crate rust_docker;
+
+use rust_docker::multiplexed::{self, Demultiplexer, Frame, StreamType};
+
+use std::io;
+use std::io::Read;
+
+/// A reader handing its data out a few bytes at a time, like a connection.
+struct Trickle<'a> {
+    data: &'a [u8],
+    step: usize,
+}
+
+impl<'a> Read for Trickle<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = self.step.min(buf.len()).min(self.data.len());
+        buf[..len].copy_from_slice(&self.data[..len]);
+        self.data = &self.data[len..];
+        Ok(len)
+    }
+}
+
+fn multiplexed(frames: &[(StreamType, &str)]) -> Vec<u8> {
+    let mut stream = Vec::new();
+    for &(kind, data) in frames {
+        multiplexed::write_frame(&mut stream, kind, data.as_bytes()).unwrap();
+    }
+    stream
+}
+
+#[test]
+fn test_frames() {
+    let mut stream = multiplexed(&[
+        (StreamType::StdOut, "listening\n"),
+        (StreamType::StdErr, "warning\n"),
+        (StreamType::StdIn, "ls\n"),
+    ]);
+    assert_eq!(&stream[..8], &[1, 0, 0, 0, 0, 0, 0, 10]);
+    // An empty frame.
+    stream.extend_from_slice(&[1, 0, 0, 0, 0, 0, 0, 0]);
+
+    let frames: Vec<Frame> = Demultiplexer::new(Trickle {
+        data: &stream,
+        step: 3,
+    })
+    .collect::<io::Result<_>>()
+    .unwrap();
+    assert_eq!(
+        frames,
+        vec![
+            Frame::StdOut(b"listening\n".to_vec()),
+            Frame::StdErr(b"warning\n".to_vec()),
+            Frame::StdIn(b"ls\n".to_vec()),
+            Frame::StdOut(Vec::new()),
+        ]
+    );
+    assert_eq!(frames[1].stream(), StreamType::StdErr);
+    assert_eq!(frames[1].data(), b"warning\n");
+}
+
+#[test]
+fn test_copy_and_strings() {
+    let stream = multiplexed(&[
+        (StreamType::StdOut, "caf"),
+        (StreamType::StdErr, "oops\n"),
+        (StreamType::StdOut, "é\n"),
+    ]);
+
+    let (mut stdout, mut stderr) = (Vec::new(), Vec::new());
+    Demultiplexer::new(&stream[..])
+        .copy_to(&mut stdout, &mut stderr)
+        .unwrap();
+    assert_eq!(stdout, "café\n".as_bytes());
+    assert_eq!(stderr, b"oops\n");
+
+    // Characters split over frames are put back together.
+    let (stdout, stderr) =
+        Demultiplexer::new(&stream[..]).read_to_strings().unwrap();
+    assert_eq!(stdout, "café\n");
+    assert_eq!(stderr, "oops\n");
+}
+
+#[test]
+fn test_truncated_and_raw_streams() {
+    let stream = multiplexed(&[(StreamType::StdOut, "listening\n")]);
+
+    let mut demux = Demultiplexer::new(&stream[..4]);
+    let err = demux.read_frame().unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+    let mut demux = Demultiplexer::new(&stream[..12]);
+    let err = demux.read_frame().unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+    // The output of containers with a TTY isn't multiplexed.
+    let mut demux = Demultiplexer::new(&b"root@f808ca:/# "[..]);
+    let err = demux.read_frame().unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+    let mut demux = Demultiplexer::new(&b""[..]);
+    assert!(demux.read_frame().unwrap().is_none());
+}
+
+#[cfg(feature = "testing")]
+#[test]
+fn test_fake_daemon_logs() {
+    use rust_docker::api::containers::Containers;
+    use rust_docker::api::request::Request;
+    use rust_docker::api::DockerApiClient;
+    use rust_docker::testing::FakeDocker;
+
+    let docker = FakeDocker::new().with_image("debian:jessie");
+    docker
+        .create_container_minimal("web", "debian:jessie", Vec::new())
+        .unwrap();
+    docker.write_stdout("web", "listening\n");
+    docker.write_stderr("web", "warning\n");
+
+    let request = Request::get("/containers/web/logs")
+        .query("stdout", true)
+        .query("stderr", true);
+    let resp = docker.send_request(request).unwrap();
+    let (stdout, stderr) = Demultiplexer::new(resp.into_reader())
+        .read_to_strings()
+        .unwrap();
+    assert_eq!(stdout, "listening\n");
+    assert_eq!(stderr, "warning\n");
+}
@@ -0,0 +1,344 @@
+extern crate rust_docker;
+
+mod common;
+
+use rust_docker::api::containers::{
+    Containers, LogsOptions, RemoveContainerOptions,
+};
+use rust_docker::api::images::Images;
+use rust_docker::api::request::{percent_encode, Method, Request};
+use rust_docker::api::DockerApiClient;
+use rust_docker::client::DockerClient;
+use rust_docker::errors::DockerApiError;
+
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Reads a request head along with the body announced by its
+/// `Content-Length` header.
+fn read_request_with_body(stream: &mut TcpStream) -> (String, Vec<u8>) {
+    let head = common::read_request(stream);
+    let length = head
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(2, ':');
+            match (parts.next(), parts.next()) {
+                (Some(name), Some(value))
+                    if name.eq_ignore_ascii_case("content-length") =>
+                {
+                    value.trim().parse::<usize>().ok()
+                }
+                _ => None,
+            }
+        })
+        .next()
+        .unwrap_or(0);
+
+    let mut body = vec![0; length];
+    stream.read_exact(&mut body).unwrap();
+    (head, body)
+}
+
+#[test]
+fn test_percent_encode() {
+    assert_eq!(percent_encode("abc-XYZ_0.9~"), "abc-XYZ_0.9~");
+    assert_eq!(percent_encode("/srv/my app"), "%2Fsrv%2Fmy%20app");
+    assert_eq!(
+        percent_encode("{\"label\":[\"a=b&c\"]}"),
+        "%7B%22label%22%3A%5B%22a%3Db%26c%22%5D%7D"
+    );
+    assert_eq!(percent_encode("é"), "%C3%A9");
+}
+
+#[test]
+fn test_request_line_for_every_method() {
+    let requests = vec![
+        (Request::get("/info"), "GET"),
+        (Request::post("/info"), "POST"),
+        (Request::put("/info"), "PUT"),
+        (Request::delete("/info"), "DELETE"),
+        (Request::head("/info"), "HEAD"),
+    ];
+
+    for (request, method) in requests {
+        assert_eq!(Method::parse(method), Some(request.method()));
+        let raw = request.to_bytes(Some("1.40")).unwrap();
+        let raw = String::from_utf8(raw).unwrap();
+        assert!(raw.starts_with(&format!(
+            "{} /v1.40/info HTTP/1.1\r\nHost: docker\r\n",
+            method
+        )));
+    }
+
+    assert_eq!(Method::parse("patch"), None);
+    assert_eq!(Method::parse("delete"), Some(Method::DELETE));
+}
+
+#[test]
+fn test_query_parameters() {
+    let request = Request::get("/containers/json")
+        .query("all", true)
+        .query_opt("limit", Some(5))
+        .query_opt("size", None::<bool>)
+        .query("filters", "{\"status\":[\"exited\"]}");
+    assert_eq!(
+        request.path(),
+        "/containers/json?all=true&limit=5&filters=%7B%22status%22%3A%5B%22exited%22%5D%7D"
+    );
+
+    // Endpoints which already carry a query string are extended.
+    let request = Request::get("/images/json?all=true").query("digests", 1);
+    assert_eq!(request.path(), "/images/json?all=true&digests=1");
+}
+
+#[test]
+fn test_headers_and_content_length() {
+    let raw = Request::post("/containers/f808ca/start")
+        .header("X-Registry-Auth", "first")
+        .header("x-registry-auth", "second")
+        .to_bytes(None)
+        .unwrap();
+    let raw = String::from_utf8(raw).unwrap();
+    assert_eq!(
+        raw,
+        "POST /containers/f808ca/start HTTP/1.1\r\nHost: docker\r\n\
+         x-registry-auth: second\r\nContent-Length: 0\r\n\r\n"
+    );
+
+    // No body is announced for requests without one.
+    let raw = Request::get("/info").to_bytes(None).unwrap();
+    let raw = String::from_utf8(raw).unwrap();
+    assert_eq!(raw, "GET /info HTTP/1.1\r\nHost: docker\r\n\r\n");
+}
+
+#[test]
+fn test_headers_set_by_the_caller() {
+    let raw = Request::post("/build")
+        .header("host", "docker.example.com")
+        .body(vec![0u8; 4], "application/x-tar")
+        .header("Content-Length", "4")
+        .to_bytes(None)
+        .unwrap();
+    let raw = String::from_utf8(raw).unwrap();
+    assert_eq!(
+        raw,
+        "POST /build HTTP/1.1\r\nhost: docker.example.com\r\n\
+         Content-Type: application/x-tar\r\nContent-Length: 4\r\n\r\n\0\0\0\0"
+    );
+}
+
+#[test]
+fn test_headers_with_line_breaks() {
+    let values = vec!["auth\r\nX-Injected: 1", "auth\n", "auth\0"];
+    for value in values {
+        let request = Request::get("/info").header("X-Registry-Auth", value);
+        assert!(request.headers().is_empty());
+        match request.to_bytes(None) {
+            Err(DockerApiError::InvalidParametersError(msg)) => {
+                assert!(msg.contains("X-Registry-Auth"))
+            }
+            other => panic!("Unexpected result : {:?}", other),
+        }
+    }
+
+    let request = Request::get("/info").header("X-Auth\r\nHost", "docker");
+    assert!(request.to_bytes(None).is_err());
+
+    // Nothing is sent to the daemon.
+    let (tx, rx) = mpsc::channel();
+    let tx = Mutex::new(tx);
+    let addr = common::serve_many(move |mut stream: TcpStream| {
+        let request = common::read_request(&mut stream);
+        if !request.is_empty() {
+            tx.lock().unwrap().send(request).unwrap();
+        }
+    });
+    let client = DockerClient::new(addr).unwrap();
+    let request = Request::get("/info").header("X-Trace", "1\r\n\r\nGET /");
+    match client.send_request(request) {
+        Err(DockerApiError::InvalidParametersError(_)) => {}
+        other => panic!("Unexpected result : {:?}", other.map(|_| ())),
+    }
+    drop(client);
+    assert!(rx.recv_timeout(Duration::from_millis(100)).is_err());
+}
+
+#[test]
+fn test_ids_with_line_breaks() {
+    let request = Request::delete("/containers/web\r\nX-Injected: 1");
+    match request.to_bytes(None) {
+        Err(DockerApiError::InvalidParametersError(msg)) => {
+            assert!(msg.contains("/containers/web\\r\\n"))
+        }
+        other => panic!("Unexpected result : {:?}", other),
+    }
+    assert!(Request::get("/containers/my app/json")
+        .to_bytes(None)
+        .is_err());
+    assert!(Request::get("/info\0").to_bytes(Some("1.41")).is_err());
+
+    // Nothing is sent to the daemon.
+    let (tx, rx) = mpsc::channel();
+    let tx = Mutex::new(tx);
+    let addr = common::serve_many(move |mut stream: TcpStream| {
+        let request = common::read_request(&mut stream);
+        if !request.is_empty() {
+            tx.lock().unwrap().send(request).unwrap();
+        }
+    });
+    let client = DockerClient::new(addr).unwrap();
+    let id = "web HTTP/1.1\r\nHost: docker\r\n\r\nDELETE /containers/db";
+    match client.remove_container(id, RemoveContainerOptions::default()) {
+        Err(DockerApiError::InvalidParametersError(_)) => {}
+        other => panic!("Unexpected result : {:?}", other),
+    }
+    match client.get_container_logs(id, LogsOptions::default()) {
+        Err(DockerApiError::InvalidParametersError(_)) => {}
+        Err(err) => panic!("Unexpected error : {}", err),
+        Ok(_) => panic!("Got the logs of an invalid id"),
+    }
+    drop(client);
+    assert!(rx.recv_timeout(Duration::from_millis(100)).is_err());
+}
+
+#[test]
+fn test_binary_body() {
+    let body: Vec<u8> = (0..=255).collect();
+    let raw = Request::put("/containers/f808ca/archive")
+        .query("path", "/tmp")
+        .body(body.clone(), "application/x-tar")
+        .to_bytes(None)
+        .unwrap();
+
+    let head = b"PUT /containers/f808ca/archive?path=%2Ftmp HTTP/1.1\r\n\
+                 Host: docker\r\nContent-Type: application/x-tar\r\n\
+                 Content-Length: 256\r\n\r\n";
+    assert_eq!(&raw[..head.len()], &head[..]);
+    assert_eq!(&raw[head.len()..], &body[..]);
+}
+
+#[test]
+fn test_put_and_delete_round_trip() {
+    let (addr, server) = common::serve_with(|mut stream: TcpStream| {
+        let put = read_request_with_body(&mut stream);
+        stream
+            .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n")
+            .unwrap();
+
+        let delete = read_request_with_body(&mut stream);
+        stream
+            .write_all(b"HTTP/1.1 204 No Content\r\n\r\n")
+            .unwrap();
+        (put, delete)
+    });
+
+    let client = DockerClient::new(addr)
+        .unwrap()
+        .with_api_version("1.40")
+        .unwrap();
+
+    let archive = vec![0u8, 13, 10, 255, 0];
+    let request = Request::put("/containers/f808ca/archive")
+        .query("path", "/srv/my app")
+        .body(archive.clone(), "application/x-tar");
+    let resp = client.send_request(request).unwrap();
+    assert_eq!(resp.status_code, 200);
+
+    let request = Request::delete("/containers/f808ca").query("force", true);
+    let resp = client.send_request(request).unwrap();
+    assert_eq!(resp.status_code, 204);
+
+    let ((put_head, put_body), (delete_head, delete_body)) =
+        server.join().unwrap();
+    assert!(put_head.starts_with(
+        "PUT /v1.40/containers/f808ca/archive?path=%2Fsrv%2Fmy%20app HTTP/1.1\r\n"
+    ));
+    assert!(put_head.contains("Content-Length: 5\r\n"));
+    assert_eq!(put_body, archive);
+    assert!(delete_head.starts_with(
+        "DELETE /v1.40/containers/f808ca?force=true HTTP/1.1\r\n"
+    ));
+    assert!(delete_body.is_empty());
+}
+
+#[test]
+fn test_container_actions_are_posted() {
+    let (addr, server) = common::serve_with(|mut stream: TcpStream| {
+        let request = read_request_with_body(&mut stream);
+        stream
+            .write_all(b"HTTP/1.1 204 No Content\r\n\r\n")
+            .unwrap();
+        request
+    });
+
+    let client = DockerClient::new(addr).unwrap();
+    assert!(client.rename_container("f808ca", "my app").is_ok());
+
+    let (head, body) = server.join().unwrap();
+    assert!(head.starts_with(
+        "POST /containers/f808ca/rename?name=my%20app HTTP/1.1\r\n"
+    ));
+    assert!(head.contains("Content-Length: 0\r\n"));
+    assert!(body.is_empty());
+}
+
+#[test]
+fn test_raw_query_of_container_actions() {
+    let (addr, server) = common::serve_with(|mut stream: TcpStream| {
+        let (head, _) = read_request_with_body(&mut stream);
+        stream
+            .write_all(b"HTTP/1.1 204 No Content\r\n\r\n")
+            .unwrap();
+        head
+    });
+
+    let client = DockerClient::new(addr).unwrap();
+    let resp = client.manipulate_container_status("stop", "f808ca", "t=5");
+    assert_eq!(resp.unwrap(), "Container stop successful");
+
+    let head = server.join().unwrap();
+    assert!(head.starts_with("POST /containers/f808ca/stop?t=5 HTTP/1.1\r\n"));
+}
+
+#[test]
+fn test_list_filters() {
+    let (requests, rx_request) = mpsc::channel();
+    let requests = Mutex::new(requests);
+    let addr = common::serve_many(move |mut stream: TcpStream| loop {
+        let request = common::read_request(&mut stream);
+        if request.is_empty() {
+            return;
+        }
+        requests.lock().unwrap().send(request).unwrap();
+        let response = common::json_response("[]");
+        stream.write_all(response.as_bytes()).unwrap();
+    });
+
+    let client = DockerClient::new(addr).unwrap();
+    let filter = "{\"dangling\":[\"true\"]}";
+    assert!(client.list_images(Some(filter)).unwrap().is_empty());
+    let request = rx_request.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert!(request.starts_with(
+        "GET /images/json?filters=%7B%22dangling%22%3A%5B%22true%22%5D%7D \
+         HTTP/1.1\r\n"
+    ));
+
+    // No filters are sent when none were given.
+    assert!(client.list_images(None).unwrap().is_empty());
+    let request = rx_request.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert!(request.starts_with("GET /images/json HTTP/1.1\r\n"));
+
+    let filter = "{\"status\":[\"exited\"]}";
+    let containers = client
+        .get_container_details_with_filter(filter, Some(1))
+        .unwrap();
+    assert!(containers.is_empty());
+    let request = rx_request.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert!(request.starts_with(
+        "GET /containers/json?all=true&size=true&limit=1\
+         &filters=%7B%22status%22%3A%5B%22exited%22%5D%7D HTTP/1.1\r\n"
+    ));
+}
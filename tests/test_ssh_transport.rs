@@ -0,0 +1,154 @@
+extern crate rust_docker;
+extern crate uuid;
+
+use rust_docker::api::request::Request;
+use rust_docker::api::version::Version;
+use rust_docker::api::DockerApiClient;
+use rust_docker::client::DockerClient;
+use rust_docker::errors::{DockerApiError, DockerClientError};
+use rust_docker::transport::{SshConfig, Timeouts};
+use rust_docker::utils::api::validate_ssh_address;
+
+use std::env;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use uuid::Uuid;
+
+/// A stand-in for the ssh client, it records the arguments it was spawned
+/// with next to itself and plays the daemon on the other side of
+/// `docker system dial-stdio` by answering a single request on its stdio.
+const SSH_STUB: &str = r#"#!/bin/sh
+printf '%s\n' "$@" > "$(dirname "$0")/args"
+cr=$(printf '\r')
+while IFS= read -r line; do
+    [ "$line" = "$cr" ] && break
+    [ -z "$line" ] && break
+done
+body='{"ID":"ssh-stand-in"}'
+printf 'HTTP/1.1 200 OK\r\nContent-Length: %s\r\n\r\n%s' "${#body}" "$body"
+"#;
+
+/// A stand-in for an ssh client whose daemon hangs, it reads the request
+/// but never answers it.
+const HUNG_SSH_STUB: &str = r#"#!/bin/sh
+cr=$(printf '\r')
+while IFS= read -r line; do
+    [ "$line" = "$cr" ] && break
+    [ -z "$line" ] && break
+done
+exec sleep 30
+"#;
+
+fn ssh_stub() -> PathBuf {
+    write_ssh_stub(SSH_STUB)
+}
+
+fn write_ssh_stub(script: &str) -> PathBuf {
+    let dir = env::temp_dir()
+        .join(format!("docker-rs-ssh-{}", Uuid::new_v4().to_simple()));
+    fs::create_dir_all(&dir).unwrap();
+
+    let stub = dir.join("ssh");
+    fs::write(&stub, script).unwrap();
+    fs::set_permissions(&stub, fs::Permissions::from_mode(0o755)).unwrap();
+    stub
+}
+
+#[test]
+fn test_version_info_over_ssh() {
+    let stub = ssh_stub();
+    let ssh = SshConfig::default()
+        .binary(&stub)
+        .option("-o")
+        .option("StrictHostKeyChecking=no");
+
+    let client =
+        DockerClient::new_ssh("ssh://ci@build-host:2222", ssh).unwrap();
+    let info = client.get_version_info().unwrap();
+    assert_eq!(info, "{\"ID\":\"ssh-stand-in\"}");
+
+    let args = fs::read_to_string(stub.with_file_name("args")).unwrap();
+    let args: Vec<&str> = args.lines().collect();
+    assert_eq!(
+        args,
+        vec![
+            "-o",
+            "StrictHostKeyChecking=no",
+            "-T",
+            "-l",
+            "ci",
+            "-p",
+            "2222",
+            "--",
+            "build-host",
+            "docker",
+            "system",
+            "dial-stdio",
+        ]
+    );
+
+    fs::remove_dir_all(stub.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn test_timeouts_over_ssh() {
+    let stub = write_ssh_stub(HUNG_SSH_STUB);
+    let ssh = SshConfig::default().binary(&stub);
+    let timeouts =
+        Timeouts::default().request(Some(Duration::from_millis(200)));
+    let client = DockerClient::new_ssh("ssh://build-host", ssh)
+        .unwrap()
+        .with_timeouts(timeouts);
+
+    // The ssh client is killed instead of blocking forever.
+    let start = Instant::now();
+    match client.send_request(Request::get("/info")) {
+        Err(DockerApiError::Timeout(endpoint)) => {
+            assert_eq!(endpoint, "/info")
+        }
+        other => panic!("Unexpected result : {:?}", other.map(|_| ())),
+    }
+    assert!(start.elapsed() < Duration::from_secs(5));
+
+    fs::remove_dir_all(stub.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn test_missing_ssh_binary() {
+    let ssh = SshConfig::default().binary("/does/not/exist/ssh");
+    match DockerClient::new_ssh("ssh://build-host", ssh) {
+        Err(DockerClientError::SshCommandError(cmd, _)) => {
+            assert_eq!(cmd, "/does/not/exist/ssh")
+        }
+        Err(err) => panic!("Unexpected error : {}", err),
+        Ok(_) => panic!("Missing ssh client went unnoticed"),
+    }
+}
+
+#[test]
+fn test_ssh_address() {
+    assert_eq!(
+        validate_ssh_address("ssh://build-host"),
+        Some((None, "build-host", None))
+    );
+    assert_eq!(
+        validate_ssh_address("ssh://ci@build-host:2222"),
+        Some((Some("ci"), "build-host", Some(2222)))
+    );
+    assert_eq!(
+        validate_ssh_address("ssh://ci@[::1]:2222"),
+        Some((Some("ci"), "::1", Some(2222)))
+    );
+    assert_eq!(validate_ssh_address("ssh://@build-host"), None);
+    assert_eq!(validate_ssh_address("ssh://build-host:port"), None);
+    assert_eq!(validate_ssh_address("tcp://build-host:2375"), None);
+
+    match DockerClient::new("ssh://") {
+        Err(DockerClientError::InvalidTargetAddress(_)) => {}
+        Err(err) => panic!("Unexpected error : {}", err),
+        Ok(_) => panic!("Invalid address was accepted"),
+    }
+}
@@ -0,0 +1,184 @@
+#![allow(non_snake_case)]
+extern crate rust_docker;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use rust_docker::api::request::{Method, Request};
+use rust_docker::api::DockerApiClient;
+use rust_docker::client::DockerClient;
+use rust_docker::errors::DockerApiError;
+use rust_docker::progress::{JsonStream, ProgressDetail, ProgressStream};
+
+use std::io::Write;
+use std::net::TcpStream;
+use std::sync::mpsc;
+use std::sync::Mutex;
+
+fn chunk(data: &str) -> String {
+    format!("{:x}\r\n{}\r\n", data.len(), data)
+}
+
+#[test]
+fn test_progress_as_it_arrives() {
+    let (tx, rx) = mpsc::channel::<()>();
+    let rx = Mutex::new(rx);
+    let addr = common::serve_many(move |mut stream: TcpStream| {
+        if common::read_request(&mut stream).is_empty() {
+            return;
+        }
+
+        let pulling = "{\"status\":\"Pulling from library/debian\",\"id\":\"jessie\"}\r\n";
+        let downloading = "{\"status\":\"Downloading\",\"progressDetail\":\
+                           {\"current\":1024,\"total\":4096},\"id\":\"c5e155d5a1d1\"}\r\n";
+        let (first, second) = downloading.split_at(20);
+        let head = "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+                    Transfer-Encoding: chunked\r\n\r\n";
+        let sent = format!("{}{}{}", head, chunk(pulling), chunk(first));
+        stream.write_all(sent.as_bytes()).unwrap();
+
+        // The rest is only sent once the first message was read.
+        rx.lock().unwrap().recv().unwrap();
+        let failed = "{\"errorDetail\":{\"message\":\"unexpected EOF\"},\
+                      \"error\":\"unexpected EOF\"}\r\n";
+        let sent = format!("{}{}0\r\n\r\n", chunk(second), chunk(failed));
+        stream.write_all(sent.as_bytes()).unwrap();
+    });
+
+    let client = DockerClient::new(addr).unwrap();
+    let request = Request::post("/images/create")
+        .query("fromImage", "debian")
+        .query("tag", "jessie");
+    let resp = client.send_request_streaming(request, 200).unwrap();
+    assert_eq!(resp.header("content-type"), Some("application/json"));
+
+    let mut progress = resp.progress();
+    let message = progress.next().unwrap().unwrap();
+    assert_eq!(message.status.unwrap(), "Pulling from library/debian");
+    assert_eq!(message.id.unwrap(), "jessie");
+    tx.send(()).unwrap();
+
+    // Split across chunks.
+    let message = progress.next().unwrap().unwrap();
+    assert_eq!(
+        message.progressDetail,
+        Some(ProgressDetail {
+            current: Some(1024),
+            total: Some(4096),
+        })
+    );
+
+    match progress.next() {
+        Some(Err(DockerApiError::StreamError(code, message))) => {
+            assert_eq!(code, None);
+            assert_eq!(message, "unexpected EOF");
+        }
+        other => panic!("Unexpected message : {:?}", other),
+    }
+    assert!(progress.next().is_none());
+}
+
+#[test]
+fn test_streaming_error_status() {
+    let addr = common::serve_many(|mut stream: TcpStream| {
+        if common::read_request(&mut stream).is_empty() {
+            return;
+        }
+
+        let body = "{\"message\":\"pull access denied for privy\"}";
+        let response = format!(
+            "HTTP/1.1 404 Not Found\r\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        stream.write_all(response.as_bytes()).unwrap();
+    });
+
+    let client = DockerClient::new(addr).unwrap();
+    let request = Request::post("/images/create").query("fromImage", "privy");
+    match client.send_request_streaming(request, 200) {
+        Err(DockerApiError::NotFound(method, endpoint, message)) => {
+            assert_eq!(method, Method::POST);
+            assert_eq!(endpoint, "/images/create");
+            assert_eq!(message, "pull access denied for privy");
+        }
+        Err(err) => panic!("Unexpected error : {}", err),
+        Ok(_) => panic!("The request succeeded"),
+    }
+}
+
+#[test]
+fn test_buffered_clients() {
+    /// A client which can't hand its connections over.
+    struct Canned;
+
+    impl DockerApiClient for Canned {
+        fn request(&self, _request: &[u8]) -> Option<Vec<u8>> {
+            let body = "{\"stream\":\"Step 1/2 : FROM debian\\n\"}\n\
+                        {\"aux\":{\"ID\":\"sha256:2bd0\"}}\n";
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            Some(response.into_bytes())
+        }
+    }
+
+    #[derive(Deserialize, Debug)]
+    struct Built {
+        ID: String,
+    }
+
+    let messages = Canned
+        .send_request_streaming(Request::post("/build"), 200)
+        .unwrap()
+        .progress()
+        .wait()
+        .unwrap();
+    assert_eq!(messages.len(), 2);
+    assert_eq!(
+        messages[0].stream.as_ref().unwrap(),
+        "Step 1/2 : FROM debian\n"
+    );
+    let built: Built = messages[1].aux_as().unwrap().unwrap();
+    assert_eq!(built.ID, "sha256:2bd0");
+}
+
+#[test]
+fn test_json_stream() {
+    #[derive(Deserialize, Debug)]
+    struct Event {
+        Type: String,
+        Action: String,
+    }
+
+    let events = "{\"Type\":\"container\",\"Action\":\"start\"}\n\
+                  \n\
+                  not json\n\
+                  {\"Type\":\"image\",\"Action\":\"pull\"}";
+    let mut stream: JsonStream<_, Event> = JsonStream::new(events.as_bytes());
+
+    let event = stream.next().unwrap().unwrap();
+    assert_eq!(event.Type, "container");
+    assert_eq!(event.Action, "start");
+
+    // Messages which can't be decoded don't end the stream.
+    match stream.next() {
+        Some(Err(DockerApiError::JsonDeserializationError(_))) => {}
+        other => panic!("Unexpected event : {:?}", other),
+    }
+    assert_eq!(stream.next().unwrap().unwrap().Action, "pull");
+    assert!(stream.next().is_none());
+
+    // Errors reported by the daemon end the progress.
+    let progress = "{\"status\":\"Loading layer\"}\n\
+                    {\"errorDetail\":{\"code\":1,\"message\":\"no space left\"}}\n";
+    match ProgressStream::new(progress.as_bytes()).wait() {
+        Err(DockerApiError::StreamError(Some(1), message)) => {
+            assert_eq!(message, "no space left")
+        }
+        other => panic!("Unexpected result : {:?}", other),
+    }
+}
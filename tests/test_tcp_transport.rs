@@ -0,0 +1,42 @@
+extern crate rust_docker;
+
+mod common;
+
+use rust_docker::api::images::Images;
+use rust_docker::api::version::Version;
+use rust_docker::client::DockerClient;
+
+#[test]
+fn test_version_info_over_tcp() {
+    let (addr, server) = common::serve_once("{\"ID\":\"stand-in\"}");
+
+    let client = DockerClient::new(addr).unwrap();
+    let info = client.get_version_info().unwrap();
+    assert_eq!(info, "{\"ID\":\"stand-in\"}");
+
+    let request = server.join().unwrap();
+    assert!(request.starts_with("GET /info HTTP/1.1\r\n"));
+}
+
+#[test]
+fn test_list_images_over_tcp() {
+    let (addr, server) = common::serve_once(
+        "[{\"Id\":\"sha256:e216a057b1cb\",\"ParentId\":\"\",\"RepoTags\":[\"debian:jessie\"],\
+         \"RepoDigests\":null,\"Created\":1527624862,\"Size\":127166010,\
+         \"VirtualSize\":127166010,\"SharedSize\":-1,\"Labels\":null,\"Containers\":-1}]",
+    );
+
+    let client = DockerClient::new(addr).unwrap();
+    let images = client.list_images(None).unwrap();
+    assert_eq!(images.len(), 1);
+    assert_eq!(images[0].RepoTags, vec!["debian:jessie".to_string()]);
+
+    server.join().unwrap();
+}
+
+#[test]
+fn test_invalid_tcp_address() {
+    assert!(DockerClient::new("tcp://127.0.0.1").is_err());
+    assert!(DockerClient::new("tcp://:2375").is_err());
+    assert!(DockerClient::new("tcp://127.0.0.1:notaport").is_err());
+}
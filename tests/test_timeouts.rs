@@ -0,0 +1,137 @@
+extern crate rust_docker;
+
+mod common;
+
+use rust_docker::api::version::Version;
+use rust_docker::api::DockerApiClient;
+use rust_docker::client::DockerClient;
+use rust_docker::errors::DockerApiError;
+use rust_docker::transport::Timeouts;
+
+use std::io::Write;
+use std::net::TcpStream;
+use std::thread;
+use std::time::{Duration, Instant};
+
+fn millis(ms: u64) -> Option<Duration> {
+    Some(Duration::from_millis(ms))
+}
+
+/// Stand-in which reads requests but never answers the first one.
+fn hung_daemon() -> String {
+    let (addr, _server) = common::serve_with(|mut stream: TcpStream| {
+        common::read_request(&mut stream);
+        thread::sleep(Duration::from_secs(5));
+    });
+    addr
+}
+
+#[test]
+fn test_read_timeout() {
+    let client = DockerClient::new(hung_daemon())
+        .unwrap()
+        .with_timeouts(Timeouts::default().read(millis(100)));
+
+    let start = Instant::now();
+    match client.get_response_from_api("/info", "GET", "") {
+        Err(DockerApiError::Timeout(endpoint)) => {
+            assert_eq!(endpoint, "/info")
+        }
+        other => panic!("Unexpected result : {:?}", other),
+    }
+    assert!(start.elapsed() < Duration::from_secs(2));
+}
+
+#[test]
+fn test_request_timeout_with_slow_body() {
+    // The daemon keeps sending the body, a byte at a time, so no single read
+    // times out but the request as a whole does.
+    let (addr, _server) = common::serve_with(|mut stream: TcpStream| {
+        common::read_request(&mut stream);
+        let head = "HTTP/1.1 200 OK\r\nContent-Length: 100\r\n\r\n";
+        stream.write_all(head.as_bytes()).unwrap();
+        for _ in 0..100 {
+            thread::sleep(Duration::from_millis(50));
+            if stream.write_all(b"x").is_err() {
+                return;
+            }
+        }
+    });
+
+    let timeouts = Timeouts::default().read(millis(500)).request(millis(300));
+    let client = DockerClient::new(addr).unwrap().with_timeouts(timeouts);
+
+    let start = Instant::now();
+    match client.get_response_from_api("/containers/json", "GET", "") {
+        Err(DockerApiError::Timeout(endpoint)) => {
+            assert_eq!(endpoint, "/containers/json")
+        }
+        other => panic!("Unexpected result : {:?}", other),
+    }
+    assert!(start.elapsed() < Duration::from_secs(2));
+}
+
+#[test]
+fn test_per_call_override() {
+    let (addr, _server) = common::serve_with(|mut stream: TcpStream| {
+        common::read_request(&mut stream);
+        thread::sleep(Duration::from_millis(300));
+        let response = common::json_response("{\"StatusCode\":0}");
+        stream.write_all(response.as_bytes()).unwrap();
+    });
+
+    let client = DockerClient::new(addr)
+        .unwrap()
+        .with_timeouts(Timeouts::default().request(millis(100)));
+
+    // Waiting on a container takes as long as the container runs.
+    let timeouts = client.timeouts().request(None);
+    let resp = client
+        .get_response_from_api_with_timeouts(
+            "/containers/f808ca/wait",
+            "POST",
+            "",
+            Some(timeouts),
+        )
+        .unwrap();
+    assert_eq!(resp.text().unwrap(), "{\"StatusCode\":0}");
+}
+
+#[test]
+fn test_timed_out_connection_is_not_reused() {
+    let (addr, server) = common::serve_with(|mut stream: TcpStream| {
+        common::read_request(&mut stream);
+        thread::sleep(Duration::from_millis(300));
+        // Answer late, this response must not be read by the next request.
+        let response = common::json_response("{\"late\":true}");
+        let _ = stream.write_all(response.as_bytes());
+    });
+
+    let client = DockerClient::new(addr)
+        .unwrap()
+        .with_timeouts(Timeouts::default().read(millis(100)));
+    assert!(client.get_version_info().is_err());
+    server.join().unwrap();
+
+    // The stand-in is gone, a new connection can't be opened.
+    match client.get_response_from_api("/info", "GET", "") {
+        Err(DockerApiError::ConnectionError(endpoint, _)) => {
+            assert_eq!(endpoint, "/info")
+        }
+        other => panic!("Unexpected result : {:?}", other),
+    }
+}
+
+#[test]
+fn test_no_timeout_by_default() {
+    let (addr, _server) = common::serve_with(|mut stream: TcpStream| {
+        common::read_request(&mut stream);
+        thread::sleep(Duration::from_millis(200));
+        let response = common::json_response("{\"ID\":\"stand-in\"}");
+        stream.write_all(response.as_bytes()).unwrap();
+    });
+
+    let client = DockerClient::new(addr).unwrap();
+    assert_eq!(client.timeouts(), Timeouts::default());
+    assert_eq!(client.get_version_info().unwrap(), "{\"ID\":\"stand-in\"}");
+}
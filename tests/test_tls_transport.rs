@@ -0,0 +1,134 @@
+extern crate openssl;
+extern crate rust_docker;
+
+mod common;
+
+use rust_docker::api::version::Version;
+use rust_docker::client::DockerClient;
+use rust_docker::errors::DockerClientError;
+use rust_docker::transport::TlsConfig;
+
+use openssl::ssl::{
+    SslAcceptor, SslFiletype, SslMethod, SslVerifyMode, SslVersion,
+};
+
+use std::io::Write;
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::thread;
+
+fn fixture(dir: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/tls")
+        .join(dir)
+}
+
+/// Starts a loopback stand-in for a TLS secured docker daemon, which requires
+/// client certificates signed by `client_ca` and answers a single request.
+/// The returned address uses `host` with the port the stand-in listens on.
+fn serve_tls_once(
+    host: &str,
+    client_ca: &str,
+    max_version: Option<SslVersion>,
+) -> String {
+    let server = fixture("server");
+    let mut acceptor =
+        SslAcceptor::mozilla_intermediate_v5(SslMethod::tls()).unwrap();
+    acceptor
+        .set_ca_file(fixture(client_ca).join("ca.pem"))
+        .unwrap();
+    acceptor
+        .set_certificate_chain_file(server.join("cert.pem"))
+        .unwrap();
+    acceptor
+        .set_private_key_file(server.join("key.pem"), SslFiletype::PEM)
+        .unwrap();
+    acceptor
+        .set_verify(SslVerifyMode::PEER | SslVerifyMode::FAIL_IF_NO_PEER_CERT);
+    acceptor.set_max_proto_version(max_version).unwrap();
+    let acceptor = acceptor.build();
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut stream = match acceptor.accept(stream) {
+            Ok(stream) => stream,
+            Err(_) => return,
+        };
+
+        common::read_request(&mut stream);
+        let response = common::json_response("{\"ID\":\"tls-stand-in\"}");
+        stream.write_all(response.as_bytes()).unwrap();
+    });
+
+    format!("tcp://{}:{}", host, port)
+}
+
+#[test]
+fn test_version_info_over_tls() {
+    let addr = serve_tls_once("localhost", "client", None);
+    let tls = TlsConfig::from_cert_path(fixture("client"));
+
+    let client = DockerClient::new_tls(addr, tls).unwrap();
+    let info = client.get_version_info().unwrap();
+    assert_eq!(info, "{\"ID\":\"tls-stand-in\"}");
+}
+
+#[test]
+fn test_hostname_verification_toggle() {
+    // The stand-in certificate is only valid for `localhost`.
+    let addr = serve_tls_once("127.0.0.1", "client", None);
+    let tls = TlsConfig::from_cert_path(fixture("client"));
+    match DockerClient::new_tls(addr, tls) {
+        Err(DockerClientError::TlsVerificationError(..)) => {}
+        Err(err) => panic!("Unexpected error : {}", err),
+        Ok(_) => panic!("Hostname mismatch was not detected"),
+    }
+
+    let addr = serve_tls_once("127.0.0.1", "client", None);
+    let tls =
+        TlsConfig::from_cert_path(fixture("client")).verify_hostname(false);
+    let client = DockerClient::new_tls(addr, tls).unwrap();
+    assert!(client.get_version_info().is_ok());
+}
+
+#[test]
+fn test_untrusted_daemon_certificate() {
+    let addr = serve_tls_once("localhost", "client", None);
+    let tls = TlsConfig::from_cert_path(fixture("untrusted"));
+
+    match DockerClient::new_tls(addr, tls) {
+        Err(DockerClientError::TlsVerificationError(..)) => {}
+        Err(err) => panic!("Unexpected error : {}", err),
+        Ok(_) => panic!("Untrusted certificate was accepted"),
+    }
+}
+
+#[test]
+fn test_client_certificate_rejected() {
+    // With TLS 1.2 the rejection of the client certificate is part of the
+    // handshake itself.
+    let addr =
+        serve_tls_once("localhost", "untrusted", Some(SslVersion::TLS1_2));
+    let tls = TlsConfig::from_cert_path(fixture("client"));
+
+    match DockerClient::new_tls(addr, tls) {
+        Err(DockerClientError::TlsHandshakeError(..)) => {}
+        Err(err) => panic!("Unexpected error : {}", err),
+        Ok(_) => panic!("Rejected client certificate went unnoticed"),
+    }
+}
+
+#[test]
+fn test_missing_tls_material() {
+    let addr = serve_tls_once("localhost", "client", None);
+    let tls = TlsConfig::from_cert_path(fixture("does-not-exist"));
+
+    match DockerClient::new_tls(addr, tls) {
+        Err(DockerClientError::TlsConfigError(_)) => {}
+        Err(err) => panic!("Unexpected error : {}", err),
+        Ok(_) => panic!("Missing TLS material was not reported"),
+    }
+}
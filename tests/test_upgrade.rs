@@ -0,0 +1,162 @@
+extern crate rust_docker;
+
+mod common;
+
+use rust_docker::api::request::{Method, Request};
+use rust_docker::api::DockerApiClient;
+use rust_docker::client::DockerClient;
+use rust_docker::errors::DockerApiError;
+
+use std::error::Error;
+use std::io;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::thread;
+
+/// Answers upgrade requests like the attach endpoint, echoing back the
+/// input until it ends. Requests are sent over `requests`.
+fn serve_attach(requests: mpsc::Sender<String>) -> String {
+    let requests = Mutex::new(requests);
+    common::serve_many(move |mut stream: TcpStream| {
+        let request = common::read_request(&mut stream);
+        if request.is_empty() {
+            return;
+        }
+        requests.lock().unwrap().send(request).unwrap();
+
+        stream
+            .write_all(
+                b"HTTP/1.1 101 UPGRADED\r\n\
+                  Content-Type: application/vnd.docker.raw-stream\r\n\
+                  Connection: Upgrade\r\n\
+                  Upgrade: tcp\r\n\
+                  \r\n\
+                  hello\n",
+            )
+            .unwrap();
+
+        let mut input = Vec::new();
+        stream.read_to_end(&mut input).unwrap();
+        stream.write_all(&input).unwrap();
+        stream.write_all(b"bye\n").unwrap();
+    })
+}
+
+#[test]
+fn test_upgrade_and_half_close() {
+    let (tx, rx) = mpsc::channel();
+    let client = DockerClient::new(serve_attach(tx))
+        .unwrap()
+        .with_api_version("1.40")
+        .unwrap();
+
+    let request = Request::post("/containers/f808ca/attach")
+        .query("stream", true)
+        .query("stdin", true);
+    let upgraded = client.send_upgrade_request(request).unwrap();
+    assert_eq!(upgraded.status_code(), 101);
+    assert_eq!(upgraded.header("upgrade"), Some("tcp"));
+
+    let request = rx.recv().unwrap();
+    assert!(request.starts_with(
+        "POST /v1.40/containers/f808ca/attach?stream=true&stdin=true HTTP/1.1\r\n"
+    ));
+    assert!(request.contains("\r\nConnection: Upgrade\r\n"));
+    assert!(request.contains("\r\nUpgrade: tcp\r\n"));
+
+    let (mut output, mut input) = upgraded.split().unwrap();
+    let reader = thread::spawn(move || {
+        let mut received = String::new();
+        output.read_to_string(&mut received).unwrap();
+        received
+    });
+
+    input.write_all(b"ping\n").unwrap();
+    input.close_write().unwrap();
+    // The output sent along with the response head isn't lost.
+    assert_eq!(reader.join().unwrap(), "hello\nping\nbye\n");
+}
+
+#[test]
+fn test_upgrade_without_split() {
+    let addr = common::serve_many(|mut stream: TcpStream| {
+        if common::read_request(&mut stream).is_empty() {
+            return;
+        }
+
+        // Older daemons hijack the connection without switching protocols.
+        stream
+            .write_all(
+                b"HTTP/1.1 200 OK\r\n\
+                  Content-Type: application/vnd.docker.raw-stream\r\n\r\n",
+            )
+            .unwrap();
+        let mut buf = [0; 5];
+        stream.read_exact(&mut buf).unwrap();
+        stream.write_all(&buf).unwrap();
+    });
+
+    let client = DockerClient::new(addr).unwrap();
+    let mut upgraded = client
+        .send_upgrade_request(Request::post("/exec/b7f3/start"))
+        .unwrap();
+    assert_eq!(upgraded.status_code(), 200);
+
+    upgraded.write_all(b"ls -l").unwrap();
+    let mut output = String::new();
+    upgraded.read_to_string(&mut output).unwrap();
+    assert_eq!(output, "ls -l");
+}
+
+#[test]
+fn test_upgrade_rejected() {
+    let addr = common::serve_many(|mut stream: TcpStream| {
+        if common::read_request(&mut stream).is_empty() {
+            return;
+        }
+
+        let body = "{\"message\":\"No such container: missing\"}";
+        let response = format!(
+            "HTTP/1.1 404 Not Found\r\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        stream.write_all(response.as_bytes()).unwrap();
+    });
+
+    let client = DockerClient::new(addr).unwrap();
+    let request = Request::post("/containers/missing/attach");
+    match client.send_upgrade_request(request) {
+        Err(DockerApiError::NotFound(method, endpoint, message)) => {
+            assert_eq!(method, Method::POST);
+            assert_eq!(endpoint, "/containers/missing/attach");
+            assert_eq!(message, "No such container: missing");
+        }
+        Err(err) => panic!("Unexpected error : {}", err),
+        Ok(_) => panic!("The connection was upgraded"),
+    }
+}
+
+#[test]
+fn test_upgrade_unsupported() {
+    struct Offline;
+
+    impl DockerApiClient for Offline {
+        fn request(&self, _request: &[u8]) -> Option<Vec<u8>> {
+            None
+        }
+    }
+
+    match Offline.send_upgrade_request(Request::post("/containers/web/attach"))
+    {
+        Err(ref err @ DockerApiError::ConnectionError(..)) => {
+            let cause = err.source().unwrap();
+            let cause = cause.downcast_ref::<io::Error>().unwrap();
+            assert_eq!(cause.kind(), io::ErrorKind::Unsupported);
+        }
+        Err(err) => panic!("Unexpected error : {}", err),
+        Ok(_) => panic!("The connection was upgraded"),
+    }
+}